@@ -1,8 +1,15 @@
 use std::collections::BTreeMap;
 
 use ravel_web::{
-    attr::*, collections::btree_map, el::*, event::*, format_text,
-    run::spawn_body, text::text, View,
+    attr::*,
+    collections::btree_map,
+    el::*,
+    event::*,
+    form::{checkbox_checked, input_value},
+    format_text, plural,
+    run::spawn_body,
+    text::text,
+    View,
 };
 use web_sys::wasm_bindgen::{JsCast as _, UnwrapThrowExt};
 
@@ -10,6 +17,7 @@ use web_sys::wasm_bindgen::{JsCast as _, UnwrapThrowExt};
 struct Model {
     filter: Filter,
     items: BTreeMap<usize, Item>,
+    new_todo: String,
 }
 
 #[derive(PartialEq, Eq, Copy, Clone, Hash, Default, Debug)]
@@ -77,14 +85,13 @@ fn item(filter: Filter, id: usize, item: &Item) -> View!(Model, '_) {
                 input((
                     Type("checkbox"),
                     Class("toggle"),
-                    // TODO: avoid circular dependency
-                    Checked(item.checked),
-                    on(InputEvent, move |model: &mut Model, e| {
-                        let input: web_sys::HtmlInputElement =
-                            e.target().unwrap_throw().dyn_into().unwrap_throw();
-                        model.items.get_mut(&id).unwrap_throw().checked =
-                            input.checked();
-                    }),
+                    checkbox_checked(
+                        item.checked,
+                        move |model: &mut Model, checked| {
+                            model.items.get_mut(&id).unwrap_throw().checked =
+                                checked;
+                        },
+                    ),
                 )),
                 label((
                     text(&item.text),
@@ -100,7 +107,11 @@ fn item(filter: Filter, id: usize, item: &Item) -> View!(Model, '_) {
                 )),
             )),
             form((
-                input((Class("edit"), Value(CloneString(&item.text)))),
+                input((
+                    Class("edit"),
+                    Value(CloneString(&item.text)),
+                    focus_when(item.editing),
+                )),
                 on(Active(Submit), move |model: &mut Model, e| {
                     e.prevent_default();
 
@@ -133,21 +144,16 @@ fn todomvc(model: &Model) -> View!(Model, '_) {
                         Class("new-todo"),
                         Placeholder("What needs to be done?"),
                         Autofocus(true),
+                        input_value(
+                            &model.new_todo,
+                            |model: &mut Model, text| model.new_todo = text,
+                        ),
                     )),
                     on(Active(Submit), move |model: &mut Model, e| {
                         e.prevent_default();
 
-                        let form: web_sys::HtmlFormElement =
-                            e.target().unwrap_throw().dyn_into().unwrap_throw();
-                        let input: web_sys::HtmlInputElement = form
-                            .elements()
-                            .get_with_index(0)
-                            .unwrap_throw()
-                            .dyn_into()
-                            .unwrap_throw();
-
-                        model.add(input.value());
-                        input.set_value(""); // TODO: clear input with framework
+                        let text = std::mem::take(&mut model.new_todo);
+                        model.add(text);
                     }),
                 )),
             )),
@@ -170,21 +176,16 @@ fn todomvc(model: &Model) -> View!(Model, '_) {
                 Class("footer"),
                 span((
                     Class("todo-count"),
-                    strong(format_text!(
-                        "{} {} left",
-                        model.count(),
-                        match model.count() {
-                            1 => "item",
-                            _ => "items",
-                        }
+                    strong(plural!(
+                        n = model.count(),
+                        one: "{n} item left",
+                        other: "{n} items left",
                     )),
                 )),
                 ul((
                     Class("filters"),
-                    // TODO: array impls
-                    Filter::All.button(model.filter),
-                    Filter::Active.button(model.filter),
-                    Filter::Completed.button(model.filter),
+                    [Filter::All, Filter::Active, Filter::Completed]
+                        .map(|filter| filter.button(model.filter)),
                 )),
                 button((
                     Class("clear-completed"),
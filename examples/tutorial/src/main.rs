@@ -106,7 +106,8 @@ fn events() -> View!(Model) {
         el::p((
             "Message: ",
             // [`on`], unlike [`on_`], also gives us access to the underlying
-            // [`web_sys::Event`].
+            // event, typed according to its kind (here a
+            // [`web_sys::InputEvent`]).
             el::input(on(event::InputEvent, |model: &mut Model, event| {
                 model.message = event
                     .target()
@@ -175,7 +176,7 @@ fn dynamic_view(model: &Model) -> View!(Model, '_) {
         // * Branch according to our chosen logic.
         // * In each branch, return `cx.build(any(...))` for our chosen view.
         el::p(with(|cx| {
-            if model.count % 2 == 0 {
+            if model.count.is_multiple_of(2) {
                 cx.build(any(el::b("Even!")))
             } else {
                 cx.build(any("Odd."))
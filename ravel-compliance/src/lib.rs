@@ -0,0 +1,506 @@
+//! A compliance test suite for [`ravel`] backends.
+//!
+//! To check a backend, implement [`Backend`] for a fixture which can build and
+//! rebuild components at some root (for example, a detached element), then
+//! call [`check`] from a test:
+//!
+//! ```ignore
+//! #[test]
+//! fn compliance() {
+//!     ravel_compliance::check(&mut MyBackend::new());
+//! }
+//! ```
+//!
+//! The checks only use [`Probe`] components, which are generic over every
+//! [`CxRep`], so they exercise the core combinators with the backend's own
+//! contexts. They panic with a description of the first violation.
+
+use std::{
+    cell::{Cell, RefCell},
+    rc::Rc,
+};
+
+use ravel::{
+    adapt, adapt_ref, lens, memo,
+    mvu::{self, Messages},
+    provide_context, record_history, tracked, use_context, with, with_local,
+    with_local_keyed, with_local_mut, with_stored, Builder, CxRep, History,
+    Revision, State, Thunk,
+};
+
+/// A root at which a backend can build and rebuild components.
+pub trait Backend {
+    /// The backend being checked.
+    type R: CxRep;
+
+    /// Builds `builder` at the root.
+    fn build<B: Builder<Self::R>>(&mut self, builder: B) -> B::State;
+
+    /// Rebuilds `builder` at the root, where `state` was previously built.
+    fn rebuild<B: Builder<Self::R>>(
+        &mut self,
+        builder: B,
+        state: &mut B::State,
+    );
+}
+
+/// Something which happened to a [`Probe`].
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+pub enum Event {
+    /// The probe was built.
+    Build(&'static str),
+    /// The probe was rebuilt.
+    Rebuild(&'static str),
+    /// The probe's state was run.
+    Run(&'static str),
+}
+
+/// A record of [`Event`]s, shared between [`Probe`]s.
+#[derive(Clone, Default, Debug)]
+pub struct Log(Rc<RefCell<Vec<Event>>>);
+
+impl Log {
+    /// A probe which records to this log, identified by `name`.
+    pub fn probe(&self, name: &'static str) -> Probe {
+        Probe {
+            name,
+            log: self.clone(),
+        }
+    }
+
+    /// Removes and returns the events recorded so far.
+    pub fn take(&self) -> Vec<Event> {
+        std::mem::take(&mut self.0.borrow_mut())
+    }
+
+    fn push(&self, event: Event) {
+        self.0.borrow_mut().push(event)
+    }
+}
+
+/// A component for any backend, which records what happens to it in a [`Log`].
+pub struct Probe {
+    name: &'static str,
+    log: Log,
+}
+
+impl<R: CxRep> Builder<R> for Probe {
+    type State = ProbeState;
+
+    fn build(self, _: R::BuildCx<'_>) -> Self::State {
+        self.log.push(Event::Build(self.name));
+        ProbeState {
+            name: self.name,
+            log: self.log,
+        }
+    }
+
+    fn rebuild(self, _: R::RebuildCx<'_>, state: &mut Self::State) {
+        self.log.push(Event::Rebuild(self.name));
+        state.name = self.name;
+    }
+}
+
+/// The state of a [`Probe`].
+pub struct ProbeState {
+    name: &'static str,
+    log: Log,
+}
+
+impl<Output> State<Output> for ProbeState {
+    fn run(&mut self, _: &mut Output) {
+        self.log.push(Event::Run(self.name))
+    }
+}
+
+/// Runs every check against `backend`.
+pub fn check<Bk: Backend>(backend: &mut Bk) {
+    tuples_in_order(backend);
+    arrays_in_order(backend);
+    with_builds_inner(backend);
+    with_local_keeps_value(backend);
+    with_local_mut_keeps_changes(backend);
+    with_local_keyed_resets(backend);
+    with_stored_keeps_callback(backend);
+    memo_skips_unchanged(backend);
+    tracked_skips_unchanged(backend);
+    adapt_runs_inner(backend);
+    record_history_commits(backend);
+    lens_focuses_part(backend);
+    mvu_applies_messages(backend);
+    context_reaches_descendants(backend);
+}
+
+/// Tuples, including nested ones, build, rebuild and run their elements in
+/// order.
+pub fn tuples_in_order<Bk: Backend>(backend: &mut Bk) {
+    use Event::*;
+
+    let log = Log::default();
+    let view = || (log.probe("a"), (log.probe("b"), log.probe("c")));
+
+    let mut state = backend.build(view());
+    assert_eq!(log.take(), [Build("a"), Build("b"), Build("c")]);
+
+    backend.rebuild(view(), &mut state);
+    assert_eq!(log.take(), [Rebuild("a"), Rebuild("b"), Rebuild("c")]);
+
+    state.run(&mut ());
+    assert_eq!(log.take(), [Run("a"), Run("b"), Run("c")]);
+}
+
+/// Arrays build, rebuild and run their elements in order.
+pub fn arrays_in_order<Bk: Backend>(backend: &mut Bk) {
+    use Event::*;
+
+    let log = Log::default();
+    let view = || [log.probe("a"), log.probe("b")];
+
+    let mut state = backend.build(view());
+    assert_eq!(log.take(), [Build("a"), Build("b")]);
+
+    backend.rebuild(view(), &mut state);
+    assert_eq!(log.take(), [Rebuild("a"), Rebuild("b")]);
+
+    state.run(&mut ());
+    assert_eq!(log.take(), [Run("a"), Run("b")]);
+}
+
+/// [`with`] builds and rebuilds the component passed to [`ravel::Cx::build`].
+pub fn with_builds_inner<Bk: Backend>(backend: &mut Bk) {
+    use Event::*;
+
+    let log = Log::default();
+
+    let mut state = backend.build(with(|cx| cx.build(log.probe("a"))));
+    assert_eq!(log.take(), [Build("a")]);
+
+    backend.rebuild(with(|cx| cx.build(log.probe("a"))), &mut state);
+    assert_eq!(log.take(), [Rebuild("a")]);
+
+    state.run(&mut ());
+    assert_eq!(log.take(), [Run("a")]);
+}
+
+/// [`with_local`] only initializes its value on build, and keeps it across
+/// rebuilds.
+pub fn with_local_keeps_value<Bk: Backend>(backend: &mut Bk) {
+    use Event::*;
+
+    let log = Log::default();
+    let view = |init: u32| {
+        with_local(
+            move || init,
+            |cx, value: &u32| {
+                assert_eq!(*value, 1, "local value was reinitialized");
+                cx.build(log.probe("a"))
+            },
+        )
+    };
+
+    let mut state = backend.build(view(1));
+    assert_eq!(log.take(), [Build("a")]);
+
+    backend.rebuild(view(2), &mut state);
+    assert_eq!(log.take(), [Rebuild("a")]);
+
+    state.run(&mut ());
+    assert_eq!(log.take(), [Run("a")]);
+}
+
+/// Changes made by [`with_local_mut`] while building are kept for the next
+/// rebuild.
+pub fn with_local_mut_keeps_changes<Bk: Backend>(backend: &mut Bk) {
+    use Event::*;
+
+    let log = &Log::default();
+    let view = |expected: u32| {
+        with_local_mut(
+            || 0,
+            move |cx, builds: &mut u32| {
+                assert_eq!(*builds, expected, "local change was lost");
+                *builds += 1;
+                cx.build(log.probe("a"))
+            },
+        )
+    };
+
+    let mut state = backend.build(view(0));
+    assert_eq!(log.take(), [Build("a")]);
+
+    backend.rebuild(view(1), &mut state);
+    assert_eq!(log.take(), [Rebuild("a")]);
+
+    backend.rebuild(view(2), &mut state);
+    assert_eq!(log.take(), [Rebuild("a")]);
+}
+
+/// [`with_local_keyed`] reinitializes its value when its key changes, and
+/// only then.
+pub fn with_local_keyed_resets<Bk: Backend>(backend: &mut Bk) {
+    use Event::*;
+
+    let log = &Log::default();
+    let view = |key: &'static str, init: u32, expected: u32| {
+        with_local_keyed(
+            key,
+            move || init,
+            move |cx, value: &u32| {
+                assert_eq!(*value, expected, "local value has the wrong key");
+                cx.build(log.probe("a"))
+            },
+        )
+    };
+
+    let mut state = backend.build(view("x", 1, 1));
+    assert_eq!(log.take(), [Build("a")]);
+
+    backend.rebuild(view("x", 2, 1), &mut state);
+    assert_eq!(log.take(), [Rebuild("a")]);
+
+    backend.rebuild(view("y", 3, 3), &mut state);
+    assert_eq!(log.take(), [Rebuild("a")]);
+}
+
+/// [`with_stored`] only creates its callback on build, and calls it with the
+/// latest input on every rebuild.
+pub fn with_stored_keeps_callback<Bk: Backend>(backend: &mut Bk) {
+    use Event::*;
+
+    let log = Log::default();
+    let inits = Rc::new(Cell::new(0));
+    let view = |input: &'static str| {
+        let log = log.clone();
+        let inits = inits.clone();
+        with_stored(
+            move || {
+                inits.set(inits.get() + 1);
+                move |cx: ravel::Cx<_, _>, input| cx.build(log.probe(input))
+            },
+            input,
+        )
+    };
+
+    let mut state = backend.build(view("a"));
+    assert_eq!(log.take(), [Build("a")]);
+
+    backend.rebuild(view("b"), &mut state);
+    assert_eq!(log.take(), [Rebuild("b")]);
+    assert_eq!(inits.get(), 1, "stored callback was recreated");
+}
+
+/// [`memo`] only rebuilds when its value changes.
+pub fn memo_skips_unchanged<Bk: Backend>(backend: &mut Bk) {
+    use Event::*;
+
+    let log = Log::default();
+    let view = |value: u32| memo(value, |cx, _| cx.build(log.probe("a")));
+
+    let mut state = backend.build(view(1));
+    assert_eq!(log.take(), [Build("a")]);
+
+    backend.rebuild(view(1), &mut state);
+    assert_eq!(log.take(), [], "memo rebuilt with an unchanged value");
+
+    backend.rebuild(view(2), &mut state);
+    assert_eq!(log.take(), [Rebuild("a")]);
+}
+
+/// [`tracked`] only rebuilds when its revision has been bumped.
+pub fn tracked_skips_unchanged<Bk: Backend>(backend: &mut Bk) {
+    use Event::*;
+
+    let log = Log::default();
+    let revision = Revision::new();
+    let view = || tracked(&revision, |cx| cx.build(log.probe("a")));
+
+    let mut state = backend.build(view());
+    assert_eq!(log.take(), [Build("a")]);
+
+    backend.rebuild(view(), &mut state);
+    assert_eq!(log.take(), [], "tracked rebuilt with an unchanged revision");
+
+    revision.bump();
+    backend.rebuild(view(), &mut state);
+    assert_eq!(log.take(), [Rebuild("a")]);
+}
+
+/// [`adapt_ref`] runs the inner state with the adapted output.
+pub fn adapt_runs_inner<Bk: Backend>(backend: &mut Bk) {
+    use Event::*;
+
+    let log = Log::default();
+    let view =
+        || adapt_ref(log.probe("a"), |(output, _): &mut ((), u32)| output);
+
+    let mut state = backend.build(view());
+    assert_eq!(log.take(), [Build("a")]);
+
+    backend.rebuild(view(), &mut state);
+    assert_eq!(log.take(), [Rebuild("a")]);
+
+    state.run(&mut ((), 0));
+    assert_eq!(log.take(), [Run("a")]);
+}
+
+/// [`record_history`] commits the changes made while running its component,
+/// as one step which can be undone.
+pub fn record_history_commits<Bk: Backend>(backend: &mut Bk) {
+    use Event::*;
+
+    let log = Log::default();
+    let view = || {
+        let edit = |thunk: Thunk<_>, history: &mut History<u32>| {
+            **history += 1;
+            thunk.run(history)
+        };
+        record_history((
+            adapt(log.probe("a"), edit),
+            adapt(log.probe("b"), edit),
+        ))
+    };
+
+    let mut state = backend.build(view());
+    assert_eq!(log.take(), [Build("a"), Build("b")]);
+
+    backend.rebuild(view(), &mut state);
+    assert_eq!(log.take(), [Rebuild("a"), Rebuild("b")]);
+
+    let mut history = History::new(0);
+    state.run(&mut history);
+    assert_eq!(log.take(), [Run("a"), Run("b")]);
+    assert_eq!(*history, 2);
+
+    assert!(history.undo(), "the run wasn't committed");
+    assert_eq!(*history, 0, "the run wasn't committed as one step");
+    assert!(history.redo());
+    assert_eq!(*history, 2);
+}
+
+/// [`lens`](fn@lens) builds with the selected part of the model, and runs its
+/// component with the same part of the output.
+pub fn lens_focuses_part<Bk: Backend>(backend: &mut Bk) {
+    use Event::*;
+
+    let log = Log::default();
+    let view = |model: &((), u32)| {
+        lens(model, ravel::lens!(((), u32), 1), |part: &u32| {
+            assert_eq!(*part, 7, "lens selected the wrong part");
+            log.probe("a")
+        })
+    };
+
+    let mut state = backend.build(view(&((), 7)));
+    assert_eq!(log.take(), [Build("a")]);
+
+    backend.rebuild(view(&((), 7)), &mut state);
+    assert_eq!(log.take(), [Rebuild("a")]);
+
+    state.run(&mut ((), 7));
+    assert_eq!(log.take(), [Run("a")]);
+}
+
+/// [`mvu::dispatch`] applies the messages sent by its component, including
+/// those converted by [`mvu::map`], in the order they were sent.
+pub fn mvu_applies_messages<Bk: Backend>(backend: &mut Bk) {
+    use Event::*;
+
+    let log = Log::default();
+    let view = || {
+        let child =
+            adapt(log.probe("a"), |thunk, messages: &mut Messages<u32>| {
+                messages.send(2);
+                thunk.run(messages)
+            });
+        let parent = (
+            adapt(log.probe("b"), |thunk, messages: &mut Messages<u32>| {
+                messages.send(1);
+                thunk.run(messages)
+            }),
+            mvu::map(child, |msg: u32| msg * 10),
+        );
+        mvu::dispatch(parent, |model: &mut Vec<u32>, msg| model.push(msg))
+    };
+
+    let mut state = backend.build(view());
+    assert_eq!(log.take(), [Build("b"), Build("a")]);
+
+    backend.rebuild(view(), &mut state);
+    assert_eq!(log.take(), [Rebuild("b"), Rebuild("a")]);
+
+    let mut model = Vec::new();
+    state.run(&mut model);
+    assert_eq!(log.take(), [Run("b"), Run("a")]);
+    assert_eq!(model, [1, 20], "messages were applied out of order");
+}
+
+/// [`provide_context`] makes its latest value available to [`use_context`] in
+/// its body, but not outside it.
+pub fn context_reaches_descendants<Bk: Backend>(backend: &mut Bk) {
+    use Event::*;
+
+    let log = Log::default();
+    let view = |value: u32| {
+        let inside = log.probe("inside");
+        let outside = log.probe("outside");
+
+        (
+            provide_context(
+                value,
+                with(move |cx| {
+                    let context = use_context::<u32, _, _>(&cx);
+                    assert_eq!(context.as_deref(), Some(&value));
+                    cx.build(inside)
+                }),
+            ),
+            with(move |cx| {
+                let context = use_context::<u32, _, _>(&cx);
+                assert_eq!(context, None, "context leaked out of its body");
+                cx.build(outside)
+            }),
+        )
+    };
+
+    let mut state = backend.build(view(1));
+    assert_eq!(log.take(), [Build("inside"), Build("outside")]);
+
+    backend.rebuild(view(2), &mut state);
+    assert_eq!(log.take(), [Rebuild("inside"), Rebuild("outside")]);
+}
+
+#[cfg(test)]
+mod tests {
+    use ravel::{Builder, CxRep};
+
+    use super::Backend;
+
+    /// A backend without any platform objects, which checks that the core
+    /// combinators themselves follow the protocol.
+    struct Null;
+
+    impl CxRep for Null {
+        type BuildCx<'a> = ();
+        type RebuildCx<'a> = ();
+    }
+
+    impl Backend for Null {
+        type R = Null;
+
+        fn build<B: Builder<Null>>(&mut self, builder: B) -> B::State {
+            builder.build(())
+        }
+
+        fn rebuild<B: Builder<Null>>(
+            &mut self,
+            builder: B,
+            state: &mut B::State,
+        ) {
+            builder.rebuild((), state)
+        }
+    }
+
+    #[test]
+    fn null_backend() {
+        super::check(&mut Null);
+    }
+}
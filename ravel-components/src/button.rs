@@ -0,0 +1,27 @@
+use ravel_web::{
+    attr::{Class, Type},
+    el,
+    event::{on_, Click},
+};
+
+use crate::{themed, Html};
+
+/// A button with the given `label`, which calls `action` when clicked.
+pub fn button<Label, Action, Output>(
+    label: Label,
+    action: Action,
+) -> Component!(Output)
+where
+    Label: Html<Output>,
+    Action: 'static + FnMut(&mut Output),
+    Output: 'static,
+{
+    themed(move |theme| {
+        el::button((
+            Class(theme.button),
+            Type("button"),
+            on_(Click, action),
+            label,
+        ))
+    })
+}
@@ -0,0 +1,53 @@
+use ravel_web::{
+    attr::{AriaLabel, AriaModal, Class, Role, Type},
+    el,
+    event::{on_, on_window, Click, KeyDown},
+    text::text,
+};
+
+use crate::{themed, Html};
+
+/// A modal dialog with a `title` and `body`, shown while `open` is `true`.
+///
+/// `on_close` is called when the close button is clicked or `Escape` is
+/// pressed. It should usually set `open` to `false`.
+pub fn dialog<'a, Body, OnClose, Output>(
+    open: bool,
+    title: &'a str,
+    body: Body,
+    on_close: OnClose,
+) -> Component!(Output, 'a)
+where
+    Body: Html<Output>,
+    OnClose: 'static + Clone + Fn(&mut Output),
+    Output: 'static,
+{
+    themed(move |theme| {
+        open.then(move || {
+            let escape = on_close.clone();
+
+            el::div((
+                Class(theme.dialog_backdrop),
+                el::div((
+                    Class(theme.dialog),
+                    Role("dialog"),
+                    AriaModal(true),
+                    on_window(KeyDown, move |output: &mut Output, e| {
+                        if e.key() == "Escape" {
+                            escape(output)
+                        }
+                    }),
+                    el::h2((Class(theme.dialog_title), text(title))),
+                    el::button((
+                        Class(theme.dialog_close),
+                        Type("button"),
+                        AriaLabel("Close"),
+                        on_(Click, move |output: &mut Output| on_close(output)),
+                        "×",
+                    )),
+                    body,
+                )),
+            ))
+        })
+    })
+}
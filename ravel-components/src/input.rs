@@ -0,0 +1,47 @@
+use ravel_web::{
+    attr::{Class, Type},
+    el,
+    form::{checkbox_checked, input_value},
+};
+
+use crate::{themed, Html};
+
+/// A single line text field showing `value`, which calls `action` with the new
+/// value on every edit.
+pub fn text_input<'a, Action, Output>(
+    value: &'a str,
+    action: Action,
+) -> Component!(Output, 'a)
+where
+    Action: 'static + FnMut(&mut Output, String),
+    Output: 'static,
+{
+    themed(move |theme| {
+        el::input((
+            Class(theme.input),
+            Type("text"),
+            input_value(value, action),
+        ))
+    })
+}
+
+/// A checkbox with the given `label`, which calls `action` with the new state
+/// when toggled.
+pub fn checkbox<Label, Action, Output>(
+    label: Label,
+    checked: bool,
+    action: Action,
+) -> Component!(Output)
+where
+    Label: Html<Output>,
+    Action: 'static + FnMut(&mut Output, bool),
+    Output: 'static,
+{
+    themed(move |theme| {
+        el::label((
+            Class(theme.checkbox),
+            el::input((Type("checkbox"), checkbox_checked(checked, action))),
+            label,
+        ))
+    })
+}
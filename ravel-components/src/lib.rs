@@ -0,0 +1,181 @@
+//! Reusable components for [`ravel_web`], such as buttons, inputs, dialogs and
+//! lists.
+//!
+//! Components are generic over the `Output` (model) type, and report changes
+//! through callbacks, so they can be used in any application. They build for
+//! both the DOM ([`Web`]) and server-side rendering ([`Ssr`]), so their
+//! contents must too (see [`Html`]). Their appearance is controlled by a
+//! [`Theme`].
+
+use std::rc::Rc;
+
+use ravel::{
+    provide_context, use_context, with, Builder, Cx, CxRep, MemSize,
+    ProvideContext, State,
+};
+use ravel_web::{ssr::Ssr, Web};
+
+/// The type of a component, which is a [`trait@ravel_web::View`] that also
+/// renders on the server.
+macro_rules! Component {
+    ($output:ty $(, $a:lifetime)*) => {
+        impl ravel_web::View<
+            ViewState = impl ravel_web::ViewMarker + ravel::State<$output>
+        > + ravel::Builder<ravel_web::ssr::Ssr>
+            $(+ ravel_web::Captures<$a>)*
+    };
+}
+
+mod button;
+mod dialog;
+mod input;
+mod list;
+
+pub use button::*;
+pub use dialog::*;
+pub use input::*;
+pub use list::*;
+
+/// Contents which can be used in a component, such as a label or a body.
+///
+/// This is implemented for every [`Builder`] which supports the same backends
+/// as the components, like the [`ravel_web::el`] and [`ravel_web::attr`]
+/// builders, text and event handlers.
+pub trait Html<Output>:
+    Builder<Web, State: State<Output>> + Builder<Ssr>
+{
+}
+
+impl<T, Output> Html<Output> for T where
+    T: Builder<Web, State: State<Output>> + Builder<Ssr>
+{
+}
+
+/// The CSS classes given to each component.
+///
+/// To customize them, wrap the components in [`provide_theme`]. Otherwise,
+/// [`Theme::default`] is used.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Theme {
+    /// A [`button`].
+    pub button: &'static str,
+    /// A [`text_input`].
+    pub input: &'static str,
+    /// The `<label>` around a [`checkbox`].
+    pub checkbox: &'static str,
+    /// The overlay behind a [`dialog`].
+    pub dialog_backdrop: &'static str,
+    /// A [`dialog`].
+    pub dialog: &'static str,
+    /// The title of a [`dialog`].
+    pub dialog_title: &'static str,
+    /// The close button of a [`dialog`].
+    pub dialog_close: &'static str,
+    /// A [`list`].
+    pub list: &'static str,
+    /// An item of a [`list`].
+    pub list_item: &'static str,
+}
+
+impl Default for Theme {
+    fn default() -> Self {
+        Self {
+            button: "rv-button",
+            input: "rv-input",
+            checkbox: "rv-checkbox",
+            dialog_backdrop: "rv-dialog-backdrop",
+            dialog: "rv-dialog",
+            dialog_title: "rv-dialog-title",
+            dialog_close: "rv-dialog-close",
+            list: "rv-list",
+            list_item: "rv-list-item",
+        }
+    }
+}
+
+impl MemSize for Theme {
+    fn heap_size(&self) -> usize {
+        0
+    }
+}
+
+/// Makes `theme` available to every component in `body`.
+pub fn provide_theme<B>(theme: Theme, body: B) -> ProvideContext<Theme, B> {
+    provide_context(theme, body)
+}
+
+/// The theme given to the innermost enclosing [`provide_theme`], or
+/// [`Theme::default`] if there is none.
+pub fn use_theme<S, R: CxRep>(cx: &Cx<S, R>) -> Rc<Theme> {
+    use_context(cx).unwrap_or_default()
+}
+
+/// A [`Builder`] created from [`themed`].
+struct Themed<F> {
+    f: F,
+}
+
+impl<F> Themed<F> {
+    fn with<R: CxRep, B: Builder<R>>(self) -> impl Builder<R, State = B::State>
+    where
+        F: FnOnce(Rc<Theme>) -> B,
+    {
+        with(move |cx| {
+            let theme = use_theme(&cx);
+            cx.build((self.f)(theme))
+        })
+    }
+}
+
+impl<R: CxRep, F, B> Builder<R> for Themed<F>
+where
+    F: FnOnce(Rc<Theme>) -> B,
+    B: Builder<R>,
+{
+    type State = B::State;
+
+    fn build(self, cx: R::BuildCx<'_>) -> Self::State {
+        self.with().build(cx)
+    }
+
+    fn rebuild(self, cx: R::RebuildCx<'_>, state: &mut Self::State) {
+        self.with().rebuild(cx, state)
+    }
+}
+
+/// Builds the result of `f`, called with the current [`Theme`], on any
+/// backend.
+fn themed<F: FnOnce(Rc<Theme>) -> B, B>(f: F) -> Themed<F> {
+    Themed { f }
+}
+
+#[cfg(test)]
+mod tests {
+    use ravel_web::ssr::render_to_string;
+
+    use super::*;
+
+    #[test]
+    fn renders_with_provided_theme() {
+        let theme = Theme {
+            button: "primary",
+            ..Theme::default()
+        };
+
+        assert_eq!(
+            render_to_string(provide_theme(
+                theme,
+                button("Go", |_: &mut ()| {}),
+            )),
+            r#"<button class="primary" type="button">Go</button>"#,
+        );
+    }
+
+    #[test]
+    fn renders_with_default_theme() {
+        assert_eq!(
+            render_to_string(list::<_, _, _, ()>(["a", "b"], |item| item)),
+            r#"<ul class="rv-list"><!--|--><li class="rv-list-item">a</li><!--|--><li class="rv-list-item">b</li><!--|--></ul>"#,
+        );
+    }
+}
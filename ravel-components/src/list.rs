@@ -0,0 +1,28 @@
+use ravel_web::{attr::Class, el};
+
+use crate::{themed, Html};
+
+/// A list of `items`, each rendered by `render_item` inside an `<li>`.
+///
+/// Items are matched by index, like a [`Vec`] of views.
+pub fn list<I, RenderItem, Item, Output>(
+    items: I,
+    render_item: RenderItem,
+) -> Component!(Output)
+where
+    I: IntoIterator,
+    RenderItem: FnMut(I::Item) -> Item,
+    Item: Html<Output>,
+    Output: 'static,
+{
+    themed(move |theme| {
+        el::ul((
+            Class(theme.list),
+            items
+                .into_iter()
+                .map(render_item)
+                .map(|item| el::li((Class(theme.list_item), item)))
+                .collect::<Vec<_>>(),
+        ))
+    })
+}
@@ -6,6 +6,8 @@ use serde::Deserialize;
 struct Config {
     element: std::collections::HashMap<String, Element>,
     attribute: std::collections::HashMap<String, Attribute>,
+    property: std::collections::HashMap<String, Property>,
+    event: std::collections::HashMap<String, Event>,
 }
 
 #[derive(Deserialize)]
@@ -21,6 +23,18 @@ struct Attribute {
     value_wrapper: Option<String>,
 }
 
+#[derive(Deserialize)]
+struct Property {
+    type_name: Option<String>,
+    value_type: Option<String>,
+}
+
+#[derive(Deserialize)]
+struct Event {
+    type_name: Option<String>,
+    event_type: Option<String>,
+}
+
 impl Attribute {
     fn value_trait(&self) -> &str {
         assert!(self.value_type.is_none());
@@ -39,6 +53,9 @@ fn main() {
     gen_el_types(&config, &out_dir);
 
     gen_attr(&config, &out_dir);
+    gen_prop(&config, &out_dir);
+
+    gen_event(&config, &out_dir);
 }
 
 fn gen_el_types(config: &Config, out_dir: &std::path::Path) {
@@ -78,7 +95,7 @@ fn gen_el(config: &Config, out_dir: &std::path::Path) {
         writeln!(&mut src, "/// [`<{name}>`](https://developer.mozilla.org/en-US/docs/Web/HTML/Element/{name}) element.").unwrap();
         writeln!(
             &mut src,
-            "pub fn {name}<Body>(body: Body) -> types::{t}<Body> {{ types::{t}(body) }}"
+            "#[track_caller] pub fn {name}<Body>(body: Body) -> types::{t}<(Src, Body)> {{ types::{t}((Src::caller(), body)) }}"
         )
         .unwrap();
     }
@@ -141,6 +158,48 @@ fn gen_attr(config: &Config, out_dir: &std::path::Path) {
     std::fs::write(out_dir.join("gen_attr.rs"), src).unwrap();
 }
 
+fn gen_prop(config: &Config, out_dir: &std::path::Path) {
+    let mut src = String::new();
+
+    for (name, prop) in &config.property {
+        let t = prop.type_name.clone().unwrap_or(type_name(name));
+
+        writeln!(&mut src, "/// `{name}` property.").unwrap();
+        writeln!(&mut src, "#[derive(Copy, Clone)]").unwrap();
+
+        match &prop.value_type {
+            Some(value_type) => {
+                writeln!(&mut src, "pub struct {t}(pub {value_type});")
+                    .unwrap();
+                writeln!(&mut src, "make_prop_value_type!(\"{name}\", {t});")
+                    .unwrap();
+            }
+            None => {
+                writeln!(&mut src, "pub struct {t}<V: PropValue>(pub V);")
+                    .unwrap();
+                writeln!(&mut src, "make_prop_value_trait!(\"{name}\", {t});")
+                    .unwrap();
+            }
+        }
+    }
+
+    std::fs::write(out_dir.join("gen_prop.rs"), src).unwrap();
+}
+
+fn gen_event(config: &Config, out_dir: &std::path::Path) {
+    let mut src = String::new();
+
+    for (name, event) in &config.event {
+        let t = event.type_name.clone().unwrap_or(type_name(name));
+        let event_type = event.event_type.as_deref().unwrap_or("Event");
+
+        writeln!(&mut src, "make_event!({name}, {t}, web_sys::{event_type});")
+            .unwrap();
+    }
+
+    std::fs::write(out_dir.join("gen_event.rs"), src).unwrap();
+}
+
 fn type_name(s: &str) -> String {
     let mut cs = s.chars();
     let mut s = String::with_capacity(s.len());
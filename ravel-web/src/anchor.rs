@@ -0,0 +1,119 @@
+//! Explicit start and end markers, for building children out of order.
+
+use std::convert::Infallible;
+
+use ravel::{MemSize, State};
+
+use crate::{
+    dom::{clear, marker},
+    ssr::{Ssr, SsrCx},
+    BuildCx, Builder, RebuildCx, View, ViewMarker, Web,
+};
+
+/// A pair of markers delimiting a component, created by [`anchored`].
+pub struct Anchors {
+    start: web_sys::CharacterData,
+    end: web_sys::CharacterData,
+}
+
+impl Anchors {
+    /// The node before the component's content.
+    pub fn start(&self) -> &web_sys::Node {
+        &self.start
+    }
+
+    /// The node after the component's content.
+    pub fn end(&self) -> &web_sys::Node {
+        &self.end
+    }
+
+    /// Removes all nodes between the anchors.
+    pub fn clear(&self, parent: &web_sys::Element) {
+        clear(parent, &self.start, &self.end)
+    }
+}
+
+/// A [`Builder`] created from [`anchored`].
+pub struct Anchored<V> {
+    view: V,
+}
+
+/// Wraps `view` in [`Anchors`], which are exposed by [`AnchoredState::anchors`].
+///
+/// This allows custom [`Builder`]s to build other components immediately
+/// before or after `view`, even after it has been built, with
+/// [`RebuildCx::build_before`]. For example, a footer can be built first, and
+/// content inserted before its [`Anchors::start`] once it is available.
+pub fn anchored<V: View>(view: V) -> Anchored<V> {
+    Anchored { view }
+}
+
+impl<V: View> Builder<Web> for Anchored<V> {
+    type State = AnchoredState<V::State>;
+
+    fn build(self, cx: BuildCx) -> Self::State {
+        let start = marker("[");
+        let end = marker("]");
+
+        cx.position.insert(&start);
+        let state = self.view.build(cx);
+        cx.position.insert(&end);
+
+        AnchoredState {
+            anchors: Anchors { start, end },
+            state,
+        }
+    }
+
+    fn rebuild(self, cx: RebuildCx, state: &mut Self::State) {
+        self.view.rebuild(cx, &mut state.state)
+    }
+}
+
+impl<B: Builder<Ssr>> Builder<Ssr> for Anchored<B> {
+    type State = B::State;
+
+    fn build(self, cx: SsrCx) -> Self::State {
+        cx.comment("[");
+        let state = self.view.build(cx);
+        cx.comment("]");
+
+        state
+    }
+
+    fn rebuild(self, cx: Infallible, _: &mut Self::State) {
+        match cx {}
+    }
+}
+
+/// The state of an [`Anchored`].
+pub struct AnchoredState<S> {
+    anchors: Anchors,
+    state: S,
+}
+
+impl<S> AnchoredState<S> {
+    /// The markers around the component.
+    pub fn anchors(&self) -> &Anchors {
+        &self.anchors
+    }
+
+    /// The state of the wrapped component.
+    pub fn inner(&mut self) -> &mut S {
+        &mut self.state
+    }
+}
+
+impl<S: State<Output>, Output> State<Output> for AnchoredState<S> {
+    fn run(&mut self, output: &mut Output) {
+        self.state.run(output)
+    }
+}
+
+impl<S: MemSize> MemSize for AnchoredState<S> {
+    fn heap_size(&self) -> usize {
+        self.state.heap_size()
+    }
+}
+
+impl<S> ViewMarker for AnchoredState<S> {}
@@ -0,0 +1,292 @@
+//! Imperative animations, with the
+//! [Web Animations API](https://developer.mozilla.org/en-US/docs/Web/API/Web_Animations_API).
+//!
+//! Unlike a [`transition`](crate::transition), which is driven by CSS classes,
+//! [`animate`] starts an animation on an element directly, typically from an
+//! event handler. The returned [`Animation`] can be kept, for example in local
+//! state, to cancel or finish it later:
+//!
+//! ```ignore
+//! with_local(Animation::default, move |cx, shake| {
+//!     cx.build(el::button((
+//!         with_ref(button.clone(), ()),
+//!         event::on_(Click, move |(_, shake): &mut (_, Animation)| {
+//!             shake.cancel();
+//!             *shake = animate(
+//!                 &button,
+//!                 &[
+//!                     Keyframe::new().set("transform", "translateX(0)"),
+//!                     Keyframe::new().set("transform", "translateX(8px)"),
+//!                     Keyframe::new().set("transform", "translateX(0)"),
+//!                 ],
+//!                 &Options::new(ms(300)).iterations(2.),
+//!             );
+//!         }),
+//!     )))
+//! })
+//! ```
+//!
+//! Animations run on the compositor where possible, and don't rebuild any
+//! components, so they suit effects which would be too costly to drive
+//! through the model.
+
+use web_sys::wasm_bindgen::{JsCast as _, JsValue};
+
+use crate::{el::NodeRef, style::StyleValue, units::Duration};
+
+/// A keyframe of an animation: the values of some style properties at one
+/// point of it.
+#[derive(Clone, Default, Debug)]
+pub struct Keyframe {
+    offset: Option<f64>,
+    easing: Option<&'static str>,
+    properties: Vec<(&'static str, String)>,
+}
+
+impl Keyframe {
+    /// A keyframe without any properties, spaced evenly with its neighbors.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets the CSS property `name` (for example `background-color`) to
+    /// `value` at this keyframe.
+    pub fn set(mut self, name: &'static str, value: impl StyleValue) -> Self {
+        value.with_css(|css| {
+            if let Some(css) = css {
+                self.properties.push((name, css.to_owned()))
+            }
+        });
+        self
+    }
+
+    /// Places the keyframe at `offset`, between `0.` (the start of the
+    /// animation) and `1.` (its end), instead of spacing it evenly.
+    pub fn offset(self, offset: f64) -> Self {
+        Self {
+            offset: Some(offset),
+            ..self
+        }
+    }
+
+    /// The timing function used from this keyframe to the next, such as
+    /// `"ease-in"`.
+    pub fn easing(self, easing: &'static str) -> Self {
+        Self {
+            easing: Some(easing),
+            ..self
+        }
+    }
+
+    fn to_js(&self) -> js_sys::Object {
+        let object = js_sys::Object::new();
+        for (name, value) in &self.properties {
+            set(&object, &property_key(name), &JsValue::from_str(value));
+        }
+        if let Some(offset) = self.offset {
+            set(&object, "offset", &offset.into());
+        }
+        if let Some(easing) = self.easing {
+            set(&object, "easing", &easing.into());
+        }
+        object
+    }
+}
+
+/// How the styles of an animation apply outside of its active period.
+#[derive(Copy, Clone, PartialEq, Eq, Hash, Debug, Default)]
+pub enum Fill {
+    /// The styles only apply while the animation is running.
+    #[default]
+    None,
+    /// The last keyframe's styles are kept once the animation has finished.
+    Forwards,
+    /// The first keyframe's styles apply during the delay.
+    Backwards,
+    /// Both [`Fill::Forwards`] and [`Fill::Backwards`].
+    Both,
+}
+
+/// The timing of an animation.
+#[derive(Copy, Clone, PartialEq, Debug)]
+pub struct Options {
+    duration: Duration,
+    delay: Duration,
+    iterations: f64,
+    easing: &'static str,
+    fill: Fill,
+    alternate: bool,
+}
+
+impl Options {
+    /// An animation which runs once for `duration`, without delay.
+    pub fn new(duration: Duration) -> Self {
+        Self {
+            duration,
+            delay: Duration::default(),
+            iterations: 1.,
+            easing: "linear",
+            fill: Fill::None,
+            alternate: false,
+        }
+    }
+
+    /// Waits for `delay` before starting the animation.
+    pub fn delay(self, delay: Duration) -> Self {
+        Self { delay, ..self }
+    }
+
+    /// Repeats the animation `iterations` times, which may be fractional or
+    /// [`f64::INFINITY`].
+    pub fn iterations(self, iterations: f64) -> Self {
+        Self { iterations, ..self }
+    }
+
+    /// The timing function over the whole of each iteration, such as
+    /// `"ease-out"`.
+    pub fn easing(self, easing: &'static str) -> Self {
+        Self { easing, ..self }
+    }
+
+    /// How the styles of the animation apply before and after it runs.
+    pub fn fill(self, fill: Fill) -> Self {
+        Self { fill, ..self }
+    }
+
+    /// Runs every other iteration backwards.
+    pub fn alternate(self) -> Self {
+        Self {
+            alternate: true,
+            ..self
+        }
+    }
+
+    fn to_js(self) -> js_sys::Object {
+        let object = js_sys::Object::new();
+        set(&object, "duration", &self.duration.as_millis().into());
+        set(&object, "delay", &self.delay.as_millis().into());
+        set(&object, "iterations", &self.iterations.into());
+        set(&object, "easing", &self.easing.into());
+        let fill = match self.fill {
+            Fill::None => "none",
+            Fill::Forwards => "forwards",
+            Fill::Backwards => "backwards",
+            Fill::Both => "both",
+        };
+        set(&object, "fill", &fill.into());
+        if self.alternate {
+            set(&object, "direction", &"alternate".into());
+        }
+        object
+    }
+}
+
+/// A handle to an animation started by [`animate`].
+///
+/// The [`Default`] handle has no animation, and its methods do nothing, so
+/// that it can be the initial value of local state. Dropping a handle doesn't
+/// stop its animation.
+#[derive(Clone, Default, Debug, PartialEq, Eq)]
+pub struct Animation(Option<web_sys::Animation>);
+
+impl Animation {
+    /// Stops the animation and removes its effects.
+    pub fn cancel(&self) {
+        if let Some(animation) = &self.0 {
+            animation.cancel();
+        }
+    }
+
+    /// Skips to the end of the animation.
+    pub fn finish(&self) {
+        if let Some(animation) = &self.0 {
+            let _ = animation.finish();
+        }
+    }
+
+    /// Pauses the animation.
+    pub fn pause(&self) {
+        if let Some(animation) = &self.0 {
+            let _ = animation.pause();
+        }
+    }
+
+    /// Resumes a paused animation, or restarts a finished one.
+    pub fn play(&self) {
+        if let Some(animation) = &self.0 {
+            let _ = animation.play();
+        }
+    }
+
+    /// Runs the animation backwards from where it is.
+    pub fn reverse(&self) {
+        if let Some(animation) = &self.0 {
+            let _ = animation.reverse();
+        }
+    }
+
+    /// The underlying animation, if one was started.
+    pub fn raw(&self) -> Option<&web_sys::Animation> {
+        self.0.as_ref()
+    }
+}
+
+/// Starts an animation of the element referenced by `node_ref`, through
+/// `keyframes`.
+///
+/// If the element isn't currently built, nothing happens, and the returned
+/// handle is empty.
+pub fn animate(
+    node_ref: &NodeRef,
+    keyframes: &[Keyframe],
+    options: &Options,
+) -> Animation {
+    let Some(element) = node_ref.get() else {
+        return Animation::default();
+    };
+
+    let keyframes: js_sys::Array =
+        keyframes.iter().map(Keyframe::to_js).collect();
+
+    // `Element.animate` is only bound by web-sys as an unstable API.
+    let Ok(method) = js_sys::Reflect::get(&element, &"animate".into()) else {
+        return Animation::default();
+    };
+    let Ok(method) = method.dyn_into::<js_sys::Function>() else {
+        return Animation::default();
+    };
+
+    match method.call2(&element, &keyframes, &options.to_js()) {
+        Ok(animation) => Animation(Some(animation.unchecked_into())),
+        Err(_) => Animation::default(),
+    }
+}
+
+/// The name of the keyframe property for the CSS property `name`: custom
+/// properties are used as is, and others are converted to camel case.
+fn property_key(name: &str) -> String {
+    if name.starts_with("--") {
+        return name.to_owned();
+    }
+    if name == "float" {
+        return "cssFloat".to_owned();
+    }
+
+    let mut key = String::with_capacity(name.len());
+    let mut upper = false;
+    for c in name.chars() {
+        if c == '-' {
+            upper = true;
+        } else if upper {
+            key.push(c.to_ascii_uppercase());
+            upper = false;
+        } else {
+            key.push(c);
+        }
+    }
+    key
+}
+
+fn set(object: &js_sys::Object, key: &str, value: &JsValue) {
+    let _ = js_sys::Reflect::set(object, &key.into(), value);
+}
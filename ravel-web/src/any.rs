@@ -1,19 +1,42 @@
-use std::{any::Any, marker::PhantomData, ops::DerefMut};
+use std::{marker::PhantomData, ops::DerefMut, rc::Rc};
 
-use ravel::State;
-use web_sys::wasm_bindgen::UnwrapThrowExt as _;
+use ravel::{MemSize, State};
 
 use crate::{
-    dom::{clear, Position},
+    dom::{clear, detach, marker, Position},
+    event::Gate,
     BuildCx, Builder, RebuildCx, View, ViewMarker, Web,
 };
 
 /// A wrapper around a [`trait@View`], erasing its [`State`] type.
 pub struct AnyView<V: View, Output> {
     inner: V,
+    pool: usize,
     phantom: PhantomData<fn(&mut Output)>,
 }
 
+impl<V: View, Output> AnyView<V, Output> {
+    /// Keeps the states of up to `capacity` views which were replaced, along
+    /// with their nodes, so that switching back to a view of the same type
+    /// rebuilds the kept state rather than building it from scratch.
+    ///
+    /// This trades memory for latency when switching between a few views
+    /// repeatedly, such as toggling panels. Kept states are still alive while
+    /// they are hidden, so any timers or tasks they started keep running.
+    /// Their events are discarded, including any still pending when they were
+    /// replaced, so nothing is replayed when they are reused. When the pool is
+    /// full, the least recently replaced state is dropped.
+    ///
+    /// An optional view can be pooled by choosing between it and an empty
+    /// view, such as `()`.
+    pub fn pooled(self, capacity: usize) -> Self {
+        Self {
+            pool: capacity,
+            ..self
+        }
+    }
+}
+
 impl<V: View, Output: 'static> Builder<Web> for AnyView<V, Output>
 where
     V::State: State<Output>,
@@ -21,41 +44,128 @@ where
     type State = AnyState<Output>;
 
     fn build(self, cx: BuildCx) -> Self::State {
-        let start = web_sys::Comment::new_with_data("{").unwrap_throw();
-        let end = web_sys::Comment::new_with_data("}").unwrap_throw();
+        let start = marker("{");
+        let end = marker("}");
 
         cx.position.insert(&start);
-        let state = Box::new(self.inner.build(cx));
+        let gate = (self.pool > 0).then(Gate::new);
+        let state = Box::new(in_gate(&gate, || self.inner.build(cx)));
         cx.position.insert(&end);
 
-        AnyState { state, start, end }
+        AnyState {
+            state,
+            gate,
+            pool: Vec::new(),
+            start,
+            end,
+        }
     }
 
     fn rebuild(self, cx: RebuildCx, state: &mut Self::State) {
-        match (state.state.as_mut_dyn_any().deref_mut() as &mut dyn Any)
-            .downcast_mut::<V::State>()
-        {
-            Some(state) => self.inner.rebuild(cx, state),
-            None => {
+        if let Some(inner) = downcast_mut::<V::State, _>(&mut state.state) {
+            return in_gate(&state.gate, || self.inner.rebuild(cx, inner));
+        }
+
+        // Only states built with a gate can be kept, since otherwise their
+        // events couldn't be discarded.
+        let nodes = match &state.gate {
+            Some(gate) if self.pool > 0 => {
+                gate.close();
+                Some(detach(&state.start, &state.end))
+            }
+            _ => {
                 clear(cx.parent, &state.start, &state.end);
+                None
+            }
+        };
 
-                state.state = Box::new(self.inner.build(BuildCx {
-                    position: Position {
-                        parent: cx.parent,
-                        insert_before: &state.end,
-                        waker: cx.waker,
-                    },
-                }))
+        let reused = state.pool.iter_mut().position(|pooled| {
+            downcast_mut::<V::State, _>(&mut pooled.state).is_some()
+        });
+        let replaced = match reused {
+            Some(i) => {
+                let pooled = state.pool.remove(i);
+                Position {
+                    parent: cx.parent,
+                    insert_before: &state.end,
+                    waker: cx.waker,
+                }
+                .insert(&pooled.nodes);
+
+                pooled.gate.open();
+                let replaced = (
+                    std::mem::replace(&mut state.state, pooled.state),
+                    state.gate.replace(pooled.gate),
+                );
+                let inner =
+                    downcast_mut::<V::State, _>(&mut state.state).unwrap();
+                in_gate(&state.gate, || self.inner.rebuild(cx, inner));
+                replaced
+            }
+            None => {
+                let gate = (self.pool > 0).then(Gate::new);
+                let position = Position {
+                    parent: cx.parent,
+                    insert_before: &state.end,
+                    waker: cx.waker,
+                };
+                let built =
+                    in_gate(&gate, || self.inner.build(BuildCx { position }));
+                (
+                    std::mem::replace(&mut state.state, Box::new(built)),
+                    std::mem::replace(&mut state.gate, gate),
+                )
             }
+        };
+
+        if let (Some(nodes), (replaced, Some(gate))) = (nodes, replaced) {
+            state.pool.push(Pooled {
+                state: replaced,
+                gate,
+                nodes,
+            });
         }
+        let excess = state.pool.len().saturating_sub(self.pool);
+        state.pool.drain(..excess);
     }
 }
 
+/// Runs `f` in the scope of `gate`, if there is one.
+fn in_gate<R>(gate: &Option<Rc<Gate>>, f: impl FnOnce() -> R) -> R {
+    match gate {
+        Some(gate) => gate.scope(f),
+        None => f(),
+    }
+}
+
+/// Downcasts a type-erased state.
+///
+/// The `Box` is itself a [`State`] (and so [`ravel::AsAny`]), so it must be
+/// dereferenced first: calling `as_mut_dyn_any` on the `Box` would erase the
+/// `Box` rather than the state inside it, and never match `S`.
+fn downcast_mut<S: 'static, Output: 'static>(
+    state: &mut Box<dyn State<Output>>,
+) -> Option<&mut S> {
+    state.deref_mut().as_mut_dyn_any().downcast_mut()
+}
+
 /// The state for an [`AnyView`].
 pub struct AnyState<Output> {
     state: Box<dyn State<Output>>,
-    start: web_sys::Comment,
-    end: web_sys::Comment,
+    /// Set if the view is pooled, to discard the events of `state` once it is
+    /// replaced.
+    gate: Option<Rc<Gate>>,
+    /// Replaced states kept by [`AnyView::pooled`], least recently replaced
+    /// first.
+    pool: Vec<Pooled<Output>>,
+    start: web_sys::CharacterData,
+    end: web_sys::CharacterData,
+}
+
+struct Pooled<Output> {
+    state: Box<dyn State<Output>>,
+    gate: Rc<Gate>,
+    nodes: web_sys::DocumentFragment,
 }
 
 impl<Output: 'static> State<Output> for AnyState<Output> {
@@ -64,6 +174,21 @@ impl<Output: 'static> State<Output> for AnyState<Output> {
     }
 }
 
+impl<Output> MemSize for AnyState<Output> {
+    /// Only counts the boxed state itself, not any memory it owns, since its
+    /// type has been erased. This is an underestimate for states such as
+    /// lists.
+    fn heap_size(&self) -> usize {
+        size_of_val(&*self.state)
+            + self.pool.capacity() * size_of::<Pooled<Output>>()
+            + self
+                .pool
+                .iter()
+                .map(|pooled| size_of_val(&*pooled.state))
+                .sum::<usize>()
+    }
+}
+
 impl<Output> ViewMarker for AnyState<Output> {}
 
 /// Wraps a [`trait@View`], erasing its [`State`] type.
@@ -73,6 +198,68 @@ impl<Output> ViewMarker for AnyState<Output> {}
 pub fn any<V: View, Output: 'static>(view: V) -> AnyView<V, Output> {
     AnyView {
         inner: view,
+        pool: 0,
         phantom: PhantomData,
     }
 }
+
+#[cfg(all(test, target_arch = "wasm32"))]
+mod tests {
+    use std::sync::Arc;
+
+    use atomic_waker::AtomicWaker;
+    use ravel::{Builder, State};
+    use wasm_bindgen_test::{wasm_bindgen_test, wasm_bindgen_test_configure};
+    use web_sys::wasm_bindgen::{JsValue, UnwrapThrowExt as _};
+
+    use crate::{
+        any, el,
+        event::{on_, Click},
+        BuildCx, Position, RebuildCx,
+    };
+
+    wasm_bindgen_test_configure!(run_in_browser);
+
+    #[wasm_bindgen_test]
+    fn pooled_state_discards_events() {
+        let parent =
+            gloo_utils::document().create_element("div").unwrap_throw();
+        let waker = Arc::new(AtomicWaker::new());
+        let cx = RebuildCx {
+            parent: &parent,
+            waker: &waker,
+        };
+        let count = |n: &mut u32| *n += 1;
+        let click = |target: &web_sys::Element| {
+            let event = web_sys::Event::new("click").unwrap_throw();
+            target.dispatch_event(&event).unwrap_throw();
+        };
+
+        let mut state =
+            any(el::button(on_(Click, count))).pooled(1).build(BuildCx {
+                position: Position {
+                    parent: &parent,
+                    insert_before: &JsValue::NULL.into(),
+                    waker: &waker,
+                },
+            });
+        let button = parent.first_element_child().unwrap_throw();
+
+        // Pending when the button is replaced, and while it is pooled.
+        click(&button);
+        any(el::span(())).pooled(1).rebuild(cx, &mut state);
+        click(&button);
+
+        let mut n = 0;
+        any(el::button(on_(Click, count)))
+            .pooled(1)
+            .rebuild(cx, &mut state);
+        state.run(&mut n);
+        assert_eq!(n, 0);
+        assert_eq!(parent.first_element_child(), Some(button.clone()));
+
+        click(&button);
+        state.run(&mut n);
+        assert_eq!(n, 1);
+    }
+}
@@ -1,10 +1,13 @@
 //! HTML attributes.
 
-use std::marker::PhantomData;
+use std::{convert::Infallible, marker::PhantomData};
 
 use ravel::Builder;
 
-use crate::{BuildCx, RebuildCx, Web};
+use crate::{
+    ssr::{Ssr, SsrCx},
+    BuildCx, RebuildCx, Web,
+};
 
 use self::types::*;
 
@@ -31,6 +34,138 @@ pub fn attr<Kind: AttrKind, Value: AttrValue>(
     }
 }
 
+/// Takes ownership of an attribute, re-asserting its value on every rebuild if
+/// it was changed by something other than this component (for example, a
+/// third party script).
+///
+/// By default, attributes are only written when their value changes, so
+/// external changes persist until the next time the value changes.
+#[derive(Copy, Clone, Debug)]
+pub struct Enforce<A: AttrBuilder>(pub A);
+
+impl<A: AttrBuilder> Builder<Web> for Enforce<A> {
+    type State = AttrState<<A::Value as AttrValue>::Saved>;
+
+    fn build(self, cx: BuildCx) -> Self::State {
+        AttrState::build(cx.position.parent, A::NAME, self.0.into_value())
+    }
+
+    fn rebuild(self, cx: RebuildCx, state: &mut Self::State) {
+        state.enforce(cx.parent, A::NAME, self.0.into_value())
+    }
+}
+
+impl<A: AttrBuilder> Builder<Ssr> for Enforce<A> {
+    type State = ();
+
+    fn build(self, cx: SsrCx) -> Self::State {
+        cx.attr(A::NAME, self.0.into_value())
+    }
+
+    fn rebuild(self, cx: Infallible, _: &mut Self::State) {
+        match cx {}
+    }
+}
+
+/// Yields ownership of an attribute to external code: a value which was
+/// changed by something other than this component (for example, a third party
+/// script) is never overwritten.
+///
+/// The attribute is still updated when the value changes, as long as it has not
+/// been changed externally since it was last written.
+#[derive(Copy, Clone, Debug)]
+pub struct Yield<A: AttrBuilder>(pub A);
+
+impl<A: AttrBuilder> Builder<Web> for Yield<A> {
+    type State = YieldState<<A::Value as AttrValue>::Saved>;
+
+    fn build(self, cx: BuildCx) -> Self::State {
+        YieldState::build(cx.position.parent, A::NAME, self.0.into_value())
+    }
+
+    fn rebuild(self, cx: RebuildCx, state: &mut Self::State) {
+        state.rebuild(cx.parent, A::NAME, self.0.into_value())
+    }
+}
+
+impl<A: AttrBuilder> Builder<Ssr> for Yield<A> {
+    type State = ();
+
+    fn build(self, cx: SsrCx) -> Self::State {
+        cx.attr(A::NAME, self.0.into_value())
+    }
+
+    fn rebuild(self, cx: Infallible, _: &mut Self::State) {
+        match cx {}
+    }
+}
+
+macro_rules! prefixed_attr {
+    ($(#[$attr:meta])* $t:ident, $state:ident, $f:ident, $prefix:literal) => {
+        $(#[$attr])*
+        #[derive(Copy, Clone, Debug)]
+        pub struct $t<V: AttrValue> {
+            name: &'static str,
+            value: V,
+        }
+
+        $(#[$attr])*
+        pub fn $f<V: AttrValue>(name: &'static str, value: V) -> $t<V> {
+            $t { name, value }
+        }
+
+        impl<V: AttrValue> Builder<Web> for $t<V> {
+            type State = $state<V::Saved>;
+
+            fn build(self, cx: BuildCx) -> Self::State {
+                $state(PrefixedAttrState::build(
+                    cx.position.parent,
+                    $prefix,
+                    self.name,
+                    self.value,
+                ))
+            }
+
+            fn rebuild(self, cx: RebuildCx, state: &mut Self::State) {
+                state.0.rebuild(cx.parent, $prefix, self.name, self.value)
+            }
+        }
+
+        impl<V: AttrValue> Builder<Ssr> for $t<V> {
+            type State = ();
+
+            fn build(self, cx: SsrCx) -> Self::State {
+                cx.attr(format!("{}{}", $prefix, self.name), self.value)
+            }
+
+            fn rebuild(self, cx: Infallible, _: &mut Self::State) {
+                match cx {}
+            }
+        }
+    };
+}
+
+prefixed_attr!(
+    /// A custom data attribute, named `data-` followed by `name`, which is
+    /// also available to scripts and stylesheets as
+    /// [`dataset`](https://developer.mozilla.org/en-US/docs/Web/API/HTMLElement/dataset).
+    DataAttr,
+    DataState,
+    data,
+    "data-"
+);
+prefixed_attr!(
+    /// An [ARIA](https://developer.mozilla.org/en-US/docs/Web/Accessibility/ARIA)
+    /// attribute, named `aria-` followed by `name`.
+    ///
+    /// The most common ones are also available as typed attributes, like
+    /// [`AriaLabel`] and [`AriaExpanded`].
+    AriaAttr,
+    AriaState,
+    aria,
+    "aria-"
+);
+
 macro_rules! make_attr_value_type {
     ($name:literal, $t:ident, $value_type:ty) => {
         make_attr_value_type_state!(
@@ -38,7 +173,7 @@ macro_rules! make_attr_value_type {
             $t,
             $value_type,
             std::convert::identity,
-            <V as AttrValue>::Saved
+            $value_type
         );
     };
     ($name:literal, $t:ident, $value_type:ty, $value_wrapper:ident) => {
@@ -47,15 +182,25 @@ macro_rules! make_attr_value_type {
             $t,
             $value_type,
             $value_wrapper,
-            <$value_wrapper as AttrValue>::Saved
+            $value_wrapper
         );
     };
 }
 
 macro_rules! make_attr_value_type_state {
-    ($name:literal, $t:ident, $value_type:ty, $value_wrapper:expr, $state_value:ty) => {
+    ($name:literal, $t:ident, $value_type:ty, $value_wrapper:expr, $value:ty) => {
+        impl AttrBuilder for $t {
+            const NAME: &'static str = $name;
+
+            type Value = $value;
+
+            fn into_value(self) -> Self::Value {
+                $value_wrapper(self.0)
+            }
+        }
+
         impl Builder<Web> for $t {
-            type State = AttrState<$state_value>;
+            type State = AttrState<<$value as AttrValue>::Saved>;
 
             fn build(self, cx: BuildCx) -> Self::State {
                 AttrState::build(
@@ -69,17 +214,66 @@ macro_rules! make_attr_value_type_state {
                 state.rebuild(cx.parent, $name, $value_wrapper(self.0))
             }
         }
+
+        impl Builder<Ssr> for $t {
+            type State = ();
+
+            fn build(self, cx: SsrCx) -> Self::State {
+                cx.attr($name, $value_wrapper(self.0))
+            }
+
+            fn rebuild(self, cx: Infallible, _: &mut Self::State) {
+                match cx {}
+            }
+        }
     };
 }
 
 macro_rules! make_attr_value_trait {
+    // Classes are diffed individually through the element's class list,
+    // rather than by rewriting the attribute.
+    ("class", $t:ident, ClassValue, Classes) => {
+        impl<V: ClassValue> AttrBuilder for $t<V> {
+            const NAME: &'static str = "class";
+
+            type Value = Classes<V>;
+
+            fn into_value(self) -> Self::Value {
+                Classes(self.0)
+            }
+        }
+
+        impl<V: ClassValue> Builder<Web> for $t<V> {
+            type State = ClassListState<V>;
+
+            fn build(self, cx: BuildCx) -> Self::State {
+                ClassListState::build(cx.position.parent, self.0)
+            }
+
+            fn rebuild(self, cx: RebuildCx, state: &mut Self::State) {
+                state.rebuild(cx.parent, self.0)
+            }
+        }
+
+        impl<V: ClassValue> Builder<Ssr> for $t<V> {
+            type State = ();
+
+            fn build(self, cx: SsrCx) -> Self::State {
+                cx.attr("class", Classes(self.0))
+            }
+
+            fn rebuild(self, cx: Infallible, _: &mut Self::State) {
+                match cx {}
+            }
+        }
+    };
     ($name:literal, $t:ident, $value_trait:ident) => {
         make_attr_value_trait_state!(
             $name,
             $t,
             $value_trait,
             std::convert::identity,
-            <V as AttrValue>::Saved
+            V
         );
     };
     ($name:literal, $t:ident, $value_trait:ident, $value_wrapper:ident) => {
@@ -88,15 +282,25 @@ macro_rules! make_attr_value_trait {
             $t,
             $value_trait,
             $value_wrapper,
-            <$value_wrapper<V> as AttrValue>::Saved
+            $value_wrapper<V>
         );
     };
 }
 
 macro_rules! make_attr_value_trait_state {
-    ($name:literal, $t:ident, $value_trait:ident, $value_wrapper:expr, $state_value:ty) => {
+    ($name:literal, $t:ident, $value_trait:ident, $value_wrapper:expr, $value:ty) => {
+        impl<V: $value_trait> AttrBuilder for $t<V> {
+            const NAME: &'static str = $name;
+
+            type Value = $value;
+
+            fn into_value(self) -> Self::Value {
+                $value_wrapper(self.0)
+            }
+        }
+
         impl<V: $value_trait> Builder<Web> for $t<V> {
-            type State = AttrState<$state_value>;
+            type State = AttrState<<$value as AttrValue>::Saved>;
 
             fn build(self, cx: BuildCx) -> Self::State {
                 AttrState::build(
@@ -110,6 +314,18 @@ macro_rules! make_attr_value_trait_state {
                 state.rebuild(cx.parent, $name, $value_wrapper(self.0))
             }
         }
+
+        impl<V: $value_trait> Builder<Ssr> for $t<V> {
+            type State = ();
+
+            fn build(self, cx: SsrCx) -> Self::State {
+                cx.attr($name, $value_wrapper(self.0))
+            }
+
+            fn rebuild(self, cx: Infallible, _: &mut Self::State) {
+                match cx {}
+            }
+        }
     };
 }
 
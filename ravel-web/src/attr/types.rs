@@ -2,12 +2,15 @@
 //!
 //! Usually you shouldn't need to import or reference these directly.
 
-use std::marker::PhantomData;
+use std::{convert::Infallible, marker::PhantomData};
 
-use ravel::{Builder, State};
+use ravel::{Builder, MemSize, State};
 use wasm_bindgen::UnwrapThrowExt;
 
-use crate::{BuildCx, RebuildCx, Web};
+use crate::{
+    ssr::{Ssr, SsrCx},
+    stats, BuildCx, RebuildCx, Web,
+};
 
 use super::CloneString;
 
@@ -17,6 +20,19 @@ pub trait AttrKind: 'static {
     const NAME: &'static str;
 }
 
+/// Trait for attribute [`Builder`]s, exposing their name and value.
+///
+/// This is implemented for all attribute types, and allows wrappers such as
+/// [`Enforce`](super::Enforce) to operate on any attribute.
+pub trait AttrBuilder {
+    /// The name of the attribute.
+    const NAME: &'static str;
+
+    type Value: AttrValue;
+
+    fn into_value(self) -> Self::Value;
+}
+
 pub trait AttrValue {
     type Saved: 'static;
 
@@ -117,6 +133,32 @@ impl AttrValue for BooleanAttrValue {
     }
 }
 
+/// A `true` or `false` value, as used by ARIA attributes like
+/// `aria-expanded`, which (unlike HTML boolean attributes) are not removed
+/// when false.
+#[doc(hidden)]
+#[derive(Clone, Copy, Debug)]
+pub struct AriaBooleanValue(pub bool);
+
+impl AttrValue for AriaBooleanValue {
+    type Saved = bool;
+
+    fn save(self) -> Self::Saved {
+        self.0
+    }
+
+    fn changed(&self, saved: &Self::Saved) -> bool {
+        self.0 != *saved
+    }
+
+    fn with_str<F, R>(&self, f: F) -> R
+    where
+        F: FnOnce(Option<&str>) -> R,
+    {
+        f(Some(if self.0 { "true" } else { "false" }))
+    }
+}
+
 macro_rules! make_attr_value_copy_to_string {
     ($t:ty) => {
         impl AttrValue for $t {
@@ -142,6 +184,9 @@ macro_rules! make_attr_value_copy_to_string {
 }
 
 make_attr_value_copy_to_string!(char);
+make_attr_value_copy_to_string!(crate::AutoId);
+make_attr_value_copy_to_string!(crate::direction::Direction);
+make_attr_value_copy_to_string!(crate::units::Color);
 make_attr_value_copy_to_string!(f32);
 make_attr_value_copy_to_string!(f64);
 make_attr_value_copy_to_string!(i128);
@@ -220,6 +265,11 @@ tuple_class_value!(a, b, c, d, e);
 tuple_class_value!(a, b, c, d, e, f);
 tuple_class_value!(a, b, c, d, e, f, g);
 tuple_class_value!(a, b, c, d, e, f, g, h);
+tuple_class_value!(a, b, c, d, e, f, g, h, i);
+tuple_class_value!(a, b, c, d, e, f, g, h, i, j);
+tuple_class_value!(a, b, c, d, e, f, g, h, i, j, k);
+tuple_class_value!(a, b, c, d, e, f, g, h, i, j, k, l);
+// Tuples only implement `PartialEq` up to this arity.
 
 #[doc(hidden)]
 pub struct Classes<V: ClassValue>(pub V);
@@ -258,6 +308,81 @@ impl<V: ClassValue> AttrValue for Classes<V> {
     }
 }
 
+/// The state of a [`Class`](super::Class) attribute.
+///
+/// Rather than rewriting the whole attribute, classes are added and removed
+/// individually, so classes added by other code (for example, a third party
+/// script) are left alone, unless they are also in the value.
+pub struct ClassListState<V> {
+    value: V,
+    classes: Vec<String>,
+}
+
+impl<V: ClassValue> ClassListState<V> {
+    pub(crate) fn build(parent: &web_sys::Element, value: V) -> Self {
+        let classes = class_names(&value);
+
+        let list = parent.class_list();
+        for class in &classes {
+            stats::attribute_written();
+            list.add_1(class).unwrap_throw();
+        }
+
+        Self { value, classes }
+    }
+
+    pub(crate) fn rebuild(&mut self, parent: &web_sys::Element, value: V) {
+        if value == self.value {
+            return;
+        }
+
+        let classes = class_names(&value);
+
+        let list = parent.class_list();
+        for class in &self.classes {
+            if !classes.contains(class) {
+                stats::attribute_written();
+                list.remove_1(class).unwrap_throw();
+            }
+        }
+        for class in &classes {
+            if !self.classes.contains(class) {
+                stats::attribute_written();
+                list.add_1(class).unwrap_throw();
+            }
+        }
+
+        self.value = value;
+        self.classes = classes;
+    }
+}
+
+/// The distinct class names in a value, which may each contain several
+/// space separated names.
+fn class_names(value: &impl ClassValue) -> Vec<String> {
+    let mut classes: Vec<String> = Vec::new();
+
+    value.for_each(|c| {
+        for class in c.split_ascii_whitespace() {
+            if !classes.iter().any(|c| c == class) {
+                classes.push(class.to_string());
+            }
+        }
+    });
+
+    classes
+}
+
+impl<V: 'static, Output> State<Output> for ClassListState<V> {
+    fn run(&mut self, _: &mut Output) {}
+}
+
+impl<V: MemSize> MemSize for ClassListState<V> {
+    fn heap_size(&self) -> usize {
+        self.value.heap_size() + self.classes.heap_size()
+    }
+}
+
 /// The state of an [`Attr`].
 pub struct AttrState<Saved> {
     value: Saved,
@@ -266,12 +391,12 @@ pub struct AttrState<Saved> {
 impl<Saved> AttrState<Saved> {
     pub(crate) fn build<V: AttrValue<Saved = Saved>>(
         parent: &web_sys::Element,
-        name: &'static str,
+        name: &str,
         value: V,
     ) -> Self {
         value.with_str(|value| {
-            if let Some(value) = value {
-                parent.set_attribute(name, value).unwrap_throw()
+            if value.is_some() {
+                set_attribute(parent, name, value)
             }
         });
 
@@ -283,20 +408,166 @@ impl<Saved> AttrState<Saved> {
     pub(crate) fn rebuild<V: AttrValue<Saved = Saved>>(
         &mut self,
         parent: &web_sys::Element,
-        name: &'static str,
+        name: &str,
         value: V,
     ) {
         if !value.changed(&self.value) {
             return;
         }
 
+        value.with_str(|value| set_attribute(parent, name, value));
+        self.value = value.save();
+    }
+
+    pub(crate) fn enforce<V: AttrValue<Saved = Saved>>(
+        &mut self,
+        parent: &web_sys::Element,
+        name: &str,
+        value: V,
+    ) {
         value.with_str(|value| {
-            if let Some(value) = value {
-                parent.set_attribute(name, value).unwrap_throw()
-            } else {
-                parent.remove_attribute(name).unwrap_throw()
+            if parent.get_attribute(name).as_deref() != value {
+                set_attribute(parent, name, value)
             }
         });
+
+        if value.changed(&self.value) {
+            self.value = value.save();
+        }
+    }
+}
+
+/// The state of an attribute whose name is a fixed prefix followed by a name
+/// chosen at runtime.
+pub struct PrefixedAttrState<Saved> {
+    name: &'static str,
+    full_name: String,
+    attr: AttrState<Saved>,
+}
+
+impl<Saved: MemSize> MemSize for PrefixedAttrState<Saved> {
+    fn heap_size(&self) -> usize {
+        self.full_name.heap_size() + self.attr.heap_size()
+    }
+}
+
+impl<Saved> PrefixedAttrState<Saved> {
+    pub(crate) fn build<V: AttrValue<Saved = Saved>>(
+        parent: &web_sys::Element,
+        prefix: &str,
+        name: &'static str,
+        value: V,
+    ) -> Self {
+        let full_name = format!("{prefix}{name}");
+
+        Self {
+            name,
+            attr: AttrState::build(parent, &full_name, value),
+            full_name,
+        }
+    }
+
+    pub(crate) fn rebuild<V: AttrValue<Saved = Saved>>(
+        &mut self,
+        parent: &web_sys::Element,
+        prefix: &str,
+        name: &'static str,
+        value: V,
+    ) {
+        if name == self.name {
+            self.attr.rebuild(parent, &self.full_name, value)
+        } else {
+            set_attribute(parent, &self.full_name, None);
+            *self = Self::build(parent, prefix, name, value);
+        }
+    }
+}
+
+/// The state of a [`DataAttr`](super::DataAttr).
+pub struct DataState<Saved>(pub(crate) PrefixedAttrState<Saved>);
+
+impl<Saved: 'static, Output> State<Output> for DataState<Saved> {
+    fn run(&mut self, _: &mut Output) {}
+}
+
+impl<Saved: MemSize> MemSize for DataState<Saved> {
+    fn heap_size(&self) -> usize {
+        self.0.heap_size()
+    }
+}
+
+/// The state of an [`AriaAttr`](super::AriaAttr).
+pub struct AriaState<Saved>(pub(crate) PrefixedAttrState<Saved>);
+
+impl<Saved: 'static, Output> State<Output> for AriaState<Saved> {
+    fn run(&mut self, _: &mut Output) {}
+}
+
+impl<Saved: MemSize> MemSize for AriaState<Saved> {
+    fn heap_size(&self) -> usize {
+        self.0.heap_size()
+    }
+}
+
+/// The state of a [`Yield`](super::Yield).
+pub struct YieldState<Saved> {
+    value: Saved,
+    written: Option<String>,
+}
+
+impl<Saved> YieldState<Saved> {
+    pub(crate) fn build<V: AttrValue<Saved = Saved>>(
+        parent: &web_sys::Element,
+        name: &'static str,
+        value: V,
+    ) -> Self {
+        let written = value.with_str(|value| {
+            set_attribute(parent, name, value);
+            value.map(str::to_string)
+        });
+
+        Self {
+            value: value.save(),
+            written,
+        }
+    }
+
+    pub(crate) fn rebuild<V: AttrValue<Saved = Saved>>(
+        &mut self,
+        parent: &web_sys::Element,
+        name: &'static str,
+        value: V,
+    ) {
+        if !value.changed(&self.value) {
+            return;
+        }
+
+        if parent.get_attribute(name) == self.written {
+            self.written = value.with_str(|value| {
+                set_attribute(parent, name, value);
+                value.map(str::to_string)
+            });
+        }
+
+        self.value = value.save();
+    }
+}
+
+impl<Saved: 'static, Output> State<Output> for YieldState<Saved> {
+    fn run(&mut self, _: &mut Output) {}
+}
+
+impl<Saved: MemSize> MemSize for YieldState<Saved> {
+    fn heap_size(&self) -> usize {
+        self.value.heap_size() + self.written.heap_size()
+    }
+}
+
+fn set_attribute(parent: &web_sys::Element, name: &str, value: Option<&str>) {
+    stats::attribute_written();
+    match value {
+        Some(value) => parent.set_attribute(name, value).unwrap_throw(),
+        None => parent.remove_attribute(name).unwrap_throw(),
     }
 }
 
@@ -304,6 +575,12 @@ impl<Saved: 'static, Output> State<Output> for AttrState<Saved> {
     fn run(&mut self, _: &mut Output) {}
 }
 
+impl<Saved: MemSize> MemSize for AttrState<Saved> {
+    fn heap_size(&self) -> usize {
+        self.value.heap_size()
+    }
+}
+
 /// An arbitrary attribute.
 #[repr(transparent)]
 #[derive(Copy, Clone, Debug)]
@@ -312,6 +589,16 @@ pub struct Attr<Kind: AttrKind, Value> {
     pub(crate) kind: PhantomData<Kind>,
 }
 
+impl<Kind: AttrKind, Value: AttrValue> AttrBuilder for Attr<Kind, Value> {
+    const NAME: &'static str = Kind::NAME;
+
+    type Value = Value;
+
+    fn into_value(self) -> Self::Value {
+        self.value
+    }
+}
+
 impl<Kind: AttrKind, Value: AttrValue> Builder<Web> for Attr<Kind, Value> {
     type State = AttrState<Value::Saved>;
 
@@ -323,3 +610,15 @@ impl<Kind: AttrKind, Value: AttrValue> Builder<Web> for Attr<Kind, Value> {
         state.rebuild(cx.parent, Kind::NAME, self.value)
     }
 }
+
+impl<Kind: AttrKind, Value: AttrValue> Builder<Ssr> for Attr<Kind, Value> {
+    type State = ();
+
+    fn build(self, cx: SsrCx) -> Self::State {
+        cx.attr(Kind::NAME, self.value)
+    }
+
+    fn rebuild(self, cx: Infallible, _: &mut Self::State) {
+        match cx {}
+    }
+}
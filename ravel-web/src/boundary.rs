@@ -0,0 +1,260 @@
+//! Containing panics to part of the page.
+
+use std::{
+    cell::Cell,
+    convert::Infallible,
+    panic::{catch_unwind, AssertUnwindSafe},
+    rc::Rc,
+    sync::Arc,
+};
+
+use atomic_waker::AtomicWaker;
+use ravel::{MemSize, State};
+
+use crate::{
+    dom::{clear, marker, Position},
+    run::panic_message,
+    ssr::{Ssr, SsrCx},
+    BuildCx, Builder, RebuildCx, View, ViewMarker, Web,
+};
+
+/// Rebuilds an [`error_boundary`]'s body after it has failed.
+#[derive(Clone)]
+pub struct Retry {
+    requested: Rc<Cell<bool>>,
+    waker: Arc<AtomicWaker>,
+}
+
+impl Retry {
+    /// Discards the fallback and builds the body from scratch on the next
+    /// frame. If it panics again, a new fallback is shown.
+    pub fn retry(&self) {
+        self.requested.set(true);
+        self.waker.wake();
+    }
+}
+
+/// A panic caught by an [`error_boundary`].
+pub struct Failure {
+    message: String,
+    retry: Retry,
+}
+
+impl Failure {
+    /// The message passed to [`panic!`].
+    pub fn message(&self) -> &str {
+        &self.message
+    }
+
+    /// A handle to retry the failed body, for example from an event handler
+    /// in the fallback.
+    pub fn retry(&self) -> Retry {
+        self.retry.clone()
+    }
+}
+
+/// A [`Builder`] created from [`error_boundary`].
+pub struct ErrorBoundary<Fallback, Body> {
+    fallback: Fallback,
+    body: Body,
+}
+
+/// Builds `body`, but if it panics while being built or rebuilt, removes
+/// whatever it had built and shows the view returned by `fallback` instead.
+///
+/// The fallback is shown until [`Retry::retry`] is called, with the handle
+/// from [`Failure::retry`]. Panics in event handlers are not caught.
+///
+/// This relies on [`std::panic::catch_unwind`], so on `wasm32` it only takes
+/// effect when compiled with `panic = "unwind"`. Otherwise, panics always
+/// abort. As with any use of `catch_unwind`, the model may be left in an
+/// inconsistent state if the panic interrupted an update.
+pub fn error_boundary<Fallback, FallbackView, Body>(
+    fallback: Fallback,
+    body: Body,
+) -> ErrorBoundary<Fallback, Body>
+where
+    Fallback: FnOnce(&Failure) -> FallbackView,
+    FallbackView: View,
+    Body: View,
+{
+    ErrorBoundary { fallback, body }
+}
+
+enum Inner<S, F> {
+    Body(S),
+    Fallback(Failure, F),
+}
+
+impl<S: MemSize, F: MemSize> MemSize for Inner<S, F> {
+    fn heap_size(&self) -> usize {
+        match self {
+            Inner::Body(state) => state.heap_size(),
+            Inner::Fallback(failure, state) => {
+                failure.message.heap_size() + state.heap_size()
+            }
+        }
+    }
+}
+
+impl<Fallback, FallbackView, Body> Builder<Web>
+    for ErrorBoundary<Fallback, Body>
+where
+    Fallback: FnOnce(&Failure) -> FallbackView,
+    FallbackView: View,
+    Body: View,
+{
+    type State = ErrorBoundaryState<Body::State, FallbackView::State>;
+
+    fn build(self, cx: BuildCx) -> Self::State {
+        let start = marker("!");
+        let end = marker("/!");
+        cx.position.insert(&start);
+        cx.position.insert(&end);
+
+        let mut state = ErrorBoundaryState {
+            inner: None,
+            start,
+            end,
+            retry: Retry {
+                requested: Rc::new(Cell::new(false)),
+                waker: cx.position.waker.clone(),
+            },
+        };
+        state.build(cx.position.parent, self.fallback, self.body);
+
+        state
+    }
+
+    fn rebuild(self, cx: RebuildCx, state: &mut Self::State) {
+        match &mut state.inner {
+            Some(Inner::Body(body)) => {
+                let result = catch_unwind(AssertUnwindSafe(|| {
+                    self.body.rebuild(cx, body)
+                }));
+
+                if let Err(panic) = result {
+                    state.fail(cx.parent, self.fallback, panic_message(&*panic))
+                }
+            }
+            Some(Inner::Fallback(_, _))
+                if state.retry.requested.replace(false) =>
+            {
+                state.build(cx.parent, self.fallback, self.body)
+            }
+            Some(Inner::Fallback(failure, fallback)) => {
+                (self.fallback)(failure).rebuild(cx, fallback)
+            }
+            None => unreachable!(),
+        }
+    }
+}
+
+impl<Fallback, Body: Builder<Ssr>> Builder<Ssr>
+    for ErrorBoundary<Fallback, Body>
+{
+    type State = Body::State;
+
+    fn build(self, cx: SsrCx) -> Self::State {
+        cx.comment("!");
+        let state = self.body.build(cx);
+        cx.comment("/!");
+
+        state
+    }
+
+    fn rebuild(self, cx: Infallible, _: &mut Self::State) {
+        match cx {}
+    }
+}
+
+/// The state of an [`ErrorBoundary`].
+pub struct ErrorBoundaryState<S, F> {
+    inner: Option<Inner<S, F>>,
+    start: web_sys::CharacterData,
+    end: web_sys::CharacterData,
+    retry: Retry,
+}
+
+impl<S, F> ErrorBoundaryState<S, F> {
+    fn cx<'a>(&'a self, parent: &'a web_sys::Element) -> BuildCx<'a> {
+        BuildCx {
+            position: Position {
+                parent,
+                insert_before: &self.end,
+                waker: &self.retry.waker,
+            },
+        }
+    }
+
+    /// Clears any previous content, then builds `body`, or `fallback` if that
+    /// panics.
+    fn build<Fallback, FallbackView, Body>(
+        &mut self,
+        parent: &web_sys::Element,
+        fallback: Fallback,
+        body: Body,
+    ) where
+        Fallback: FnOnce(&Failure) -> FallbackView,
+        FallbackView: Builder<Web, State = F>,
+        Body: Builder<Web, State = S>,
+    {
+        self.clear(parent);
+
+        let cx = self.cx(parent);
+        match catch_unwind(AssertUnwindSafe(|| body.build(cx))) {
+            Ok(body) => self.inner = Some(Inner::Body(body)),
+            Err(panic) => self.fail(parent, fallback, panic_message(&*panic)),
+        }
+    }
+
+    /// Replaces any previous content with `fallback`.
+    fn fail<Fallback, FallbackView>(
+        &mut self,
+        parent: &web_sys::Element,
+        fallback: Fallback,
+        message: Option<&str>,
+    ) where
+        Fallback: FnOnce(&Failure) -> FallbackView,
+        FallbackView: Builder<Web, State = F>,
+    {
+        let failure = Failure {
+            message: message.unwrap_or("the component panicked").to_string(),
+            retry: self.retry.clone(),
+        };
+
+        self.clear(parent);
+
+        let state = fallback(&failure).build(self.cx(parent));
+        self.inner = Some(Inner::Fallback(failure, state));
+    }
+
+    fn clear(&mut self, parent: &web_sys::Element) {
+        // The state may be inconsistent after a panic, so dropping it could
+        // panic too.
+        let _ = catch_unwind(AssertUnwindSafe(|| self.inner = None));
+        clear(parent, &self.start, &self.end);
+    }
+}
+
+impl<S, F, Output> State<Output> for ErrorBoundaryState<S, F>
+where
+    S: State<Output>,
+    F: State<Output>,
+{
+    fn run(&mut self, output: &mut Output) {
+        match &mut self.inner {
+            Some(Inner::Body(state)) => state.run(output),
+            Some(Inner::Fallback(_, state)) => state.run(output),
+            None => {}
+        }
+    }
+}
+
+impl<S: MemSize, F: MemSize> MemSize for ErrorBoundaryState<S, F> {
+    fn heap_size(&self) -> usize {
+        self.inner.heap_size()
+    }
+}
+
+impl<S, F> ViewMarker for ErrorBoundaryState<S, F> {}
@@ -2,11 +2,10 @@ use std::{
     cmp::Ordering, collections::BTreeMap, marker::PhantomData, ops::Bound,
 };
 
-use ravel::{with, State, Token};
-use web_sys::wasm_bindgen::UnwrapThrowExt;
+use ravel::{with, MemSize, State, Token};
 
 use crate::{
-    dom::{clear, Position},
+    dom::{clear, marker, remove, Position},
     BuildCx, Builder, Cx, RebuildCx, Web,
 };
 
@@ -24,29 +23,32 @@ where
     type State = BTreeMapState<K, S>;
 
     fn build(self, cx: BuildCx) -> Self::State {
-        let data = self
-            .data
-            .iter()
-            .map(|(k, v)| {
-                let header =
-                    web_sys::Comment::new_with_data("|").unwrap_throw();
-                cx.position.insert(&header);
-
-                (
-                    k.clone(),
-                    Entry {
-                        header,
-                        state: with(|cx| (self.render_item)(cx, k, v))
-                            .build(cx),
-                    },
-                )
-            })
-            .collect();
+        cx.position.batch(|position| {
+            let cx = BuildCx { position };
 
-        let footer = web_sys::Comment::new_with_data("|").unwrap_throw();
-        cx.position.insert(&footer);
+            let data = self
+                .data
+                .iter()
+                .map(|(k, v)| {
+                    let header = marker("|");
+                    cx.position.insert(&header);
+
+                    (
+                        k.clone(),
+                        Entry {
+                            header,
+                            state: with(|cx| (self.render_item)(cx, k, v))
+                                .build(cx),
+                        },
+                    )
+                })
+                .collect();
 
-        BTreeMapState { data, footer }
+            let footer = marker("|");
+            cx.position.insert(&footer);
+
+            BTreeMapState { data, footer }
+        })
     }
 
     fn rebuild(self, cx: RebuildCx, state: &mut Self::State) {
@@ -55,35 +57,20 @@ where
         let mut existing = sdata.iter_mut().peekable();
 
         let mut add = vec![];
-        let mut remove = vec![];
+        let mut removed = vec![];
 
         loop {
             match (source.peek(), existing.peek()) {
                 (None, None) => break,
                 (None, Some((_, _))) => {
                     let (k, _) = existing.next().unwrap();
-                    remove.push(k.clone());
+                    removed.push(k.clone());
                 }
                 (Some((_, _)), None) => {
                     let (k, v) = source.next().unwrap();
-
-                    let position = Position {
-                        parent: cx.parent,
-                        insert_before: &state.footer,
-                        waker: cx.waker,
-                    };
-
-                    let header =
-                        web_sys::Comment::new_with_data("|").unwrap_throw();
-                    position.insert(&header);
-
                     add.push((
                         k.clone(),
-                        Entry {
-                            header,
-                            state: with(|cx| (self.render_item)(cx, k, v))
-                                .build(BuildCx { position }),
-                        },
+                        build_entry(&self.render_item, cx, &state.footer, k, v),
                     ));
                 }
                 (Some((sk, _)), Some((ek, _))) => match sk.cmp(ek) {
@@ -93,17 +80,30 @@ where
                         with(|cx| (self.render_item)(cx, sk, sv))
                             .rebuild(cx, &mut e.state)
                     }
-                    Ordering::Less => todo!(),
+                    Ordering::Less => {
+                        let (sk, sv) = source.next().unwrap();
+                        let (_, e) = existing.peek().unwrap();
+                        add.push((
+                            sk.clone(),
+                            build_entry(
+                                &self.render_item,
+                                cx,
+                                &e.header,
+                                sk,
+                                sv,
+                            ),
+                        ));
+                    }
                     Ordering::Greater => {
                         let (ek, _) = existing.next().unwrap();
-                        remove.push(ek.clone());
+                        removed.push(ek.clone());
                     }
                 },
             }
         }
 
         sdata.extend(add);
-        for k in remove {
+        for k in removed {
             let e = sdata.remove(&k).unwrap();
             let end = match sdata
                 .range((Bound::Excluded(&k), Bound::Unbounded))
@@ -114,14 +114,41 @@ where
             };
 
             clear(cx.parent, &e.header, end);
-            cx.parent.remove_child(&e.header).unwrap_throw();
+            remove(cx.parent, &e.header);
         }
     }
 }
 
+/// Builds a new entry, inserting it before `insert_before`.
+fn build_entry<K, V, RenderItem, S>(
+    render_item: &RenderItem,
+    cx: RebuildCx,
+    insert_before: &web_sys::Node,
+    k: &K,
+    v: &V,
+) -> Entry<S>
+where
+    RenderItem: Fn(Cx<S, Web>, &K, &V) -> Token<S>,
+    S: 'static,
+{
+    let position = Position {
+        parent: cx.parent,
+        insert_before,
+        waker: cx.waker,
+    };
+
+    let header = marker("|");
+    position.insert(&header);
+
+    Entry {
+        header,
+        state: with(|cx| render_item(cx, k, v)).build(BuildCx { position }),
+    }
+}
+
 pub struct BTreeMapState<K, S> {
     data: BTreeMap<K, Entry<S>>,
-    footer: web_sys::Comment,
+    footer: web_sys::CharacterData,
 }
 
 impl<K: 'static + Ord, S, Output> State<Output> for BTreeMapState<K, S>
@@ -135,15 +162,26 @@ where
     }
 }
 
+impl<K: MemSize, S: MemSize> MemSize for BTreeMapState<K, S> {
+    fn heap_size(&self) -> usize {
+        self.data
+            .iter()
+            .map(|(k, e)| {
+                k.mem_size() + size_of::<Entry<S>>() + e.state.heap_size()
+            })
+            .sum()
+    }
+}
+
 struct Entry<S> {
-    header: web_sys::Comment,
+    header: web_sys::CharacterData,
     state: S,
 }
 
 pub fn btree_map<K: Ord, V, RenderItem, S>(
     data: &BTreeMap<K, V>,
     render_item: RenderItem,
-) -> BTreeMapBuilder<K, V, RenderItem, S>
+) -> BTreeMapBuilder<'_, K, V, RenderItem, S>
 where
     RenderItem: Fn(Cx<S, Web>, &K, &V) -> Token<S>,
 {
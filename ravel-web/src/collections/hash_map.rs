@@ -0,0 +1,124 @@
+use std::{
+    cmp::Ordering, collections::HashMap, hash::Hash, marker::PhantomData,
+};
+
+use ravel::Token;
+
+use crate::{
+    collections::keyed::{keyed, KeyedState},
+    BuildCx, Builder, Cx, RebuildCx, Web,
+};
+
+/// The order of the entries of a [`HashMapBuilder`].
+pub trait EntryOrder<K, V> {
+    /// Compares two entries.
+    fn cmp(&self, a: (&K, &V), b: (&K, &V)) -> Ordering;
+}
+
+impl<K, V, F: Fn((&K, &V), (&K, &V)) -> Ordering> EntryOrder<K, V> for F {
+    fn cmp(&self, a: (&K, &V), b: (&K, &V)) -> Ordering {
+        self(a, b)
+    }
+}
+
+/// Orders entries by their keys, for [`hash_map`].
+#[derive(Copy, Clone, Debug, Default)]
+pub struct ByKey;
+
+impl<K: Ord, V> EntryOrder<K, V> for ByKey {
+    fn cmp(&self, (a, _): (&K, &V), (b, _): (&K, &V)) -> Ordering {
+        a.cmp(b)
+    }
+}
+
+pub struct HashMapBuilder<'data, K, V, Order, RenderItem, S> {
+    data: &'data HashMap<K, V>,
+    order: Order,
+    render_item: RenderItem,
+    phantom: PhantomData<S>,
+}
+
+impl<'data, K, V, Order, RenderItem, S>
+    HashMapBuilder<'data, K, V, Order, RenderItem, S>
+where
+    Order: EntryOrder<K, V>,
+{
+    /// The entries of the map, in the order they are rendered.
+    fn entries(&self) -> Vec<(&'data K, &'data V)> {
+        let mut entries: Vec<_> = self.data.iter().collect();
+        entries.sort_by(|a, b| self.order.cmp(*a, *b));
+        entries
+    }
+}
+
+impl<K, V, Order, RenderItem, S: 'static> Builder<Web>
+    for HashMapBuilder<'_, K, V, Order, RenderItem, S>
+where
+    K: 'static + Clone + Hash + Eq,
+    Order: EntryOrder<K, V>,
+    RenderItem: Fn(Cx<S, Web>, &K, &V) -> Token<S>,
+{
+    type State = KeyedState<K, S>;
+
+    fn build(self, cx: BuildCx) -> Self::State {
+        let render_item = &self.render_item;
+        keyed(
+            self.entries(),
+            |(k, _)| K::clone(k),
+            |cx, (k, v)| render_item(cx, k, v),
+        )
+        .build(cx)
+    }
+
+    fn rebuild(self, cx: RebuildCx, state: &mut Self::State) {
+        let render_item = &self.render_item;
+        keyed(
+            self.entries(),
+            |(k, _)| K::clone(k),
+            |cx, (k, v)| render_item(cx, k, v),
+        )
+        .rebuild(cx, state)
+    }
+}
+
+/// A view of a [`HashMap`], with its entries in the order of their keys.
+///
+/// Since hash maps have no order of their own, entries are sorted on every
+/// build and rebuild, then reconciled like [`keyed`]. Use [`hash_map_by`] for
+/// an order other than the keys'.
+pub fn hash_map<K: Ord, V, RenderItem, S>(
+    data: &HashMap<K, V>,
+    render_item: RenderItem,
+) -> HashMapBuilder<'_, K, V, ByKey, RenderItem, S>
+where
+    RenderItem: Fn(Cx<S, Web>, &K, &V) -> Token<S>,
+{
+    HashMapBuilder {
+        data,
+        order: ByKey,
+        render_item,
+        phantom: PhantomData,
+    }
+}
+
+/// A view of a [`HashMap`], with its entries sorted by `order`, which compares
+/// two `(key, value)` pairs.
+///
+/// Entries which `order` considers equal are rendered in an unspecified order,
+/// which may change whenever the map does.
+pub fn hash_map_by<K, V, Order, RenderItem, S>(
+    data: &HashMap<K, V>,
+    order: Order,
+    render_item: RenderItem,
+) -> HashMapBuilder<'_, K, V, Order, RenderItem, S>
+where
+    Order: Fn((&K, &V), (&K, &V)) -> Ordering,
+    RenderItem: Fn(Cx<S, Web>, &K, &V) -> Token<S>,
+{
+    HashMapBuilder {
+        data,
+        order,
+        render_item,
+        phantom: PhantomData,
+    }
+}
@@ -0,0 +1,62 @@
+use std::{hash::Hash, marker::PhantomData};
+
+use indexmap::IndexMap;
+use ravel::Token;
+
+use crate::{
+    collections::keyed::{keyed, KeyedState},
+    BuildCx, Builder, Cx, RebuildCx, Web,
+};
+
+pub struct IndexMapBuilder<'data, K, V, RenderItem, S> {
+    data: &'data IndexMap<K, V>,
+    render_item: RenderItem,
+    phantom: PhantomData<S>,
+}
+
+impl<K, V, RenderItem, S: 'static> Builder<Web>
+    for IndexMapBuilder<'_, K, V, RenderItem, S>
+where
+    K: 'static + Clone + Hash + Eq,
+    RenderItem: Fn(Cx<S, Web>, &K, &V) -> Token<S>,
+{
+    type State = KeyedState<K, S>;
+
+    fn build(self, cx: BuildCx) -> Self::State {
+        let render_item = &self.render_item;
+        keyed(
+            self.data,
+            |(k, _)| K::clone(k),
+            |cx, (k, v)| render_item(cx, k, v),
+        )
+        .build(cx)
+    }
+
+    fn rebuild(self, cx: RebuildCx, state: &mut Self::State) {
+        let render_item = &self.render_item;
+        keyed(
+            self.data,
+            |(k, _)| K::clone(k),
+            |cx, (k, v)| render_item(cx, k, v),
+        )
+        .rebuild(cx, state)
+    }
+}
+
+/// A view of an [`IndexMap`], with its entries in the map's order.
+///
+/// Entries are reconciled like [`keyed`], so reordering the map moves the
+/// existing entries rather than rebuilding them.
+pub fn index_map<K, V, RenderItem, S>(
+    data: &IndexMap<K, V>,
+    render_item: RenderItem,
+) -> IndexMapBuilder<'_, K, V, RenderItem, S>
+where
+    RenderItem: Fn(Cx<S, Web>, &K, &V) -> Token<S>,
+{
+    IndexMapBuilder {
+        data,
+        render_item,
+        phantom: PhantomData,
+    }
+}
@@ -1,10 +1,9 @@
 use std::{iter::once, marker::PhantomData};
 
-use ravel::{with, State, Token};
-use web_sys::wasm_bindgen::UnwrapThrowExt;
+use ravel::{with, MemSize, State, Token};
 
 use crate::{
-    dom::{clear, Position},
+    dom::{clear, marker, Position},
     BuildCx, Builder, Cx, RebuildCx, Web,
 };
 
@@ -22,25 +21,29 @@ where
     type State = IterState<S>;
 
     fn build(self, cx: BuildCx) -> Self::State {
-        let data = self
-            .iter
-            .enumerate()
-            .map(|(i, v)| {
-                let header =
-                    web_sys::Comment::new_with_data("|").unwrap_throw();
-                cx.position.insert(&header);
-
-                Entry {
-                    header,
-                    state: with(|cx| (self.render_item)(cx, i, v)).build(cx),
-                }
-            })
-            .collect();
-
-        let footer = web_sys::Comment::new_with_data("|").unwrap_throw();
-        cx.position.insert(&footer);
-
-        IterState { data, footer }
+        cx.position.batch(|position| {
+            let cx = BuildCx { position };
+
+            let data = self
+                .iter
+                .enumerate()
+                .map(|(i, v)| {
+                    let header = marker("|");
+                    cx.position.insert(&header);
+
+                    Entry {
+                        header,
+                        state: with(|cx| (self.render_item)(cx, i, v))
+                            .build(cx),
+                    }
+                })
+                .collect();
+
+            let footer = marker("|");
+            cx.position.insert(&footer);
+
+            IterState { data, footer }
+        })
     }
 
     fn rebuild(mut self, cx: RebuildCx, state: &mut Self::State) {
@@ -62,8 +65,7 @@ where
                             waker: cx.waker,
                         };
 
-                        let header =
-                            web_sys::Comment::new_with_data("|").unwrap_throw();
+                        let header = marker("|");
                         position.insert(&header);
 
                         Entry {
@@ -85,7 +87,7 @@ where
 
 pub struct IterState<S> {
     data: Vec<Entry<S>>,
-    footer: web_sys::Comment,
+    footer: web_sys::CharacterData,
 }
 
 impl<S, Output> State<Output> for IterState<S>
@@ -99,8 +101,15 @@ where
     }
 }
 
+impl<S: MemSize> MemSize for IterState<S> {
+    fn heap_size(&self) -> usize {
+        self.data.capacity() * size_of::<Entry<S>>()
+            + self.data.iter().map(|e| e.state.heap_size()).sum::<usize>()
+    }
+}
+
 struct Entry<S> {
-    header: web_sys::Comment,
+    header: web_sys::CharacterData,
     state: S,
 }
 
@@ -0,0 +1,523 @@
+use std::{collections::HashMap, hash::Hash, marker::PhantomData};
+
+use ravel::{with, MemSize, State, Token};
+use web_sys::wasm_bindgen::UnwrapThrowExt;
+
+use crate::{
+    dom::{clear, marker, remove, Position},
+    BuildCx, Builder, Cx, RebuildCx, Web,
+};
+
+pub struct KeyedBuilder<I, Key, RenderItem, S> {
+    iter: I,
+    key: Key,
+    render_item: RenderItem,
+    phantom: PhantomData<S>,
+}
+
+/// The position of an entry in a [`keyed`] collection, as passed to the
+/// render callback of [`keyed_with_meta`].
+#[derive(Debug)]
+pub struct EntryMeta<'a, K> {
+    index: usize,
+    len: usize,
+    previous: Option<&'a K>,
+}
+
+impl<K> Clone for EntryMeta<'_, K> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<K> Copy for EntryMeta<'_, K> {}
+
+impl<'a, K> EntryMeta<'a, K> {
+    /// The index of the entry in the collection.
+    pub fn index(&self) -> usize {
+        self.index
+    }
+
+    /// Whether this is the first entry.
+    pub fn is_first(&self) -> bool {
+        self.index == 0
+    }
+
+    /// Whether this is the last entry.
+    pub fn is_last(&self) -> bool {
+        self.index + 1 == self.len
+    }
+
+    /// The key of the previous entry, unless this is the first one.
+    pub fn previous_key(&self) -> Option<&'a K> {
+        self.previous
+    }
+}
+
+/// A render callback for a [`KeyedBuilder`].
+pub trait RenderEntry<K, T, S> {
+    fn render(&self, cx: Cx<S, Web>, meta: EntryMeta<K>, item: T) -> Token<S>;
+}
+
+/// A render callback which ignores the [`EntryMeta`], from [`keyed`].
+pub struct Plain<F>(F);
+
+impl<K, T, S, F> RenderEntry<K, T, S> for Plain<F>
+where
+    F: Fn(Cx<S, Web>, T) -> Token<S>,
+{
+    fn render(&self, cx: Cx<S, Web>, _: EntryMeta<K>, item: T) -> Token<S> {
+        (self.0)(cx, item)
+    }
+}
+
+/// A render callback which receives the [`EntryMeta`], from
+/// [`keyed_with_meta`].
+pub struct WithMeta<F>(F);
+
+impl<K, T, S, F> RenderEntry<K, T, S> for WithMeta<F>
+where
+    F: Fn(Cx<S, Web>, EntryMeta<K>, T) -> Token<S>,
+{
+    fn render(&self, cx: Cx<S, Web>, meta: EntryMeta<K>, item: T) -> Token<S> {
+        (self.0)(cx, meta, item)
+    }
+}
+
+impl<I: Iterator, K, Key, RenderItem, S: 'static> Builder<Web>
+    for KeyedBuilder<I, Key, RenderItem, S>
+where
+    K: 'static + Hash + Eq,
+    Key: Fn(&I::Item) -> K,
+    RenderItem: RenderEntry<K, I::Item, S>,
+{
+    type State = KeyedState<K, S>;
+
+    fn build(self, cx: BuildCx) -> Self::State {
+        cx.position.batch(|position| {
+            let cx = BuildCx { position };
+
+            let items: Vec<(K, I::Item)> =
+                self.iter.map(|v| ((self.key)(&v), v)).collect();
+            let len = items.len();
+
+            let mut data: Vec<Entry<K, S>> = Vec::with_capacity(len);
+            for (index, (key, v)) in items.into_iter().enumerate() {
+                let header = marker("|");
+                cx.position.insert(&header);
+
+                let meta = EntryMeta {
+                    index,
+                    len,
+                    previous: data.last().map(|e| &e.key),
+                };
+                let state =
+                    with(|cx| self.render_item.render(cx, meta, v)).build(cx);
+
+                data.push(Entry { key, header, state });
+            }
+
+            let footer = marker("|");
+            cx.position.insert(&footer);
+
+            KeyedState { data, footer }
+        })
+    }
+
+    fn rebuild(self, cx: RebuildCx, state: &mut Self::State) {
+        let items: Vec<(K, I::Item)> =
+            self.iter.map(|v| ((self.key)(&v), v)).collect();
+        let len = items.len();
+
+        let mut old: Vec<Option<Entry<K, S>>> = std::mem::take(&mut state.data)
+            .into_iter()
+            .map(Some)
+            .collect();
+
+        // For each new item, the index of the existing entry with the same key.
+        let sources: Vec<Option<usize>> = {
+            let mut index: HashMap<&K, usize> = old
+                .iter()
+                .enumerate()
+                .map(|(i, e)| (&e.as_ref().unwrap().key, i))
+                .collect();
+
+            items.iter().map(|(k, _)| index.remove(k)).collect()
+        };
+
+        let mut used = vec![false; old.len()];
+        for i in sources.iter().flatten() {
+            used[*i] = true;
+        }
+
+        // Remove entries which no longer exist.
+        for i in 0..old.len() {
+            if used[i] {
+                continue;
+            }
+
+            let end = match old[i + 1..].iter().flatten().next() {
+                Some(e) => &e.header,
+                None => &state.footer,
+            };
+
+            let e = old[i].as_ref().unwrap();
+            clear(cx.parent, &e.header, end);
+            remove(cx.parent, &e.header);
+        }
+
+        // Entries which are part of the longest run already in the correct
+        // relative order stay in place. All others are moved.
+        let reused: Vec<usize> = sources.iter().flatten().copied().collect();
+        let mut stay = vec![false; old.len()];
+        for (i, keep) in longest_increasing_subsequence(&reused)
+            .into_iter()
+            .enumerate()
+        {
+            stay[reused[i]] = keep;
+        }
+
+        // The header of each existing entry, to find where the nodes of the
+        // entries before them end once they have been rebuilt.
+        let headers: Vec<web_sys::Node> = old
+            .iter()
+            .map(|e| e.as_ref().unwrap().header.clone().into())
+            .collect();
+
+        // Rebuild existing entries in place, in order. This must happen before
+        // their nodes are collected for moving, since it may add or remove
+        // nodes.
+        let mut placed = Vec::with_capacity(len);
+        for (index, ((k, v), source)) in
+            items.into_iter().zip(&sources).enumerate()
+        {
+            let meta = EntryMeta {
+                index,
+                len,
+                previous: placed.last().map(placed_key),
+            };
+            let entry = match source {
+                Some(i) => {
+                    let mut e = old[*i].take().unwrap();
+                    with(|cx| self.render_item.render(cx, meta, v))
+                        .rebuild(cx, &mut e.state);
+                    e.key = k;
+                    Ok(e)
+                }
+                None => Err((k, v)),
+            };
+            placed.push(entry);
+        }
+
+        // Collect the nodes of moving entries, before any are moved.
+        let mut moving: Vec<Vec<web_sys::Node>> = vec![vec![]; headers.len()];
+        let mut surviving = (0..headers.len()).filter(|i| used[*i]).peekable();
+        while let Some(i) = surviving.next() {
+            if stay[i] {
+                continue;
+            }
+
+            let end: &web_sys::Node = match surviving.peek() {
+                Some(j) => &headers[*j],
+                None => &state.footer,
+            };
+
+            let mut node = headers[i].clone();
+            while &node != end {
+                let next = node.next_sibling().unwrap_throw();
+                moving[i].push(node);
+                node = next;
+            }
+        }
+
+        // Place entries from back to front, building new ones.
+        let mut next: web_sys::Node = state.footer.clone().into();
+        let mut data = Vec::with_capacity(len);
+        let mut sources = sources;
+        while let (Some(entry), Some(source)) = (placed.pop(), sources.pop()) {
+            let e = match entry {
+                Ok(e) => {
+                    let i = source.unwrap();
+                    for node in &moving[i] {
+                        cx.parent
+                            .insert_before(node, Some(&next))
+                            .unwrap_throw();
+                    }
+                    e
+                }
+                Err((key, v)) => {
+                    let position = Position {
+                        parent: cx.parent,
+                        insert_before: &next,
+                        waker: cx.waker,
+                    };
+
+                    let header = marker("|");
+                    position.insert(&header);
+
+                    let meta = EntryMeta {
+                        index: placed.len(),
+                        len,
+                        previous: placed.last().map(placed_key),
+                    };
+                    let state = with(|cx| self.render_item.render(cx, meta, v))
+                        .build(BuildCx { position });
+
+                    Entry { key, header, state }
+                }
+            };
+
+            next = e.header.clone().into();
+            data.push(e);
+        }
+
+        data.reverse();
+        state.data = data;
+    }
+}
+
+/// The key of an entry being placed by [`KeyedBuilder::rebuild`].
+fn placed_key<K, S, T>(entry: &Result<Entry<K, S>, (K, T)>) -> &K {
+    match entry {
+        Ok(e) => &e.key,
+        Err((k, _)) => k,
+    }
+}
+
+/// Marks the elements of a longest strictly increasing subsequence.
+fn longest_increasing_subsequence(seq: &[usize]) -> Vec<bool> {
+    let mut tails: Vec<usize> = Vec::new();
+    let mut prev: Vec<Option<usize>> = vec![None; seq.len()];
+
+    for i in 0..seq.len() {
+        let pos = tails.partition_point(|&t| seq[t] < seq[i]);
+        if pos > 0 {
+            prev[i] = Some(tails[pos - 1]);
+        }
+
+        if pos == tails.len() {
+            tails.push(i);
+        } else {
+            tails[pos] = i;
+        }
+    }
+
+    let mut keep = vec![false; seq.len()];
+    let mut i = tails.last().copied();
+    while let Some(j) = i {
+        keep[j] = true;
+        i = prev[j];
+    }
+
+    keep
+}
+
+pub struct KeyedState<K, S> {
+    data: Vec<Entry<K, S>>,
+    footer: web_sys::CharacterData,
+}
+
+impl<K: 'static, S, Output> State<Output> for KeyedState<K, S>
+where
+    S: State<Output>,
+{
+    fn run(&mut self, output: &mut Output) {
+        for entry in self.data.iter_mut() {
+            entry.state.run(output);
+        }
+    }
+}
+
+impl<K: MemSize, S: MemSize> MemSize for KeyedState<K, S> {
+    fn heap_size(&self) -> usize {
+        self.data.capacity() * size_of::<Entry<K, S>>()
+            + self
+                .data
+                .iter()
+                .map(|e| e.key.heap_size() + e.state.heap_size())
+                .sum::<usize>()
+    }
+}
+
+struct Entry<K, S> {
+    key: K,
+    header: web_sys::CharacterData,
+    state: S,
+}
+
+/// A collection whose entries are identified by a key, rather than their
+/// position.
+///
+/// When items are inserted, removed or reordered, the existing state and DOM
+/// nodes of each entry are reused according to its key, with a minimal number
+/// of DOM moves. Keys should be unique; an entry with a duplicate key is
+/// rebuilt from scratch.
+pub fn keyed<I: IntoIterator, K, Key, RenderItem, S>(
+    iter: I,
+    key: Key,
+    render_item: RenderItem,
+) -> KeyedBuilder<I::IntoIter, Key, Plain<RenderItem>, S>
+where
+    K: Hash + Eq,
+    Key: Fn(&I::Item) -> K,
+    RenderItem: Fn(Cx<S, Web>, I::Item) -> Token<S>,
+{
+    KeyedBuilder {
+        iter: iter.into_iter(),
+        key,
+        render_item: Plain(render_item),
+        phantom: PhantomData,
+    }
+}
+
+/// Like [`keyed`], but `render_item` also receives the [`EntryMeta`] of each
+/// entry, for separators, zebra striping and the like.
+///
+/// The metadata is recomputed by every rebuild, so it is always current, and
+/// is passed separately from the item, so a [`memo`](ravel::memo) over the
+/// item alone isn't invalidated when only its position changes.
+pub fn keyed_with_meta<I: IntoIterator, K, Key, RenderItem, S>(
+    iter: I,
+    key: Key,
+    render_item: RenderItem,
+) -> KeyedBuilder<I::IntoIter, Key, WithMeta<RenderItem>, S>
+where
+    K: Hash + Eq,
+    Key: Fn(&I::Item) -> K,
+    RenderItem: Fn(Cx<S, Web>, EntryMeta<K>, I::Item) -> Token<S>,
+{
+    KeyedBuilder {
+        iter: iter.into_iter(),
+        key,
+        render_item: WithMeta(render_item),
+        phantom: PhantomData,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::longest_increasing_subsequence;
+
+    fn kept(seq: &[usize]) -> Vec<usize> {
+        seq.iter()
+            .zip(longest_increasing_subsequence(seq))
+            .filter_map(|(&v, keep)| keep.then_some(v))
+            .collect()
+    }
+
+    #[test]
+    fn lis_empty() {
+        assert!(kept(&[]).is_empty());
+    }
+
+    #[test]
+    fn lis_sorted_keeps_everything() {
+        assert_eq!(kept(&[0, 1, 2, 3]), [0, 1, 2, 3]);
+    }
+
+    #[test]
+    fn lis_reversed_keeps_one() {
+        assert_eq!(kept(&[3, 2, 1, 0]).len(), 1);
+    }
+
+    #[test]
+    fn lis_single_move() {
+        // The last entry moved to the front.
+        assert_eq!(kept(&[3, 0, 1, 2]), [0, 1, 2]);
+        // The first entry moved to the back.
+        assert_eq!(kept(&[1, 2, 3, 0]), [1, 2, 3]);
+    }
+
+    #[test]
+    fn lis_swap() {
+        assert_eq!(kept(&[0, 3, 2, 1, 4]).len(), 3);
+        assert_eq!(kept(&[2, 0, 1, 3]), [0, 1, 3]);
+    }
+
+    #[test]
+    fn lis_is_increasing_and_longest() {
+        let seq = [5, 1, 6, 2, 7, 3, 8, 0, 4];
+        let kept = kept(&seq);
+        assert!(kept.windows(2).all(|w| w[0] < w[1]));
+        assert_eq!(kept.len(), 4);
+    }
+
+    #[cfg(target_arch = "wasm32")]
+    mod dom {
+        use std::sync::Arc;
+
+        use atomic_waker::AtomicWaker;
+        use ravel::Builder;
+        use wasm_bindgen_test::{
+            wasm_bindgen_test, wasm_bindgen_test_configure,
+        };
+        use web_sys::wasm_bindgen::{JsValue, UnwrapThrowExt as _};
+
+        use crate::{collections::keyed, BuildCx, Position, RebuildCx};
+
+        wasm_bindgen_test_configure!(run_in_browser);
+
+        /// Builds entries `(key, extra)`, each showing its key followed by
+        /// `!` if `extra`, then rebuilds them as `after`, and returns the
+        /// resulting text.
+        fn rebuild_text(
+            before: Vec<(&'static str, bool)>,
+            after: Vec<(&'static str, bool)>,
+        ) -> String {
+            let parent =
+                gloo_utils::document().create_element("div").unwrap_throw();
+            let waker = Arc::new(AtomicWaker::new());
+            let view = |entries: Vec<(&'static str, bool)>| {
+                keyed(
+                    entries,
+                    |(key, _)| *key,
+                    |cx, (key, extra)| cx.build((key, extra.then_some("!"))),
+                )
+            };
+
+            let mut state = view(before).build(BuildCx {
+                position: Position {
+                    parent: &parent,
+                    insert_before: &JsValue::NULL.into(),
+                    waker: &waker,
+                },
+            });
+            view(after).rebuild(
+                RebuildCx {
+                    parent: &parent,
+                    waker: &waker,
+                },
+                &mut state,
+            );
+
+            parent.text_content().unwrap_or_default()
+        }
+
+        #[wasm_bindgen_test]
+        fn moved_entry_gains_nodes() {
+            let text = rebuild_text(
+                vec![("a", false), ("b", false), ("c", false)],
+                vec![("c", true), ("a", false), ("b", false)],
+            );
+            assert_eq!(text, "c!ab");
+        }
+
+        #[wasm_bindgen_test]
+        fn moved_entry_loses_nodes() {
+            let text = rebuild_text(
+                vec![("a", true), ("b", false), ("c", false)],
+                vec![("c", false), ("b", false), ("a", false)],
+            );
+            assert_eq!(text, "cba");
+        }
+
+        #[wasm_bindgen_test]
+        fn staying_entry_changes_around_moves() {
+            let text = rebuild_text(
+                vec![("a", false), ("b", false), ("c", true), ("d", false)],
+                vec![("d", true), ("b", true), ("c", false), ("a", true)],
+            );
+            assert_eq!(text, "d!b!ca!");
+        }
+    }
+}
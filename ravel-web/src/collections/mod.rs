@@ -1,7 +1,17 @@
 //! Views over dynamically sized collections.
 
 pub mod btree_map;
+pub mod hash_map;
+#[cfg(feature = "indexmap")]
+pub mod index_map;
 pub mod iter;
+pub mod keyed;
+pub mod paged;
 
 pub use btree_map::btree_map;
+pub use hash_map::{hash_map, hash_map_by};
+#[cfg(feature = "indexmap")]
+pub use index_map::index_map;
 pub use iter::iter;
+pub use keyed::{keyed, keyed_with_meta};
+pub use paged::{page_count, paged};
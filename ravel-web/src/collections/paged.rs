@@ -0,0 +1,205 @@
+use std::{collections::HashMap, hash::Hash, marker::PhantomData};
+
+use ravel::{MemSize, State, Token};
+
+use crate::{
+    collections::keyed::{keyed, KeyedState},
+    BuildCx, Builder, Cx, RebuildCx, Web,
+};
+
+pub struct PagedBuilder<'data, T, Key, RenderItem, S> {
+    data: &'data [T],
+    page_size: usize,
+    page_index: usize,
+    key: Key,
+    render_item: RenderItem,
+    phantom: PhantomData<S>,
+}
+
+impl<'data, T, Key, RenderItem, S> PagedBuilder<'data, T, Key, RenderItem, S> {
+    /// The items of the current page.
+    fn page(&self) -> &'data [T] {
+        let start = self.page_index.saturating_mul(self.page_size);
+        let end = start.saturating_add(self.page_size);
+        let len = self.data.len();
+        &self.data[start.min(len)..end.min(len)]
+    }
+}
+
+impl<T, K, Key, RenderItem, S: 'static> Builder<Web>
+    for PagedBuilder<'_, T, Key, RenderItem, S>
+where
+    K: 'static + Hash + Eq,
+    Key: Fn(&T) -> K,
+    RenderItem: Fn(Cx<S, Web>, &T) -> Token<S>,
+{
+    type State = PagedState<K, S>;
+
+    fn build(self, cx: BuildCx) -> Self::State {
+        let mut slots = Slots::default();
+        let rows = slots.assign(self.page().iter().map(|v| ((self.key)(v), v)));
+
+        let render_item = &self.render_item;
+        let rows =
+            keyed(rows, |(slot, _)| *slot, |cx, (_, v)| render_item(cx, v))
+                .build(cx);
+
+        PagedState { slots, rows }
+    }
+
+    fn rebuild(self, cx: RebuildCx, state: &mut Self::State) {
+        let rows = state
+            .slots
+            .assign(self.page().iter().map(|v| ((self.key)(v), v)));
+
+        let render_item = &self.render_item;
+        keyed(rows, |(slot, _)| *slot, |cx, (_, v)| render_item(cx, v))
+            .rebuild(cx, &mut state.rows)
+    }
+}
+
+/// The rows of a page, identified by slots rather than the keys of their
+/// items, so that rows can be reused for different items.
+struct Slots<K> {
+    /// The slot of each item on the current page.
+    current: HashMap<K, usize>,
+    next: usize,
+}
+
+impl<K> Default for Slots<K> {
+    fn default() -> Self {
+        Self {
+            current: HashMap::new(),
+            next: 0,
+        }
+    }
+}
+
+impl<K: Hash + Eq> Slots<K> {
+    /// Assigns a slot to each of `items`: the same slot as before for a key
+    /// which was already on the page, otherwise one freed by a key which is no
+    /// longer on the page, or a new one.
+    ///
+    /// Keys must be unique among `items`. Otherwise, the slot of the first
+    /// item with a duplicate key is lost, and a new one is allocated on every
+    /// following page.
+    fn assign<T>(
+        &mut self,
+        items: impl Iterator<Item = (K, T)>,
+    ) -> Vec<(usize, T)> {
+        let mut previous = std::mem::take(&mut self.current);
+
+        let items: Vec<(K, Option<usize>, T)> = items
+            .map(|(k, v)| {
+                let slot = previous.remove(&k);
+                (k, slot, v)
+            })
+            .collect();
+
+        let mut free: Vec<usize> = previous.into_values().collect();
+        free.sort_unstable_by(|a, b| b.cmp(a));
+
+        items
+            .into_iter()
+            .map(|(k, slot, v)| {
+                let slot = slot.or_else(|| free.pop()).unwrap_or_else(|| {
+                    self.next += 1;
+                    self.next - 1
+                });
+                let duplicate = self.current.insert(k, slot).is_some();
+                debug_assert!(!duplicate, "duplicate key on a page");
+                (slot, v)
+            })
+            .collect()
+    }
+}
+
+pub struct PagedState<K, S> {
+    slots: Slots<K>,
+    rows: KeyedState<usize, S>,
+}
+
+impl<K: 'static, S, Output> State<Output> for PagedState<K, S>
+where
+    S: State<Output>,
+{
+    fn run(&mut self, output: &mut Output) {
+        self.rows.run(output)
+    }
+}
+
+impl<K: MemSize, S: MemSize> MemSize for PagedState<K, S> {
+    fn heap_size(&self) -> usize {
+        self.slots.current.heap_size() + self.rows.heap_size()
+    }
+}
+
+/// The page at `page_index` (counting from zero) of `data`, split into pages
+/// of `page_size` items.
+///
+/// Rows are reused as the page changes. An item which stays on the page (for
+/// example, when the page size grows) keeps its row, identified by `key` as in
+/// [`keyed`]. Rows of items which left the page are then rebuilt with the
+/// items which joined it, in order, so flipping to a page of entirely new
+/// items updates the existing rows in place rather than replacing them. Any
+/// state local to a row is therefore carried over to a different item; use
+/// [`keyed`] over a slice of the page instead if that is undesirable.
+///
+/// Keys should be unique within a page; this is checked in debug builds.
+///
+/// Pages past the end of `data` are empty.
+pub fn paged<T, K, Key, RenderItem, S>(
+    data: &[T],
+    page_size: usize,
+    page_index: usize,
+    key: Key,
+    render_item: RenderItem,
+) -> PagedBuilder<'_, T, Key, RenderItem, S>
+where
+    K: Hash + Eq,
+    Key: Fn(&T) -> K,
+    RenderItem: Fn(Cx<S, Web>, &T) -> Token<S>,
+{
+    PagedBuilder {
+        data,
+        page_size,
+        page_index,
+        key,
+        render_item,
+        phantom: PhantomData,
+    }
+}
+
+/// The number of pages of `page_size` items needed to show `len` items, for
+/// example to render pagination controls for [`paged`].
+pub fn page_count(len: usize, page_size: usize) -> usize {
+    len.div_ceil(page_size.max(1))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Slots;
+
+    fn assign(slots: &mut Slots<u32>, keys: &[u32]) -> Vec<usize> {
+        let items = keys.iter().map(|&k| (k, ()));
+        slots
+            .assign(items)
+            .into_iter()
+            .map(|(slot, ())| slot)
+            .collect()
+    }
+
+    #[test]
+    fn slots_are_kept_then_reused() {
+        let mut slots = Slots::default();
+        assert_eq!(assign(&mut slots, &[1, 2, 3]), [0, 1, 2]);
+        assert_eq!(assign(&mut slots, &[2, 3, 4]), [1, 2, 0]);
+        assert_eq!(assign(&mut slots, &[5, 6, 7, 8]), [0, 1, 2, 3]);
+    }
+
+    #[test]
+    #[should_panic = "duplicate key"]
+    fn duplicate_keys_are_rejected() {
+        assign(&mut Slots::default(), &[1, 1]);
+    }
+}
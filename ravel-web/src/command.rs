@@ -0,0 +1,66 @@
+//! Commands emitted by event handlers, for the event loop to execute.
+//!
+//! Event handlers can only update the model. Work which must happen outside
+//! of it, such as navigating, or starting an HTTP request, can instead be
+//! described by a command value, which handlers return with
+//! [`on_command`](crate::event::on_command), or pass to [`emit`]. The event
+//! loop of [`run_with_commands`](crate::run::run_with_commands) collects them
+//! and hands them to its executor once the rebuild is done:
+//!
+//! ```ignore
+//! enum Cmd {
+//!     Save(Todo),
+//! }
+//!
+//! event::on_command_(Click, |model: &mut Model| {
+//!     model.saving = true;
+//!     Some(Cmd::Save(model.todo.clone()))
+//! })
+//! ```
+//!
+//! Commands are queued by type, so the event loop only executes commands of
+//! its own command type.
+
+use std::{
+    any::{Any, TypeId},
+    cell::RefCell,
+    collections::HashMap,
+};
+
+thread_local! {
+    /// A `Vec<Cmd>` of pending commands for each command type of a running
+    /// event loop.
+    static QUEUES: RefCell<HashMap<TypeId, Box<dyn Any>>> =
+        RefCell::default();
+}
+
+/// Queues `command` to be executed after the current rebuild.
+///
+/// If no event loop executes commands of this type, the command is dropped.
+pub fn emit<Cmd: 'static>(command: Cmd) {
+    QUEUES.with_borrow_mut(|queues| {
+        if let Some(queue) = queues.get_mut(&TypeId::of::<Cmd>()) {
+            queue.downcast_mut::<Vec<Cmd>>().unwrap().push(command)
+        }
+    })
+}
+
+/// Starts collecting commands of type `Cmd`.
+pub(crate) fn listen<Cmd: 'static>() {
+    QUEUES.with_borrow_mut(|queues| {
+        queues
+            .entry(TypeId::of::<Cmd>())
+            .or_insert_with(|| Box::new(Vec::<Cmd>::new()));
+    })
+}
+
+/// Removes the pending commands of type `Cmd`, in the order they were
+/// emitted.
+pub(crate) fn take<Cmd: 'static>() -> Vec<Cmd> {
+    QUEUES.with_borrow_mut(|queues| {
+        queues
+            .get_mut(&TypeId::of::<Cmd>())
+            .map(|queue| std::mem::take(queue.downcast_mut().unwrap()))
+            .unwrap_or_default()
+    })
+}
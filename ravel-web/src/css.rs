@@ -0,0 +1,426 @@
+//! Scoped stylesheet rules, for styles which can't be expressed with an inline
+//! [`Style`](crate::attr::Style) attribute, such as pseudo-classes, media
+//! queries and animations.
+//!
+//! Rules are added to a single `<style>` element in the document `<head>`, the
+//! first time a component which needs them is built, and are never removed.
+//! Each rule is scoped to a generated attribute or class name, so rules from
+//! different components never interfere with each other.
+
+use std::{
+    cell::RefCell,
+    collections::{hash_map::DefaultHasher, HashMap},
+    convert::Infallible,
+    hash::{Hash, Hasher},
+};
+
+use ravel::Builder;
+use web_sys::wasm_bindgen::UnwrapThrowExt as _;
+
+use crate::{
+    ssr::{Ssr, SsrCx},
+    stats, BuildCx, RebuildCx, Web,
+};
+
+#[derive(Default)]
+struct Sheet {
+    /// Scope names by rule hash, which are leaked since there are only as many
+    /// as there are distinct rules in the program.
+    scopes: HashMap<u64, &'static str>,
+    rules: String,
+    /// The length of the prefix of `rules` which has been added to `element`.
+    injected: usize,
+    element: Option<web_sys::Element>,
+}
+
+thread_local! {
+    static SHEET: RefCell<Sheet> = RefCell::default();
+}
+
+/// How a rule is scoped to the elements it applies to.
+#[derive(Copy, Clone, Hash)]
+enum Scope {
+    /// An attribute like `data-rv-1a2b3c4d5e6f7a8b`, matched by
+    /// `[data-rv-1a2b3c4d5e6f7a8b]`.
+    Attribute,
+    /// A class like `rv-1a2b3c4d5e6f7a8b`, matched by `.rv-1a2b3c4d5e6f7a8b`.
+    Class,
+    /// The whole document, matched by `:root`, with no generated name.
+    Root,
+}
+
+/// Registers a rule scoped to a generated attribute name, which is returned.
+///
+/// `rule` is called with the scope selector (for example,
+/// `[data-rv-1a2b3c4d5e6f7a8b]`) to produce the rule's text, only the first
+/// time `key` is seen. Call [`inject`] to add it to the document.
+pub(crate) fn scope(
+    key: impl Hash,
+    rule: impl FnOnce(&str) -> String,
+) -> &'static str {
+    register(Scope::Attribute, key, rule)
+}
+
+fn register(
+    scope: Scope,
+    key: impl Hash,
+    rule: impl FnOnce(&str) -> String,
+) -> &'static str {
+    let mut hasher = DefaultHasher::new();
+    (scope, key).hash(&mut hasher);
+    let hash = hasher.finish();
+
+    SHEET.with_borrow_mut(|sheet| {
+        if let Some(name) = sheet.scopes.get(&hash) {
+            return *name;
+        }
+
+        let (name, selector) = match scope {
+            Scope::Attribute => {
+                let name = format!("data-rv-{hash:x}");
+                let selector = format!("[{name}]");
+                (name, selector)
+            }
+            Scope::Class => {
+                let name = format!("rv-{hash:x}");
+                let selector = format!(".{name}");
+                (name, selector)
+            }
+            Scope::Root => (String::new(), ":root".to_string()),
+        };
+        let name: &'static str = Box::leak(name.into_boxed_str());
+        sheet.scopes.insert(hash, name);
+
+        sheet.rules.push_str(&rule(&selector));
+        sheet.rules.push('\n');
+
+        name
+    })
+}
+
+/// Adds any rules registered since the last call to the document.
+pub(crate) fn inject() {
+    SHEET.with_borrow_mut(|sheet| {
+        if sheet.injected == sheet.rules.len() {
+            return;
+        }
+
+        let document = gloo_utils::document();
+        let element = sheet.element.get_or_insert_with(|| {
+            let element = document.create_element("style").unwrap_throw();
+            gloo_utils::head().append_child(&element).unwrap_throw();
+            element
+        });
+        element
+            .append_child(
+                &document.create_text_node(&sheet.rules[sheet.injected..]),
+            )
+            .unwrap_throw();
+        sheet.injected = sheet.rules.len();
+    })
+}
+
+/// The text of every rule registered so far, for embedding in a `<style>`
+/// element in server rendered HTML.
+pub fn rules() -> String {
+    SHEET.with_borrow(|sheet| sheet.rules.clone())
+}
+
+/// Applies `declarations` to the parent element while it matches
+/// `pseudo_class` (for example, `":hover"`), created with [`pseudo_class`].
+#[derive(Copy, Clone, Debug)]
+pub struct PseudoClass {
+    pseudo_class: &'static str,
+    declarations: &'static str,
+}
+
+/// Applies the CSS `declarations` to the parent element while it matches
+/// `pseudo_class`.
+///
+/// Usually, one of [`style_hover!`](crate::style_hover!),
+/// [`style_focus!`](crate::style_focus!),
+/// [`style_focus_visible!`](crate::style_focus_visible!) or
+/// [`style_active!`](crate::style_active!) is more convenient.
+pub fn pseudo_class(
+    pseudo_class: &'static str,
+    declarations: &'static str,
+) -> PseudoClass {
+    PseudoClass {
+        pseudo_class,
+        declarations,
+    }
+}
+
+impl PseudoClass {
+    fn scope(self) -> &'static str {
+        scope((self.pseudo_class, self.declarations), |selector| {
+            format!(
+                "{selector}{} {{ {} }}",
+                self.pseudo_class, self.declarations
+            )
+        })
+    }
+}
+
+impl Builder<Web> for PseudoClass {
+    type State = ();
+
+    fn build(self, cx: BuildCx) -> Self::State {
+        let scope = self.scope();
+        inject();
+        stats::attribute_written();
+        cx.position.parent.set_attribute(scope, "").unwrap_throw();
+    }
+
+    fn rebuild(self, _: RebuildCx, (): &mut Self::State) {}
+}
+
+impl Builder<Ssr> for PseudoClass {
+    type State = ();
+
+    fn build(self, cx: SsrCx) -> Self::State {
+        cx.attr(self.scope(), "")
+    }
+
+    fn rebuild(self, cx: Infallible, _: &mut Self::State) {
+        match cx {}
+    }
+}
+
+/// Rules scoped to the parent element, created with [`css!`](crate::css!).
+#[derive(Copy, Clone, Debug)]
+pub struct Scoped {
+    rules: &'static str,
+}
+
+/// Applies a set of CSS `rules` to the parent element.
+///
+/// Within `rules`, `&` stands for a selector matching only the parent element
+/// (and any other elements with the same rules), so `& { color: red }`
+/// styles the element itself, `&:hover > a { .. }` its links while it is
+/// hovered, and so on. Any other rule, such as `@keyframes` or an `@media`
+/// block containing `&` rules, can be used as well. Note that `@keyframes`
+/// names are not scoped, so they should be distinctive.
+///
+/// Usually, [`css!`](crate::css!) is more convenient.
+pub fn scoped(rules: &'static str) -> Scoped {
+    Scoped { rules }
+}
+
+impl Scoped {
+    fn scope(self) -> &'static str {
+        scope(self.rules, |selector| self.rules.replace('&', selector))
+    }
+}
+
+impl Builder<Web> for Scoped {
+    type State = ();
+
+    fn build(self, cx: BuildCx) -> Self::State {
+        let scope = self.scope();
+        inject();
+        stats::attribute_written();
+        cx.position.parent.set_attribute(scope, "").unwrap_throw();
+    }
+
+    fn rebuild(self, _: RebuildCx, (): &mut Self::State) {}
+}
+
+impl Builder<Ssr> for Scoped {
+    type State = ();
+
+    fn build(self, cx: SsrCx) -> Self::State {
+        cx.attr(self.scope(), "")
+    }
+
+    fn rebuild(self, cx: Infallible, _: &mut Self::State) {
+        match cx {}
+    }
+}
+
+/// Registers a stylesheet under a generated class name, which is returned, so
+/// that a component's styles can be kept alongside its view code.
+///
+/// Within `rules`, `&` stands for a selector matching the class, as for
+/// [`scoped`]. Each distinct stylesheet is registered once, however many
+/// times this is called, and every element with the class shares it:
+///
+/// ```ignore
+/// fn badge(label: &'static str) -> View!(Model) {
+///     let class = stylesheet!(
+///         "& { padding: 0 4px; border-radius: 4px }",
+///         "& + & { margin-left: 4px }",
+///     );
+///
+///     el::span((Class(class), label))
+/// }
+/// ```
+///
+/// In the browser, the stylesheet is added to the document straight away.
+/// Elsewhere (for example, when rendering on the server), include [`rules`]
+/// in the page instead.
+pub fn stylesheet(rules: &'static str) -> &'static str {
+    let class =
+        register(Scope::Class, rules, |selector| rules.replace('&', selector));
+
+    if cfg!(target_arch = "wasm32") {
+        inject();
+    }
+
+    class
+}
+
+/// Registers a rule for the whole document, with `:root` as the selector, the
+/// first time `key` is seen, and adds it to the document in the browser.
+#[doc(hidden)]
+pub fn root(key: impl Hash, rule: impl FnOnce(&str) -> String) {
+    register(Scope::Root, key, rule);
+
+    if cfg!(target_arch = "wasm32") {
+        inject();
+    }
+}
+
+/// Declares a set of design tokens, such as colors and spacing, as CSS custom
+/// properties with typed values.
+///
+/// This defines a type with an associated [`Var`](crate::units::Var) constant
+/// for each token, which can be used wherever a value of the token's type is
+/// expected, so a misspelled token is a compile error rather than a silently
+/// ignored `var()`. The type's `register` function adds the values to the
+/// document (or to [`rules`], for server rendering), and must be called
+/// before the tokens are used, typically at startup:
+///
+/// ```ignore
+/// theme! {
+///     /// The application's design tokens.
+///     pub Theme {
+///         /// The brand color.
+///         accent: Color = hex(0x3366ff),
+///         spacing: Px = px(8),
+///     }
+/// }
+///
+/// Theme::register();
+///
+/// el::button((
+///     style::color(Theme::accent),
+///     style!("padding: {}", Theme::spacing),
+/// ))
+/// ```
+///
+/// Tokens can be overridden for part of the page by setting the custom
+/// property (here, `--accent`) with [`style::property`](crate::style::property).
+#[macro_export]
+macro_rules! theme {
+    (
+        $(#[$attr:meta])*
+        $vis:vis $name:ident {
+            $($(#[$token_attr:meta])* $token:ident: $t:ty = $value:expr),* $(,)?
+        }
+    ) => {
+        $(#[$attr])*
+        $vis struct $name;
+
+        #[allow(non_upper_case_globals)]
+        impl $name {
+            $(
+                $(#[$token_attr])*
+                $vis const $token: $crate::units::Var<$t> =
+                    $crate::units::Var::new(::std::concat!(
+                        "--",
+                        ::std::stringify!($token),
+                    ));
+            )*
+
+            /// Adds the tokens' values to the document.
+            $vis fn register() {
+                $crate::css::root(::std::stringify!($name), |selector| {
+                    let mut rule = ::std::format!("{selector} {{");
+                    $(
+                        let value: $t = $value;
+                        rule.push_str(&::std::format!(
+                            " {}: {};",
+                            Self::$token.name(),
+                            value,
+                        ));
+                    )*
+                    rule.push_str(" }");
+                    rule
+                })
+            }
+        }
+    };
+}
+
+/// Registers a stylesheet under a generated class name, which is returned. See
+/// [`css::stylesheet`](crate::css::stylesheet).
+///
+/// Several string literals may be given, which are concatenated.
+#[macro_export]
+macro_rules! stylesheet {
+    ($($rules:literal),+ $(,)?) => {
+        $crate::css::stylesheet(::std::concat!($($rules, "\n"),+))
+    };
+}
+
+/// Applies a set of CSS rules to the parent element, where `&` stands for the
+/// element. See [`css::scoped`](crate::css::scoped).
+///
+/// Several string literals may be given, which are concatenated.
+///
+/// ```ignore
+/// el::div((
+///     css!(
+///         "@keyframes spinner-turn { to { transform: rotate(1turn) } }",
+///         "& { animation: spinner-turn 1s linear infinite }",
+///         "@media (prefers-reduced-motion) { & { animation: none } }",
+///     ),
+///     text("Loading"),
+/// ))
+/// ```
+#[macro_export]
+macro_rules! css {
+    ($($rules:literal),+ $(,)?) => {
+        $crate::css::scoped(::std::concat!($($rules, "\n"),+))
+    };
+}
+
+/// Applies CSS declarations to the parent element while it is hovered.
+///
+/// ```ignore
+/// el::button((style_hover!("background: #eee"), text("Save")))
+/// ```
+#[macro_export]
+macro_rules! style_hover {
+    ($declarations:literal) => {
+        $crate::css::pseudo_class(":hover", $declarations)
+    };
+}
+
+/// Applies CSS declarations to the parent element while it has focus.
+#[macro_export]
+macro_rules! style_focus {
+    ($declarations:literal) => {
+        $crate::css::pseudo_class(":focus", $declarations)
+    };
+}
+
+/// Applies CSS declarations to the parent element while it has focus which
+/// the browser would indicate, typically from the keyboard rather than the
+/// pointer. This is usually the right place for focus rings.
+#[macro_export]
+macro_rules! style_focus_visible {
+    ($declarations:literal) => {
+        $crate::css::pseudo_class(":focus-visible", $declarations)
+    };
+}
+
+/// Applies CSS declarations to the parent element while it is being activated,
+/// for example while a button is pressed.
+#[macro_export]
+macro_rules! style_active {
+    ($declarations:literal) => {
+        $crate::css::pseudo_class(":active", $declarations)
+    };
+}
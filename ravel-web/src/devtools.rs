@@ -0,0 +1,254 @@
+//! A debug channel for browser devtools extensions.
+//!
+//! Components wrapped with [`named`] form a tree, which is published to the
+//! page with
+//! [`window.postMessage`](https://developer.mozilla.org/en-US/docs/Web/API/Window/postMessage),
+//! where a devtools extension's content script can pick it up. Messages are
+//! only posted when the `devtools` feature is enabled. Otherwise, [`named`] is
+//! a zero cost wrapper.
+//!
+//! # Protocol
+//!
+//! Every message is a plain object with `source: "ravel-devtools"`,
+//! `version: 1` (which changes only if the schema does), and a `kind`:
+//!
+//! * `"mount"`: a component was built. It has the component's `id` (a number,
+//!   unique for the life of the page), `parent` (the `id` of the closest
+//!   enclosing named component, or `null`), `name` (as passed to [`named`])
+//!   and `state` (the Rust type name of its state).
+//! * `"rebuild"`: the component with the given `id` was rebuilt.
+//! * `"unmount"`: the component with the given `id` was removed.
+//! * `"tree"`: a snapshot of every mounted component, as `nodes`, an array of
+//!   objects with the same `id`, `parent`, `name` and `state` fields as
+//!   `"mount"`, with parents before their children.
+//!
+//! A `"tree"` message is posted in reply to a message with
+//! `source: "ravel-devtools-client"` and `kind: "dump"`, so an extension can
+//! catch up with components mounted before it connected.
+
+use std::convert::Infallible;
+
+use ravel::{MemSize, State};
+
+use crate::{
+    ssr::{Ssr, SsrCx},
+    BuildCx, Builder, RebuildCx, ViewMarker, Web,
+};
+
+/// A [`Builder`] created from [`named`].
+pub struct Named<B> {
+    name: &'static str,
+    inner: B,
+}
+
+/// Wraps a component so that it is reported to devtools as `name`, along with
+/// each of its rebuilds.
+pub fn named<B: Builder<Web>>(name: &'static str, inner: B) -> Named<B> {
+    Named { name, inner }
+}
+
+impl<B: Builder<Web>> Builder<Web> for Named<B> {
+    type State = NamedState<B::State>;
+
+    fn build(self, cx: BuildCx) -> Self::State {
+        let node =
+            imp::Node::mount(self.name, std::any::type_name::<B::State>());
+        let inner = node.enter(|| self.inner.build(cx));
+
+        NamedState { node, inner }
+    }
+
+    fn rebuild(self, cx: RebuildCx, state: &mut Self::State) {
+        state.node.rebuilt();
+        state
+            .node
+            .enter(|| self.inner.rebuild(cx, &mut state.inner))
+    }
+}
+
+impl<B: Builder<Ssr>> Builder<Ssr> for Named<B> {
+    type State = B::State;
+
+    fn build(self, cx: SsrCx) -> Self::State {
+        self.inner.build(cx)
+    }
+
+    fn rebuild(self, cx: Infallible, _: &mut Self::State) {
+        match cx {}
+    }
+}
+
+/// The state of a [`Named`].
+pub struct NamedState<S> {
+    node: imp::Node,
+    inner: S,
+}
+
+impl<S: State<Output>, Output> State<Output> for NamedState<S> {
+    fn run(&mut self, output: &mut Output) {
+        self.inner.run(output)
+    }
+}
+
+impl<S: MemSize> MemSize for NamedState<S> {
+    fn heap_size(&self) -> usize {
+        self.inner.heap_size()
+    }
+}
+
+impl<S: ViewMarker> ViewMarker for NamedState<S> {}
+
+#[cfg(feature = "devtools")]
+mod imp {
+    use std::{cell::RefCell, collections::BTreeMap};
+
+    use web_sys::wasm_bindgen::{JsCast as _, JsValue};
+
+    const SOURCE: &str = "ravel-devtools";
+    const CLIENT_SOURCE: &str = "ravel-devtools-client";
+    const VERSION: u32 = 1;
+
+    struct Info {
+        parent: Option<u32>,
+        name: &'static str,
+        state: &'static str,
+    }
+
+    #[derive(Default)]
+    struct Tree {
+        next_id: u32,
+        nodes: BTreeMap<u32, Info>,
+        /// The components currently being built or rebuilt, innermost last.
+        stack: Vec<u32>,
+        listener: Option<gloo_events::EventListener>,
+    }
+
+    thread_local! {
+        static TREE: RefCell<Tree> = RefCell::default();
+    }
+
+    pub struct Node(u32);
+
+    impl Node {
+        pub fn mount(name: &'static str, state: &'static str) -> Self {
+            TREE.with_borrow_mut(|tree| {
+                tree.listener.get_or_insert_with(listen);
+
+                let id = tree.next_id;
+                tree.next_id += 1;
+
+                let info = Info {
+                    parent: tree.stack.last().copied(),
+                    name,
+                    state,
+                };
+                let message = message("mount", id);
+                set_info(&message, &info);
+                post(&message);
+
+                tree.nodes.insert(id, info);
+                Node(id)
+            })
+        }
+
+        pub fn enter<R>(&self, f: impl FnOnce() -> R) -> R {
+            TREE.with_borrow_mut(|tree| tree.stack.push(self.0));
+            let result = f();
+            TREE.with_borrow_mut(|tree| tree.stack.pop());
+            result
+        }
+
+        pub fn rebuilt(&self) {
+            post(&message("rebuild", self.0))
+        }
+    }
+
+    impl Drop for Node {
+        fn drop(&mut self) {
+            TREE.with_borrow_mut(|tree| tree.nodes.remove(&self.0));
+            post(&message("unmount", self.0))
+        }
+    }
+
+    fn listen() -> gloo_events::EventListener {
+        gloo_events::EventListener::new(&gloo_utils::window(), "message", |e| {
+            let Some(e) = e.dyn_ref::<web_sys::MessageEvent>() else {
+                return;
+            };
+            let data = e.data();
+            if get(&data, "source").as_string().as_deref()
+                == Some(CLIENT_SOURCE)
+                && get(&data, "kind").as_string().as_deref() == Some("dump")
+            {
+                post(&dump());
+            }
+        })
+    }
+
+    fn dump() -> js_sys::Object {
+        let nodes = TREE.with_borrow(|tree| {
+            tree.nodes
+                .iter()
+                .map(|(id, info)| {
+                    let node = js_sys::Object::new();
+                    set(&node, "id", *id);
+                    set_info(&node, info);
+                    node
+                })
+                .collect::<js_sys::Array>()
+        });
+
+        let message = message_object("tree");
+        set(&message, "nodes", nodes);
+        message
+    }
+
+    fn message_object(kind: &str) -> js_sys::Object {
+        let message = js_sys::Object::new();
+        set(&message, "source", SOURCE);
+        set(&message, "version", VERSION);
+        set(&message, "kind", kind);
+        message
+    }
+
+    fn message(kind: &str, id: u32) -> js_sys::Object {
+        let message = message_object(kind);
+        set(&message, "id", id);
+        message
+    }
+
+    fn set_info(object: &js_sys::Object, info: &Info) {
+        set(object, "parent", info.parent);
+        set(object, "name", info.name);
+        set(object, "state", info.state);
+    }
+
+    fn set(object: &js_sys::Object, key: &str, value: impl Into<JsValue>) {
+        let _ = js_sys::Reflect::set(object, &key.into(), &value.into());
+    }
+
+    fn get(object: &JsValue, key: &str) -> JsValue {
+        js_sys::Reflect::get(object, &key.into()).unwrap_or_default()
+    }
+
+    fn post(message: &js_sys::Object) {
+        let _ = gloo_utils::window().post_message(message, "*");
+    }
+}
+
+#[cfg(not(feature = "devtools"))]
+mod imp {
+    pub struct Node;
+
+    impl Node {
+        pub fn mount(_: &'static str, _: &'static str) -> Self {
+            Node
+        }
+
+        pub fn enter<R>(&self, f: impl FnOnce() -> R) -> R {
+            f()
+        }
+
+        pub fn rebuilt(&self) {}
+    }
+}
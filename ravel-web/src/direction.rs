@@ -0,0 +1,150 @@
+//! Text direction, for apps which support right-to-left languages.
+//!
+//! Browsers lay out text, flex rows and grids according to the `dir`
+//! attribute, and resolve logical style properties (like
+//! [`style::margin_inline_start`](crate::style::margin_inline_start)) to the
+//! matching physical side. So styles written with logical properties work in
+//! both directions, without duplicating them.
+//!
+//! [`provide_direction`] sets the attribute on an element, and makes the
+//! direction available to the components inside it through [`use_direction`],
+//! for what the browser can't flip by itself, such as transforms or
+//! directional icons:
+//!
+//! ```ignore
+//! el::body(provide_direction(
+//!     Direction::for_lang(&model.lang),
+//!     with(|cx| {
+//!         let back = match use_direction(&cx) {
+//!             Direction::Ltr => "←",
+//!             Direction::Rtl => "→",
+//!         };
+//!         cx.build(el::button(back))
+//!     }),
+//! ))
+//! ```
+
+use std::fmt;
+
+use ravel::{provide_context, use_context, Cx, CxRep, MemSize, ProvideContext};
+
+use crate::attr::Dir;
+
+/// The direction of text, as given by the `dir` attribute.
+#[derive(Copy, Clone, PartialEq, Eq, Hash, Debug, Default)]
+pub enum Direction {
+    /// Left to right, as in English.
+    #[default]
+    Ltr,
+    /// Right to left, as in Arabic or Hebrew.
+    Rtl,
+}
+
+/// Primary language subtags of languages usually written right to left.
+const RTL_LANGUAGES: &[&str] = &[
+    "ar", "arc", "ckb", "dv", "fa", "he", "iw", "ks", "ps", "sd", "syr", "ug",
+    "ur", "yi",
+];
+
+/// Script subtags of scripts written right to left.
+const RTL_SCRIPTS: &[&str] =
+    &["adlm", "arab", "hebr", "nkoo", "rohg", "syrc", "thaa"];
+
+impl Direction {
+    /// The usual direction of the language with the
+    /// [BCP 47](https://www.rfc-editor.org/info/bcp47) tag `lang`, such as
+    /// `"en-US"` or `"ar"`.
+    ///
+    /// An explicit script subtag takes precedence, so `"az-Arab"` is right to
+    /// left. Unknown languages are left to right.
+    pub fn for_lang(lang: &str) -> Self {
+        let lang = lang.to_ascii_lowercase();
+        let mut subtags = lang.split(['-', '_']);
+        let language = subtags.next().unwrap_or_default();
+
+        for subtag in subtags {
+            if subtag.len() == 4 {
+                return if RTL_SCRIPTS.contains(&subtag) {
+                    Direction::Rtl
+                } else {
+                    Direction::Ltr
+                };
+            }
+        }
+
+        if RTL_LANGUAGES.contains(&language) {
+            Direction::Rtl
+        } else {
+            Direction::Ltr
+        }
+    }
+
+    /// The computed direction of `element`, as inherited from its ancestors
+    /// or set by a stylesheet.
+    pub fn of(element: &web_sys::Element) -> Self {
+        let direction = gloo_utils::window()
+            .get_computed_style(element)
+            .ok()
+            .flatten()
+            .and_then(|style| style.get_property_value("direction").ok());
+
+        match direction.as_deref() {
+            Some("rtl") => Direction::Rtl,
+            _ => Direction::Ltr,
+        }
+    }
+
+    /// Whether this is [`Direction::Rtl`].
+    pub fn is_rtl(self) -> bool {
+        self == Direction::Rtl
+    }
+
+    /// The value of the `dir` attribute.
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Direction::Ltr => "ltr",
+            Direction::Rtl => "rtl",
+        }
+    }
+
+    /// `1.` for [`Direction::Ltr`] and `-1.` for [`Direction::Rtl`].
+    ///
+    /// Multiplying a horizontal offset by this mirrors it, for example in a
+    /// `translateX` transform which should move towards the end of the line.
+    pub fn sign(self) -> f64 {
+        match self {
+            Direction::Ltr => 1.,
+            Direction::Rtl => -1.,
+        }
+    }
+}
+
+impl fmt::Display for Direction {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl MemSize for Direction {
+    fn heap_size(&self) -> usize {
+        0
+    }
+}
+
+/// Sets the `dir` attribute of the parent element to `direction`, and makes it
+/// available to every component in `body` with [`use_direction`].
+///
+/// This must be used as (or in) the body of an element, like
+/// [`with_ref`](crate::el::with_ref).
+pub fn provide_direction<B>(
+    direction: Direction,
+    body: B,
+) -> (Dir<Direction>, ProvideContext<Direction, B>) {
+    (Dir(direction), provide_context(direction, body))
+}
+
+/// The direction given to the innermost enclosing [`provide_direction`], or
+/// [`Direction::Ltr`] if there is none.
+pub fn use_direction<S, R: CxRep>(cx: &Cx<S, R>) -> Direction {
+    use_context::<Direction, _, _>(cx).map_or(Direction::Ltr, |d| *d)
+}
@@ -1,22 +1,115 @@
-use std::sync::Arc;
+use std::{cell::Cell, sync::Arc};
 
 use atomic_waker::AtomicWaker;
 use web_sys::wasm_bindgen::UnwrapThrowExt;
 
+use crate::stats;
+
+/// A location in the DOM where nodes are inserted, as provided by
+/// [`BuildCx::position`](crate::BuildCx::position).
 #[derive(Copy, Clone)]
 pub struct Position<'cx> {
+    /// The element which new nodes are inserted into.
     pub parent: &'cx web_sys::Element,
+    /// The child of `parent` which new nodes are inserted before. This may be
+    /// `null` to append.
     pub insert_before: &'cx web_sys::Node,
+    /// Wakes the event loop, to schedule a rebuild.
     // TODO: Remove double pointer.
     pub waker: &'cx Arc<AtomicWaker>,
 }
 
 impl Position<'_> {
+    /// Inserts `node` at this position.
     pub fn insert(&self, node: &web_sys::Node) {
         self.parent
             .insert_before(node, Some(self.insert_before))
             .unwrap_throw();
     }
+
+    /// Calls `build` with a position in a detached element, then moves all of
+    /// the nodes it inserted to this position at once.
+    ///
+    /// This avoids touching the live document for each node when building many
+    /// at once, such as the initial entries of a collection. The nodes must all
+    /// be inserted at the given position: anything which modifies the parent
+    /// itself, such as an attribute, would apply to the detached element
+    /// instead. If the parent is already detached, `build` is called with this
+    /// position directly.
+    pub fn batch<T>(&self, build: impl FnOnce(Position) -> T) -> T {
+        if !self.parent.is_connected() {
+            return build(*self);
+        }
+
+        let document = gloo_utils::document();
+        let staging = document.create_element("div").unwrap_throw();
+        let end = document.create_comment("");
+        staging.append_child(&end).unwrap_throw();
+
+        let result = build(Position {
+            parent: &staging,
+            insert_before: &end,
+            waker: self.waker,
+        });
+
+        staging.remove_child(&end).unwrap_throw();
+        let fragment = document.create_document_fragment();
+        while let Some(child) = staging.first_child() {
+            fragment.append_child(&child).unwrap_throw();
+        }
+        self.insert(&fragment);
+
+        result
+    }
+}
+
+/// The kind of node used to mark the start and end of a range of nodes, as set
+/// with [`set_markers`].
+#[derive(Copy, Clone, PartialEq, Eq, Debug, Default)]
+pub enum Markers {
+    /// Comments, such as `<!--{-->`, which show the structure of the
+    /// component tree when inspecting the page.
+    #[default]
+    Comment,
+    /// Empty text nodes, which don't show up in the source of the page, and
+    /// are skipped by tools which only expect elements and text.
+    ///
+    /// Nodes which are combined or removed by
+    /// [`Node.normalize()`](https://developer.mozilla.org/en-US/docs/Web/API/Node/normalize)
+    /// can't be found again, so this must not be used if anything normalizes
+    /// the nodes rendered by ravel.
+    Text,
+}
+
+thread_local! {
+    static MARKERS: Cell<Markers> = const { Cell::new(Markers::Comment) };
+}
+
+/// Sets the kind of node used as markers by components built from now on,
+/// such as [`Option`] and collections.
+///
+/// This should be called before the event loop starts, since components built
+/// earlier keep their markers. Server-side rendering always uses comments.
+pub fn set_markers(markers: Markers) {
+    MARKERS.set(markers)
+}
+
+/// Creates a node to mark the start or end of a range of nodes, with `data`
+/// describing it if markers are comments.
+pub fn marker(data: &str) -> web_sys::CharacterData {
+    stats::created();
+    match MARKERS.get() {
+        Markers::Comment => {
+            web_sys::Comment::new_with_data(data).unwrap_throw().into()
+        }
+        Markers::Text => web_sys::Text::new().unwrap_throw().into(),
+    }
+}
+
+/// Removes `node` from `parent`.
+pub fn remove(parent: &web_sys::Node, node: &web_sys::Node) {
+    stats::removed();
+    parent.remove_child(node).unwrap_throw();
 }
 
 pub fn clear(
@@ -28,6 +121,24 @@ pub fn clear(
         if &next == end {
             break;
         }
-        parent.remove_child(&next).unwrap_throw();
+        remove(parent, &next);
+    }
+}
+
+/// Moves the nodes between `start` and `end` into a new fragment, from which
+/// they can later be reinserted as a whole.
+pub fn detach(
+    start: &web_sys::Node,
+    end: &web_sys::Node,
+) -> web_sys::DocumentFragment {
+    let fragment = gloo_utils::document().create_document_fragment();
+
+    while let Some(next) = start.next_sibling() {
+        if &next == end {
+            break;
+        }
+        fragment.append_child(&next).unwrap_throw();
     }
+
+    fragment
 }
@@ -0,0 +1,134 @@
+use std::convert::Infallible;
+
+use ravel::{MemSize, State};
+
+use crate::{
+    dom::{clear, marker, Position},
+    ssr::{Ssr, SsrCx},
+    BuildCx, Builder, RebuildCx, View, ViewMarker, Web,
+};
+
+/// The state for one of a fixed set of alternative components, such as an
+/// [`Either`].
+///
+/// `E` holds the state of the current alternative, such as an [`Either`] of
+/// the alternatives' states.
+pub struct EitherState<E> {
+    inner: E,
+    start: web_sys::CharacterData,
+    end: web_sys::CharacterData,
+}
+
+impl<E: State<Output>, Output> State<Output> for EitherState<E> {
+    fn run(&mut self, output: &mut Output) {
+        self.inner.run(output)
+    }
+}
+
+impl<E: MemSize> MemSize for EitherState<E> {
+    fn heap_size(&self) -> usize {
+        self.inner.heap_size()
+    }
+}
+
+impl<E> ViewMarker for EitherState<E> {}
+
+macro_rules! either {
+    ($(#[$attr:meta])* $name:ident, $($var:ident),*) => {
+        $(#[$attr])*
+        ///
+        /// Unlike [`any`](crate::any), the state of each alternative is stored
+        /// without boxing. When the alternative changes between frames, the
+        /// previous one is removed and the new one is built from scratch.
+        #[derive(Copy, Clone, PartialEq, Eq, Hash, Debug)]
+        pub enum $name<$($var),*> {
+            $($var($var),)*
+        }
+
+        impl<$($var: MemSize),*> MemSize for $name<$($var),*> {
+            fn heap_size(&self) -> usize {
+                match self {
+                    $($name::$var(s) => s.heap_size(),)*
+                }
+            }
+        }
+
+        impl<$($var: View),*> Builder<Web> for $name<$($var),*> {
+            type State = EitherState<$name<$($var::State),*>>;
+
+            fn build(self, cx: BuildCx) -> Self::State {
+                let start = marker("{");
+                let end = marker("}");
+
+                cx.position.insert(&start);
+                let inner = match self {
+                    $($name::$var(b) => $name::$var(b.build(cx)),)*
+                };
+                cx.position.insert(&end);
+
+                EitherState { inner, start, end }
+            }
+
+            fn rebuild(self, cx: RebuildCx, state: &mut Self::State) {
+                match (self, &mut state.inner) {
+                    $(($name::$var(b), $name::$var(s)) => b.rebuild(cx, s),)*
+                    (b, _) => {
+                        clear(cx.parent, &state.start, &state.end);
+
+                        let cx = BuildCx {
+                            position: Position {
+                                parent: cx.parent,
+                                insert_before: &state.end,
+                                waker: cx.waker,
+                            },
+                        };
+                        state.inner = match b {
+                            $($name::$var(b) => $name::$var(b.build(cx)),)*
+                        };
+                    }
+                }
+            }
+        }
+
+        impl<$($var: Builder<Ssr>),*> Builder<Ssr> for $name<$($var),*> {
+            type State = $name<$($var::State),*>;
+
+            fn build(self, cx: SsrCx) -> Self::State {
+                cx.comment("{");
+                let state = match self {
+                    $($name::$var(b) => $name::$var(b.build(cx)),)*
+                };
+                cx.comment("}");
+
+                state
+            }
+
+            fn rebuild(self, cx: Infallible, _: &mut Self::State) {
+                match cx {}
+            }
+        }
+
+        impl<$($var: State<Output>,)* Output> State<Output>
+            for $name<$($var),*>
+        {
+            fn run(&mut self, output: &mut Output) {
+                match self {
+                    $($name::$var(state) => state.run(output),)*
+                }
+            }
+        }
+    };
+}
+
+either!(
+    /// One of two alternative components.
+    Either, A, B
+);
+either!(
+    /// One of three alternative components.
+    Either3, A, B, C
+);
+either!(
+    /// One of four alternative components.
+    Either4, A, B, C, D
+);
@@ -0,0 +1,117 @@
+use std::{cell::Cell, convert::Infallible};
+
+use ravel::{Builder, MemSize, State};
+
+use crate::{
+    ssr::{Ssr, SsrCx},
+    BuildCx, RebuildCx, Web,
+};
+
+thread_local! {
+    /// Whether a hook was armed during the current pass.
+    static PENDING: Cell<bool> = const { Cell::new(false) };
+    /// Whether the current pass is one which runs hooks, in which case
+    /// rebuilding doesn't arm them again.
+    static MEASURING: Cell<bool> = const { Cell::new(false) };
+}
+
+/// Called by the event loop after each build or rebuild. Returns whether any
+/// hooks are armed, in which case the next pass must follow immediately.
+///
+/// This also moves focus as requested by [`focus_when`](super::focus_when),
+/// starts the enter transitions of any
+/// [`transition`](crate::transition::transition)s built in the pass, and runs
+/// effects such as [`on_mount`](super::on_mount).
+pub(crate) fn finish_render() -> bool {
+    super::focus::flush();
+    crate::transition::flush();
+    super::effect::flush();
+
+    let pending = PENDING.take();
+    MEASURING.set(pending);
+    pending
+}
+
+/// A [`Builder`] created from [`after_render`].
+pub struct AfterRender<F> {
+    hook: F,
+}
+
+/// Calls `hook` with the model and the parent element once every DOM change of
+/// the current frame has been made, to read layout (for example, with
+/// `getBoundingClientRect`) and store the result in the model.
+///
+/// Hooks run in an extra pass of the event loop straight after the rebuild,
+/// before the browser paints. Since all of a frame's hooks run together,
+/// before anything is written to the DOM again, the browser computes layout at
+/// most once for all of them, rather than once per measurement. The rebuild
+/// following the hooks applies their results to the DOM, but doesn't run them
+/// again, so measurements settle after one extra pass.
+///
+/// This must be used as (or in) the body of an element, like
+/// [`with_ref`](super::with_ref).
+pub fn after_render<F>(hook: F) -> AfterRender<F> {
+    AfterRender { hook }
+}
+
+impl<F: 'static> Builder<Web> for AfterRender<F> {
+    type State = AfterRenderState<F>;
+
+    fn build(self, cx: BuildCx) -> Self::State {
+        let mut state = AfterRenderState {
+            hook: self.hook,
+            element: cx.position.parent.clone(),
+            armed: false,
+        };
+        state.arm();
+        state
+    }
+
+    fn rebuild(self, _: RebuildCx, state: &mut Self::State) {
+        state.hook = self.hook;
+        state.arm();
+    }
+}
+
+impl<F> Builder<Ssr> for AfterRender<F> {
+    type State = ();
+
+    fn build(self, _: SsrCx) -> Self::State {}
+
+    fn rebuild(self, cx: Infallible, _: &mut Self::State) {
+        match cx {}
+    }
+}
+
+/// The state of an [`AfterRender`].
+pub struct AfterRenderState<F> {
+    hook: F,
+    element: web_sys::Element,
+    armed: bool,
+}
+
+impl<F> AfterRenderState<F> {
+    fn arm(&mut self) {
+        if !MEASURING.get() {
+            self.armed = true;
+            PENDING.set(true);
+        }
+    }
+}
+
+impl<F, Output> State<Output> for AfterRenderState<F>
+where
+    F: 'static + FnMut(&mut Output, &web_sys::Element),
+{
+    fn run(&mut self, output: &mut Output) {
+        if std::mem::take(&mut self.armed) {
+            (self.hook)(output, &self.element)
+        }
+    }
+}
+
+impl<F> MemSize for AfterRenderState<F> {
+    fn heap_size(&self) -> usize {
+        0
+    }
+}
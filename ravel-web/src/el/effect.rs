@@ -0,0 +1,217 @@
+use std::{cell::RefCell, convert::Infallible};
+
+use ravel::{Builder, MemSize, State};
+
+use crate::{
+    ssr::{Ssr, SsrCx},
+    BuildCx, RebuildCx, Web,
+};
+
+thread_local! {
+    /// Effects to run once the current pass has finished rebuilding.
+    static PENDING: RefCell<Vec<Box<dyn FnOnce()>>> =
+        const { RefCell::new(Vec::new()) };
+}
+
+/// Runs the effects scheduled during the current pass, in order.
+pub(crate) fn flush() {
+    // Don't hold the borrow while the effects run, since they are arbitrary
+    // code.
+    for effect in PENDING.take() {
+        effect()
+    }
+}
+
+pub(crate) fn schedule(
+    element: &web_sys::Element,
+    effect: impl 'static + FnOnce(&web_sys::Element),
+) {
+    let element = element.clone();
+    PENDING.with_borrow_mut(|pending| {
+        pending.push(Box::new(move || effect(&element)))
+    });
+}
+
+/// A [`Builder`] created from [`on_mount`].
+pub struct OnMount<F> {
+    effect: F,
+}
+
+/// Calls `effect` with the parent element once, after it is initially built.
+///
+/// This is for setting up things outside of the view, like a chart library
+/// drawing on a canvas. The effect runs once every DOM change of the frame
+/// has been made, so the element is attached by then. Resources it creates
+/// can be released with [`on_unmount`].
+///
+/// This must be used as (or in) the body of an element, like
+/// [`with_ref`](super::with_ref).
+pub fn on_mount<F>(effect: F) -> OnMount<F>
+where
+    F: 'static + FnOnce(&web_sys::Element),
+{
+    OnMount { effect }
+}
+
+impl<F> Builder<Web> for OnMount<F>
+where
+    F: 'static + FnOnce(&web_sys::Element),
+{
+    type State = ();
+
+    fn build(self, cx: BuildCx) -> Self::State {
+        schedule(cx.position.parent, self.effect)
+    }
+
+    fn rebuild(self, _: RebuildCx, _: &mut Self::State) {}
+}
+
+impl<F> Builder<Ssr> for OnMount<F> {
+    type State = ();
+
+    fn build(self, _: SsrCx) -> Self::State {}
+
+    fn rebuild(self, cx: Infallible, _: &mut Self::State) {
+        match cx {}
+    }
+}
+
+/// A [`Builder`] created from [`after_rebuild`].
+pub struct AfterRebuild<F> {
+    effect: F,
+}
+
+/// Calls `effect` with the parent element after each rebuild, but not after it
+/// is initially built.
+///
+/// Like [`on_mount`], the effect runs once every DOM change of the frame has
+/// been made. Since the effect is given afresh on every rebuild, it can
+/// capture the latest values from the model, for example to update an
+/// imperative widget.
+///
+/// This must be used as (or in) the body of an element, like
+/// [`with_ref`](super::with_ref).
+pub fn after_rebuild<F>(effect: F) -> AfterRebuild<F>
+where
+    F: 'static + FnOnce(&web_sys::Element),
+{
+    AfterRebuild { effect }
+}
+
+/// The state of an [`AfterRebuild`].
+pub struct AfterRebuildState {
+    element: web_sys::Element,
+}
+
+impl<F> Builder<Web> for AfterRebuild<F>
+where
+    F: 'static + FnOnce(&web_sys::Element),
+{
+    type State = AfterRebuildState;
+
+    fn build(self, cx: BuildCx) -> Self::State {
+        AfterRebuildState {
+            element: cx.position.parent.clone(),
+        }
+    }
+
+    fn rebuild(self, _: RebuildCx, state: &mut Self::State) {
+        schedule(&state.element, self.effect)
+    }
+}
+
+impl<F> Builder<Ssr> for AfterRebuild<F> {
+    type State = ();
+
+    fn build(self, _: SsrCx) -> Self::State {}
+
+    fn rebuild(self, cx: Infallible, _: &mut Self::State) {
+        match cx {}
+    }
+}
+
+impl<Output> State<Output> for AfterRebuildState {
+    fn run(&mut self, _: &mut Output) {}
+}
+
+impl MemSize for AfterRebuildState {
+    fn heap_size(&self) -> usize {
+        0
+    }
+}
+
+/// A [`Builder`] created from [`on_unmount`].
+pub struct OnUnmount<F> {
+    effect: F,
+}
+
+/// Calls `effect` with the parent element when it is removed, or more
+/// precisely, when its state is dropped.
+///
+/// The effect given by the latest rebuild is the one which runs. It runs
+/// straight away, while the rest of the frame is still being rebuilt, so it
+/// shouldn't expect the element to be attached.
+///
+/// This must be used as (or in) the body of an element, like
+/// [`with_ref`](super::with_ref).
+pub fn on_unmount<F>(effect: F) -> OnUnmount<F>
+where
+    F: 'static + FnOnce(&web_sys::Element),
+{
+    OnUnmount { effect }
+}
+
+impl<F> Builder<Web> for OnUnmount<F>
+where
+    F: 'static + FnOnce(&web_sys::Element),
+{
+    type State = OnUnmountState<F>;
+
+    fn build(self, cx: BuildCx) -> Self::State {
+        OnUnmountState {
+            effect: Some(self.effect),
+            element: cx.position.parent.clone(),
+        }
+    }
+
+    fn rebuild(self, _: RebuildCx, state: &mut Self::State) {
+        state.effect = Some(self.effect);
+    }
+}
+
+impl<F> Builder<Ssr> for OnUnmount<F> {
+    type State = ();
+
+    fn build(self, _: SsrCx) -> Self::State {}
+
+    fn rebuild(self, cx: Infallible, _: &mut Self::State) {
+        match cx {}
+    }
+}
+
+/// The state of an [`OnUnmount`].
+pub struct OnUnmountState<F: FnOnce(&web_sys::Element)> {
+    effect: Option<F>,
+    element: web_sys::Element,
+}
+
+impl<F: FnOnce(&web_sys::Element)> Drop for OnUnmountState<F> {
+    fn drop(&mut self) {
+        if let Some(effect) = self.effect.take() {
+            effect(&self.element)
+        }
+    }
+}
+
+impl<F: FnOnce(&web_sys::Element)> MemSize for OnUnmountState<F> {
+    fn heap_size(&self) -> usize {
+        0
+    }
+}
+
+impl<F, Output> State<Output> for OnUnmountState<F>
+where
+    F: 'static + FnOnce(&web_sys::Element),
+{
+    fn run(&mut self, _: &mut Output) {}
+}
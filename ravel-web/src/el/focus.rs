@@ -0,0 +1,100 @@
+use std::{cell::RefCell, convert::Infallible};
+
+use ravel::{Builder, MemSize, State};
+use web_sys::wasm_bindgen::JsCast as _;
+
+use crate::{
+    ssr::{Ssr, SsrCx},
+    BuildCx, RebuildCx, Web,
+};
+
+thread_local! {
+    /// The element to focus once the current pass has finished rebuilding.
+    static PENDING: RefCell<Option<web_sys::HtmlElement>> = const {
+        RefCell::new(None)
+    };
+}
+
+/// Focuses the element requested during the current pass, if any.
+pub(crate) fn flush() {
+    if let Some(element) = PENDING.take() {
+        let _ = element.focus();
+    }
+}
+
+/// A [`Builder`] created from [`focus_when`].
+#[derive(Copy, Clone, Debug)]
+pub struct FocusWhen {
+    focused: bool,
+}
+
+/// Focuses the parent element when `focused` becomes `true`, either when it is
+/// initially built or on a rebuild where it was previously `false`.
+///
+/// This is for elements which should take focus in response to a change in
+/// the model, like an input which appears when an item is being edited:
+///
+/// ```ignore
+/// input((Class("edit"), focus_when(item.editing)))
+/// ```
+///
+/// Focus is moved once every DOM change of the frame has been made, so the
+/// element is attached by then. If several elements request focus in the same
+/// frame, the last one built wins. The element isn't blurred when `focused`
+/// becomes `false`.
+pub fn focus_when(focused: bool) -> FocusWhen {
+    FocusWhen { focused }
+}
+
+fn request(parent: &web_sys::Element) {
+    if let Some(element) = parent.dyn_ref::<web_sys::HtmlElement>() {
+        PENDING.set(Some(element.clone()));
+    }
+}
+
+impl Builder<Web> for FocusWhen {
+    type State = FocusWhenState;
+
+    fn build(self, cx: BuildCx) -> Self::State {
+        if self.focused {
+            request(cx.position.parent);
+        }
+
+        FocusWhenState {
+            focused: self.focused,
+        }
+    }
+
+    fn rebuild(self, cx: RebuildCx, state: &mut Self::State) {
+        if self.focused && !state.focused {
+            request(cx.parent);
+        }
+
+        state.focused = self.focused;
+    }
+}
+
+impl Builder<Ssr> for FocusWhen {
+    type State = ();
+
+    fn build(self, _: SsrCx) -> Self::State {}
+
+    fn rebuild(self, cx: Infallible, _: &mut Self::State) {
+        match cx {}
+    }
+}
+
+/// The state of a [`FocusWhen`].
+pub struct FocusWhenState {
+    focused: bool,
+}
+
+impl<Output> State<Output> for FocusWhenState {
+    fn run(&mut self, _: &mut Output) {}
+}
+
+impl MemSize for FocusWhenState {
+    fn heap_size(&self) -> usize {
+        0
+    }
+}
@@ -4,13 +4,25 @@ use std::marker::PhantomData;
 
 use self::types::*;
 
+mod after_render;
+mod effect;
+mod focus;
+mod node_ref;
+mod src;
 pub mod types;
 
+pub use after_render::*;
+pub use effect::*;
+pub use focus::*;
+pub use node_ref::*;
+pub use src::*;
+
 /// An arbitrary element.
-pub fn el<Kind: ElKind, Body>(_: Kind, body: Body) -> El<Kind, Body> {
+#[track_caller]
+pub fn el<Kind: ElKind, Body>(_: Kind, body: Body) -> El<Kind, (Src, Body)> {
     El {
         kind: PhantomData,
-        body,
+        body: (Src::caller(), body),
     }
 }
 
@@ -0,0 +1,124 @@
+use std::{cell::RefCell, convert::Infallible, rc::Rc};
+
+use ravel::{Builder, MemSize, State};
+use web_sys::wasm_bindgen::JsCast;
+
+use crate::{
+    ssr::{Ssr, SsrCx},
+    BuildCx, RebuildCx, Web,
+};
+
+/// A reference to an element, captured by [`with_ref`].
+///
+/// This is cheap to clone, so it can be stored in the model or moved into
+/// event handlers, to call DOM methods like `focus` or measure the element.
+///
+/// Two references are equal if they are clones of the same reference, not if
+/// they currently hold the same element.
+#[derive(Clone, Default, Debug)]
+pub struct NodeRef(Rc<RefCell<Option<web_sys::Element>>>);
+
+impl PartialEq for NodeRef {
+    fn eq(&self, other: &Self) -> bool {
+        Rc::ptr_eq(&self.0, &other.0)
+    }
+}
+
+impl Eq for NodeRef {}
+
+impl NodeRef {
+    /// An empty reference.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// The referenced element, if it is currently built.
+    pub fn get(&self) -> Option<web_sys::Element> {
+        self.0.borrow().clone()
+    }
+
+    /// The referenced element, if it is currently built and has type `T`.
+    pub fn cast<T: JsCast>(&self) -> Option<T> {
+        self.get()?.dyn_into().ok()
+    }
+
+    fn set(&self, element: Option<&web_sys::Element>) {
+        *self.0.borrow_mut() = element.cloned();
+    }
+}
+
+/// A [`Builder`] created from [`with_ref`].
+pub struct WithRef<B> {
+    node_ref: NodeRef,
+    body: B,
+}
+
+impl<B: Builder<Web>> Builder<Web> for WithRef<B> {
+    type State = WithRefState<B::State>;
+
+    fn build(self, cx: BuildCx) -> Self::State {
+        self.node_ref.set(Some(cx.position.parent));
+
+        WithRefState {
+            node_ref: self.node_ref,
+            body: self.body.build(cx),
+        }
+    }
+
+    fn rebuild(self, cx: RebuildCx, state: &mut Self::State) {
+        if !Rc::ptr_eq(&self.node_ref.0, &state.node_ref.0) {
+            state.node_ref.set(None);
+            self.node_ref.set(Some(cx.parent));
+            state.node_ref = self.node_ref;
+        }
+
+        self.body.rebuild(cx, &mut state.body)
+    }
+}
+
+impl<B: Builder<Ssr>> Builder<Ssr> for WithRef<B> {
+    type State = B::State;
+
+    fn build(self, cx: SsrCx) -> Self::State {
+        self.body.build(cx)
+    }
+
+    fn rebuild(self, cx: Infallible, _: &mut Self::State) {
+        match cx {}
+    }
+}
+
+/// The state of a [`WithRef`].
+///
+/// The reference is cleared when this is dropped.
+pub struct WithRefState<S> {
+    node_ref: NodeRef,
+    body: S,
+}
+
+impl<S> Drop for WithRefState<S> {
+    fn drop(&mut self) {
+        self.node_ref.set(None);
+    }
+}
+
+impl<S: MemSize> MemSize for WithRefState<S> {
+    fn heap_size(&self) -> usize {
+        self.body.heap_size()
+    }
+}
+
+impl<S: State<Output>, Output> State<Output> for WithRefState<S> {
+    fn run(&mut self, output: &mut Output) {
+        self.body.run(output)
+    }
+}
+
+/// Captures the parent element in `node_ref`, for as long as this component is
+/// built.
+///
+/// This must be used as (or in) the body of an element, for example
+/// `el::input(with_ref(node_ref.clone(), (...)))`.
+pub fn with_ref<B: Builder<Web>>(node_ref: NodeRef, body: B) -> WithRef<B> {
+    WithRef { node_ref, body }
+}
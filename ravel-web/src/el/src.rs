@@ -0,0 +1,71 @@
+use std::convert::Infallible;
+
+use ravel::Builder;
+use web_sys::wasm_bindgen::UnwrapThrowExt as _;
+
+use crate::{
+    ssr::{Ssr, SsrCx},
+    BuildCx, RebuildCx, Web,
+};
+
+/// In debug builds, sets the `data-ravel-src` attribute on the parent element
+/// to the source location (`file.rs:line`) of the code which created it.
+///
+/// This is added to every element created by the functions in [`crate::el`],
+/// so that the code which produced an element can be found from the browser's
+/// developer tools. In release builds, and when rendering on the server, it
+/// does nothing, so that server markup doesn't reveal source paths or depend
+/// on the build profile.
+#[derive(Copy, Clone, Debug)]
+pub struct Src {
+    #[cfg(debug_assertions)]
+    location: &'static std::panic::Location<'static>,
+}
+
+impl Src {
+    /// The location of the caller, which is propagated through functions
+    /// annotated with `#[track_caller]`.
+    #[track_caller]
+    pub fn caller() -> Self {
+        Self {
+            #[cfg(debug_assertions)]
+            location: std::panic::Location::caller(),
+        }
+    }
+
+    fn value(self) -> Option<String> {
+        #[cfg(debug_assertions)]
+        return Some(format!(
+            "{}:{}",
+            self.location.file(),
+            self.location.line()
+        ));
+
+        #[cfg(not(debug_assertions))]
+        None
+    }
+}
+
+const SRC: &str = "data-ravel-src";
+
+impl Builder<Web> for Src {
+    type State = ();
+
+    fn build(self, cx: BuildCx) -> Self::State {
+        if let Some(value) = self.value() {
+            cx.position.parent.set_attribute(SRC, &value).unwrap_throw();
+        }
+    }
+
+    fn rebuild(self, _: RebuildCx, (): &mut Self::State) {}
+}
+
+impl Builder<Ssr> for Src {
+    type State = ();
+
+    fn build(self, _: SsrCx) -> Self::State {}
+
+    fn rebuild(self, cx: Infallible, _: &mut Self::State) {
+        match cx {}
+    }
+}
@@ -2,12 +2,16 @@
 //!
 //! Usually you shouldn't need to import or reference these directly.
 
-use std::marker::PhantomData;
+use std::{convert::Infallible, marker::PhantomData};
 
-use ravel::State;
+use ravel::{MemSize, State};
 use web_sys::wasm_bindgen::{JsValue, UnwrapThrowExt};
 
-use crate::{dom::Position, BuildCx, Builder, RebuildCx, ViewMarker, Web};
+use crate::{
+    dom::Position,
+    ssr::{Ssr, SsrCx},
+    stats, BuildCx, Builder, RebuildCx, ViewMarker, Web,
+};
 
 /// Trait to identify element types.
 pub trait ElKind: 'static {
@@ -41,6 +45,18 @@ impl<Kind: ElKind, Body: Builder<Web>> Builder<Web> for El<Kind, Body> {
     }
 }
 
+impl<Kind: ElKind, Body: Builder<Ssr>> Builder<Ssr> for El<Kind, Body> {
+    type State = Body::State;
+
+    fn build(self, cx: SsrCx) -> Self::State {
+        cx.element(Kind::NAME, self.body)
+    }
+
+    fn rebuild(self, cx: Infallible, _: &mut Self::State) {
+        match cx {}
+    }
+}
+
 /// The state of an [`El`].
 pub struct ElState<S> {
     node: web_sys::Element,
@@ -56,6 +72,12 @@ where
     }
 }
 
+impl<S: MemSize> MemSize for ElState<S> {
+    fn heap_size(&self) -> usize {
+        self.body.heap_size()
+    }
+}
+
 impl<S> ViewMarker for ElState<S> {}
 
 fn create_element(kind: &'static str) -> web_sys::Element {
@@ -67,6 +89,7 @@ fn build_el<Body: Builder<Web>>(
     el: web_sys::Element,
     body: Body,
 ) -> ElState<Body::State> {
+    stats::created();
     let state = body.build(BuildCx {
         position: Position {
             parent: &el,
@@ -113,6 +136,18 @@ macro_rules! make_el {
                 )
             }
         }
+
+        impl<Body: Builder<Ssr>> Builder<Ssr> for $t<Body> {
+            type State = Body::State;
+
+            fn build(self, cx: SsrCx) -> Self::State {
+                cx.element(stringify!($name), self.0)
+            }
+
+            fn rebuild(self, cx: Infallible, _: &mut Self::State) {
+                match cx {}
+            }
+        }
     };
 }
 
@@ -0,0 +1,469 @@
+//! Sources of time and randomness, which can be replaced for testing.
+//!
+//! [`timer`](crate::timer)s, [`transition`](crate::transition)s,
+//! [`spring`](crate::spring)s and [`run::timed`](crate::run::timed) read the
+//! time with [`now`] and wait with [`set_timeout`] and [`set_interval`].
+//! Components which need random numbers can get them from [`random`]. By
+//! default these use the browser, but a [`Clock`] or [`Rng`] can be
+//! registered in the [`Services`] (with [`Services::with_clock`] and
+//! [`Services::with_rng`]) to drive time-dependent UI deterministically, for
+//! example with a [`ManualClock`] and a [`SeededRng`]:
+//!
+//! ```ignore
+//! let clock = ManualClock::new(0.);
+//! provide_services(Services::new().with_clock(clock.clone()), ...);
+//!
+//! // Fires every timer due in the next second.
+//! clock.advance(1000.);
+//! ```
+//!
+//! Animations run by the browser itself, such as CSS transitions and
+//! [`animate`](crate::animate), aren't affected.
+
+use std::{
+    cell::{Cell, RefCell},
+    rc::{Rc, Weak},
+};
+
+use gloo_timers::callback;
+
+use crate::services::{try_service, Services};
+
+/// A source of the current time, which also schedules callbacks.
+pub trait Clock {
+    /// The current time, in milliseconds since the Unix epoch.
+    fn now(&self) -> f64;
+
+    /// Calls `callback` once, after `millis` milliseconds.
+    ///
+    /// By default, this uses the browser's `setTimeout`.
+    fn set_timeout(
+        &self,
+        millis: u32,
+        callback: Box<dyn FnOnce()>,
+    ) -> Scheduled {
+        let timeout = callback::Timeout::new(millis, callback);
+        Scheduled::new(move || drop(timeout))
+    }
+
+    /// Calls `callback` every `millis` milliseconds.
+    ///
+    /// By default, this uses the browser's `setInterval`.
+    fn set_interval(
+        &self,
+        millis: u32,
+        callback: Box<dyn FnMut()>,
+    ) -> Scheduled {
+        let interval = callback::Interval::new(millis, callback);
+        Scheduled::new(move || drop(interval))
+    }
+}
+
+impl<C: ?Sized + Clock> Clock for Box<C> {
+    fn now(&self) -> f64 {
+        (**self).now()
+    }
+
+    fn set_timeout(
+        &self,
+        millis: u32,
+        callback: Box<dyn FnOnce()>,
+    ) -> Scheduled {
+        (**self).set_timeout(millis, callback)
+    }
+
+    fn set_interval(
+        &self,
+        millis: u32,
+        callback: Box<dyn FnMut()>,
+    ) -> Scheduled {
+        (**self).set_interval(millis, callback)
+    }
+}
+
+impl<C: ?Sized + Clock> Clock for Rc<C> {
+    fn now(&self) -> f64 {
+        (**self).now()
+    }
+
+    fn set_timeout(
+        &self,
+        millis: u32,
+        callback: Box<dyn FnOnce()>,
+    ) -> Scheduled {
+        (**self).set_timeout(millis, callback)
+    }
+
+    fn set_interval(
+        &self,
+        millis: u32,
+        callback: Box<dyn FnMut()>,
+    ) -> Scheduled {
+        (**self).set_interval(millis, callback)
+    }
+}
+
+/// A callback scheduled by a [`Clock`], which is cancelled when this is
+/// dropped.
+#[must_use = "the callback is cancelled when this is dropped"]
+pub struct Scheduled {
+    cancel: Option<Box<dyn FnOnce()>>,
+}
+
+impl Scheduled {
+    /// A scheduled callback which is cancelled by calling `cancel`.
+    pub fn new(cancel: impl 'static + FnOnce()) -> Self {
+        Self {
+            cancel: Some(Box::new(cancel)),
+        }
+    }
+
+    /// Lets the callback run even after this is dropped.
+    pub fn forget(mut self) {
+        self.cancel = None;
+    }
+}
+
+impl Drop for Scheduled {
+    fn drop(&mut self) {
+        if let Some(cancel) = self.cancel.take() {
+            cancel()
+        }
+    }
+}
+
+/// The browser's clock.
+#[derive(Copy, Clone, Default, Debug)]
+pub struct SystemClock;
+
+impl Clock for SystemClock {
+    fn now(&self) -> f64 {
+        js_sys::Date::now()
+    }
+}
+
+/// A clock which only advances when told to, calling the callbacks which
+/// become due as it does.
+///
+/// Clones share the same time, so a test can keep one to advance the clock it
+/// registered with [`Services::with_clock`].
+#[derive(Clone, Default)]
+pub struct ManualClock(Rc<ManualClockInner>);
+
+#[derive(Default)]
+struct ManualClockInner {
+    now: Cell<f64>,
+    next_id: Cell<u64>,
+    pending: RefCell<Vec<Pending>>,
+}
+
+/// A callback scheduled on a [`ManualClock`].
+struct Pending {
+    id: u64,
+    due: f64,
+    /// The period of an interval.
+    period: Option<f64>,
+    callback: Rc<RefCell<dyn FnMut()>>,
+}
+
+impl ManualClock {
+    /// A clock stopped at `now`, in milliseconds since the Unix epoch.
+    pub fn new(now: f64) -> Self {
+        let clock = Self::default();
+        clock.0.now.set(now);
+        clock
+    }
+
+    /// Sets the current time, calling every callback due by then, in the
+    /// order they are due.
+    pub fn set(&self, now: f64) {
+        while let Some(callback) = self.next_due(now) {
+            (callback.borrow_mut())()
+        }
+        self.0.now.set(now);
+    }
+
+    /// Advances the current time by `ms` milliseconds, calling every callback
+    /// due by then, in the order they are due.
+    pub fn advance(&self, ms: f64) {
+        self.set(self.0.now.get() + ms)
+    }
+
+    /// Removes the earliest callback due by `until` (rescheduling it, if it
+    /// is an interval), and moves the time to when it was due.
+    fn next_due(&self, until: f64) -> Option<Rc<RefCell<dyn FnMut()>>> {
+        let mut pending = self.0.pending.borrow_mut();
+        let (index, _) = pending
+            .iter()
+            .enumerate()
+            .filter(|(_, p)| p.due <= until)
+            .min_by(|(_, a), (_, b)| a.due.total_cmp(&b.due))?;
+
+        let entry = &mut pending[index];
+        self.0.now.set(entry.due);
+        let callback = entry.callback.clone();
+        match entry.period {
+            Some(period) => entry.due += period,
+            None => {
+                pending.remove(index);
+            }
+        }
+        Some(callback)
+    }
+
+    fn schedule(
+        &self,
+        millis: u32,
+        period: Option<f64>,
+        callback: Rc<RefCell<dyn FnMut()>>,
+    ) -> Scheduled {
+        let id = self.0.next_id.get();
+        self.0.next_id.set(id + 1);
+        self.0.pending.borrow_mut().push(Pending {
+            id,
+            due: self.0.now.get() + f64::from(millis),
+            period,
+            callback,
+        });
+
+        let clock = Rc::downgrade(&self.0);
+        Scheduled::new(move || {
+            if let Some(clock) = Weak::upgrade(&clock) {
+                clock.pending.borrow_mut().retain(|p| p.id != id)
+            }
+        })
+    }
+}
+
+impl Clock for ManualClock {
+    fn now(&self) -> f64 {
+        self.0.now.get()
+    }
+
+    fn set_timeout(
+        &self,
+        millis: u32,
+        callback: Box<dyn FnOnce()>,
+    ) -> Scheduled {
+        let mut callback = Some(callback);
+        let callback = Rc::new(RefCell::new(move || {
+            if let Some(callback) = callback.take() {
+                callback()
+            }
+        }));
+        self.schedule(millis, None, callback)
+    }
+
+    fn set_interval(
+        &self,
+        millis: u32,
+        callback: Box<dyn FnMut()>,
+    ) -> Scheduled {
+        // Like browsers, don't allow an interval to fire continuously.
+        let period = f64::from(millis.max(1));
+        self.schedule(millis, Some(period), Rc::new(RefCell::new(callback)))
+    }
+}
+
+impl std::fmt::Debug for ManualClock {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_tuple("ManualClock")
+            .field(&self.0.now.get())
+            .finish()
+    }
+}
+
+/// A source of random numbers.
+pub trait Rng {
+    /// A uniformly distributed number in `[0, 1)`.
+    fn next_f64(&self) -> f64;
+}
+
+/// The browser's random number generator (`Math.random`).
+#[derive(Copy, Clone, Default, Debug)]
+pub struct SystemRng;
+
+impl Rng for SystemRng {
+    fn next_f64(&self) -> f64 {
+        js_sys::Math::random()
+    }
+}
+
+/// A deterministic random number generator, producing the same sequence for
+/// the same seed.
+///
+/// This is not suitable for cryptography.
+#[derive(Clone, Debug)]
+pub struct SeededRng(Cell<u64>);
+
+impl SeededRng {
+    /// A generator starting from `seed`.
+    pub fn new(seed: u64) -> Self {
+        Self(Cell::new(seed))
+    }
+}
+
+impl Rng for SeededRng {
+    fn next_f64(&self) -> f64 {
+        // SplitMix64.
+        let state = self.0.get().wrapping_add(0x9e3779b97f4a7c15);
+        self.0.set(state);
+
+        let mut z = state;
+        z = (z ^ (z >> 30)).wrapping_mul(0xbf58476d1ce4e5b9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94d049bb133111eb);
+        z ^= z >> 31;
+
+        (z >> 11) as f64 / (1u64 << 53) as f64
+    }
+}
+
+impl Services {
+    /// Registers the [`Clock`] used by [`now`].
+    pub fn with_clock(self, clock: impl 'static + Clock) -> Self {
+        self.with::<Box<dyn Clock>>(Box::new(clock))
+    }
+
+    /// Registers the [`Rng`] used by [`random`].
+    pub fn with_rng(self, rng: impl 'static + Rng) -> Self {
+        self.with::<Box<dyn Rng>>(Box::new(rng))
+    }
+}
+
+/// The provided [`Clock`], or else the [`SystemClock`].
+///
+/// Components which schedule callbacks outside of building (for example,
+/// after rendering) look the clock up while building, and keep it.
+pub(crate) fn clock() -> Rc<dyn Clock> {
+    match try_service::<Box<dyn Clock>>() {
+        Some(clock) => clock,
+        None => Rc::new(SystemClock),
+    }
+}
+
+/// The current time, in milliseconds since the Unix epoch, according to the
+/// provided [`Clock`] or else the [`SystemClock`].
+pub fn now() -> f64 {
+    clock().now()
+}
+
+/// Calls `callback` once, after `millis` milliseconds, according to the
+/// provided [`Clock`] or else the browser.
+pub fn set_timeout(
+    millis: u32,
+    callback: impl 'static + FnOnce(),
+) -> Scheduled {
+    clock().set_timeout(millis, Box::new(callback))
+}
+
+/// Calls `callback` every `millis` milliseconds, according to the provided
+/// [`Clock`] or else the browser.
+pub fn set_interval(
+    millis: u32,
+    callback: impl 'static + FnMut(),
+) -> Scheduled {
+    clock().set_interval(millis, Box::new(callback))
+}
+
+/// A uniformly distributed number in `[0, 1)`, according to the provided
+/// [`Rng`] or else the [`SystemRng`].
+pub fn random() -> f64 {
+    match try_service::<Box<dyn Rng>>() {
+        Some(rng) => rng.next_f64(),
+        None => SystemRng.next_f64(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{cell::RefCell, rc::Rc};
+
+    use super::{Clock, ManualClock};
+
+    type Log = Rc<RefCell<Vec<(&'static str, f64)>>>;
+
+    fn log() -> (Log, ManualClock) {
+        (Rc::default(), ManualClock::new(0.))
+    }
+
+    #[test]
+    fn clones_share_time() {
+        let clock = ManualClock::new(10.);
+        let registered: Box<dyn Clock> = Box::new(clock.clone());
+        clock.advance(5.);
+        assert_eq!(registered.now(), 15.);
+    }
+
+    #[test]
+    fn timeouts_fire_in_order_when_due() {
+        let (log, clock) = log();
+        for (name, millis) in [("b", 20), ("a", 10), ("c", 30)] {
+            let (log, c) = (log.clone(), clock.clone());
+            clock
+                .set_timeout(
+                    millis,
+                    Box::new(move || log.borrow_mut().push((name, c.now()))),
+                )
+                .forget();
+        }
+
+        clock.advance(25.);
+        assert_eq!(*log.borrow(), [("a", 10.), ("b", 20.)]);
+        assert_eq!(clock.now(), 25.);
+
+        clock.advance(25.);
+        assert_eq!(*log.borrow(), [("a", 10.), ("b", 20.), ("c", 30.)]);
+    }
+
+    #[test]
+    fn intervals_repeat() {
+        let (log, clock) = log();
+        let c = clock.clone();
+        let l = log.clone();
+        let _interval = clock.set_interval(
+            10,
+            Box::new(move || l.borrow_mut().push(("i", c.now()))),
+        );
+
+        clock.advance(35.);
+        assert_eq!(*log.borrow(), [("i", 10.), ("i", 20.), ("i", 30.)]);
+    }
+
+    #[test]
+    fn dropping_cancels() {
+        let (log, clock) = log();
+        let l = log.clone();
+        let timeout = clock
+            .set_timeout(10, Box::new(move || l.borrow_mut().push(("t", 0.))));
+        drop(timeout);
+
+        clock.advance(20.);
+        assert!(log.borrow().is_empty());
+    }
+
+    #[test]
+    fn callbacks_can_schedule_more() {
+        let (log, clock) = log();
+        let c = clock.clone();
+        let l = log.clone();
+        clock
+            .set_timeout(
+                10,
+                Box::new(move || {
+                    let l = l.clone();
+                    let c2 = c.clone();
+                    c.set_timeout(
+                        5,
+                        Box::new(move || {
+                            l.borrow_mut().push(("nested", c2.now()))
+                        }),
+                    )
+                    .forget();
+                }),
+            )
+            .forget();
+
+        clock.advance(20.);
+        assert_eq!(*log.borrow(), [("nested", 15.)]);
+    }
+}
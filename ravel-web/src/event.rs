@@ -1,21 +1,44 @@
 //! HTML events.
 
-use std::{cell::RefCell, marker::PhantomData, ops::DerefMut, rc::Rc};
+use std::{
+    any::TypeId,
+    cell::{Cell, RefCell},
+    collections::{HashSet, VecDeque},
+    convert::Infallible,
+    marker::PhantomData,
+    rc::{Rc, Weak},
+    sync::Arc,
+};
 
-use ravel::State;
-use web_sys::wasm_bindgen::JsValue;
+use atomic_waker::AtomicWaker;
+use ravel::{MemSize, State};
+use web_sys::wasm_bindgen::JsCast;
 
-use crate::{BuildCx, Builder, RebuildCx, Web};
+use crate::{
+    command,
+    form::{checked_of, value_of},
+    ssr::{Ssr, SsrCx},
+    BuildCx, Builder, RebuildCx, Web,
+};
 
 /// Trait to identify event types.
 pub trait EventKind: 'static {
     /// The name of the event.
     const NAME: &'static str;
 
+    /// The type of the event object passed to handlers.
+    type Event: JsCast;
+
     /// Active events may use [`web_sys::Event::prevent_default`]. By default,
     /// this is
     /// [disabled to improve performance](https://developer.mozilla.org/en-US/docs/Web/API/EventTarget/addEventListener#passive).
     const ACTIVE: bool = false;
+
+    /// Capturing handlers receive events in the
+    /// [capture phase](https://developer.mozilla.org/en-US/docs/Web/API/EventTarget/addEventListener#capture),
+    /// before any handlers on descendant elements, rather than as they bubble
+    /// up.
+    const CAPTURE: bool = false;
 }
 
 /// An "active" version of an [`EventKind`], which may use
@@ -27,67 +50,379 @@ pub struct Active<K: EventKind>(pub K);
 impl<K: EventKind> EventKind for Active<K> {
     const NAME: &'static str = K::NAME;
     const ACTIVE: bool = true;
+    const CAPTURE: bool = K::CAPTURE;
+
+    type Event = K::Event;
+}
+
+/// A capturing version of an [`EventKind`], which is handled on the way down
+/// to the target, before any handlers on descendant elements.
+///
+/// This wraps another kind, setting [`EventKind::CAPTURE`] to `true`. It can
+/// be combined with [`Active`], as in `Active(Capture(Click))`.
+pub struct Capture<K: EventKind>(pub K);
+
+impl<K: EventKind> EventKind for Capture<K> {
+    const NAME: &'static str = K::NAME;
+    const ACTIVE: bool = K::ACTIVE;
+    const CAPTURE: bool = true;
+
+    type Event = K::Event;
+}
+
+/// Determines how events are buffered when several arrive before the next
+/// frame.
+pub trait QueuePolicy: 'static {
+    /// Adds a newly received event to the queue of pending events.
+    fn push(
+        &mut self,
+        queue: &mut VecDeque<web_sys::Event>,
+        event: web_sys::Event,
+    );
+}
+
+/// Keeps up to the given number of pending events, dropping the oldest ones
+/// beyond that.
+///
+/// This suits high frequency events where only recent history matters. The
+/// default capacity is [`EVENT_QUEUE_CAPACITY`]. A capacity of `0` drops every
+/// event.
+#[derive(Copy, Clone, Debug)]
+pub struct Bounded(pub usize);
+
+impl Default for Bounded {
+    fn default() -> Self {
+        Self(EVENT_QUEUE_CAPACITY)
+    }
+}
+
+impl QueuePolicy for Bounded {
+    fn push(
+        &mut self,
+        queue: &mut VecDeque<web_sys::Event>,
+        event: web_sys::Event,
+    ) {
+        if self.0 == 0 {
+            return;
+        }
+
+        while queue.len() >= self.0 {
+            queue.pop_front();
+        }
+        queue.push_back(event);
+    }
+}
+
+/// Keeps every pending event. Suitable for discrete events which must never be
+/// dropped.
+///
+/// This is the default policy.
+#[derive(Copy, Clone, Debug, Default)]
+pub struct KeepAll;
+
+impl QueuePolicy for KeepAll {
+    fn push(
+        &mut self,
+        queue: &mut VecDeque<web_sys::Event>,
+        event: web_sys::Event,
+    ) {
+        queue.push_back(event);
+    }
+}
+
+/// Keeps only the most recent pending event. Suitable for high frequency
+/// events like `mousemove`, where only the latest state matters.
+#[derive(Copy, Clone, Debug, Default)]
+pub struct KeepLatest;
+
+impl QueuePolicy for KeepLatest {
+    fn push(
+        &mut self,
+        queue: &mut VecDeque<web_sys::Event>,
+        event: web_sys::Event,
+    ) {
+        queue.clear();
+        queue.push_back(event);
+    }
+}
+
+/// Merges each new event with the pending one using a callback, so at most one
+/// event is delivered per frame.
+///
+/// The callback receives the pending event and the new event, in that order.
+#[derive(Copy, Clone, Debug)]
+pub struct Coalesce<F>(pub F);
+
+impl<F> QueuePolicy for Coalesce<F>
+where
+    F: 'static + FnMut(web_sys::Event, web_sys::Event) -> web_sys::Event,
+{
+    fn push(
+        &mut self,
+        queue: &mut VecDeque<web_sys::Event>,
+        event: web_sys::Event,
+    ) {
+        let event = match queue.pop_back() {
+            Some(pending) => (self.0)(pending, event),
+            None => event,
+        };
+        queue.push_back(event);
+    }
 }
 
 /// An event handler.
-pub struct On<Kind: EventKind, Action> {
+pub struct On<Kind: EventKind, Action, Policy = KeepAll> {
     action: Action,
+    policy: Policy,
+    stop_propagation: bool,
+    fixed: bool,
     kind: PhantomData<Kind>,
 }
 
-impl<Kind: EventKind, Action: 'static> Builder<Web> for On<Kind, Action> {
-    type State = OnState<Action>;
+impl<Kind: EventKind, Action, Policy> On<Kind, Action, Policy> {
+    /// Sets the [`QueuePolicy`] for events which arrive before the next frame.
+    pub fn policy<P: QueuePolicy>(self, policy: P) -> On<Kind, Action, P> {
+        On {
+            action: self.action,
+            policy,
+            stop_propagation: self.stop_propagation,
+            fixed: self.fixed,
+            kind: PhantomData,
+        }
+    }
+
+    /// Stops events from propagating any further once they reach this
+    /// handler.
+    ///
+    /// Since the action only runs on the next frame, this happens immediately
+    /// when the event is dispatched, regardless of the action. Like the action,
+    /// it is updated on every rebuild, so a handler can start or stop doing
+    /// this between frames.
+    pub fn stop_propagation(self) -> Self {
+        Self {
+            stop_propagation: true,
+            ..self
+        }
+    }
+}
+
+impl<Kind: EventKind, Action, Policy> Builder<Ssr>
+    for On<Kind, Action, Policy>
+{
+    type State = ();
+
+    fn build(self, _: SsrCx) -> Self::State {}
+
+    fn rebuild(self, cx: Infallible, _: &mut Self::State) {
+        match cx {}
+    }
+}
+
+impl<Kind: EventKind, Action: 'static, Policy: QueuePolicy> Builder<Web>
+    for On<Kind, Action, Policy>
+{
+    type State = OnState<Kind, Action>;
 
     fn build(self, cx: BuildCx) -> Self::State {
-        let waker = cx.position.waker.clone();
+        if self.fixed {
+            warn_if_capturing::<Action>();
+        }
 
-        let cell = EventCell::new();
+        let event = EventCell::new();
+        let stop_propagation = Rc::new(Cell::new(self.stop_propagation));
 
         OnState {
-            event: cell.clone(),
-            _handle: gloo_events::EventListener::new_with_options(
+            _handle: listen::<Kind>(
                 cx.position.parent,
-                Kind::NAME,
-                gloo_events::EventListenerOptions {
-                    passive: !Kind::ACTIVE,
-                    ..Default::default()
-                },
-                move |e| {
-                    cell.put(e.clone());
-                    waker.wake();
-                },
+                cx.position.waker,
+                &event,
+                self.policy,
+                stop_propagation.clone(),
             ),
+            event,
+            stop_propagation,
             action: self.action,
+            kind: PhantomData,
         }
     }
 
     fn rebuild(self, _: RebuildCx, state: &mut Self::State) {
+        state.stop_propagation.set(self.stop_propagation);
+        if !self.fixed {
+            state.action = self.action;
+        }
+    }
+}
+
+/// Warns, once per closure type, when a [`static_handler`] is given a closure
+/// which captures something. Its captures are frozen when the handler is
+/// built, so any that change between frames are silently ignored.
+fn warn_if_capturing<Action: 'static>() {
+    if !cfg!(debug_assertions) || std::mem::size_of::<Action>() == 0 {
+        return;
+    }
+
+    thread_local! {
+        static WARNED: RefCell<HashSet<TypeId>> = RefCell::default();
+    }
+
+    if WARNED.with_borrow_mut(|w| w.insert(TypeId::of::<Action>())) {
+        web_sys::console::warn_1(
+            &format!(
+                "static_handler was given a capturing closure ({}), whose \
+                captures are never updated after the first build",
+                std::any::type_name::<Action>()
+            )
+            .into(),
+        );
+    }
+}
+
+/// An event handler attached to the global `window`, rather than the parent
+/// element.
+pub struct OnWindow<Kind: EventKind, Action, Policy = KeepAll> {
+    action: Action,
+    policy: Policy,
+    stop_propagation: bool,
+    kind: PhantomData<Kind>,
+}
+
+impl<Kind: EventKind, Action, Policy> OnWindow<Kind, Action, Policy> {
+    /// Sets the [`QueuePolicy`] for events which arrive before the next frame.
+    pub fn policy<P: QueuePolicy>(
+        self,
+        policy: P,
+    ) -> OnWindow<Kind, Action, P> {
+        OnWindow {
+            action: self.action,
+            policy,
+            stop_propagation: self.stop_propagation,
+            kind: PhantomData,
+        }
+    }
+
+    /// Stops events from propagating any further once they reach this
+    /// handler. This is mostly useful with [`Capture`], to intercept events
+    /// before they reach any element.
+    ///
+    /// See [`On::stop_propagation`].
+    pub fn stop_propagation(self) -> Self {
+        Self {
+            stop_propagation: true,
+            ..self
+        }
+    }
+}
+
+impl<Kind: EventKind, Action, Policy> Builder<Ssr>
+    for OnWindow<Kind, Action, Policy>
+{
+    type State = ();
+
+    fn build(self, _: SsrCx) -> Self::State {}
+
+    fn rebuild(self, cx: Infallible, _: &mut Self::State) {
+        match cx {}
+    }
+}
+
+impl<Kind: EventKind, Action: 'static, Policy: QueuePolicy> Builder<Web>
+    for OnWindow<Kind, Action, Policy>
+{
+    type State = OnState<Kind, Action>;
+
+    fn build(self, cx: BuildCx) -> Self::State {
+        let event = EventCell::new();
+        let stop_propagation = Rc::new(Cell::new(self.stop_propagation));
+
+        OnState {
+            _handle: listen::<Kind>(
+                &gloo_utils::window(),
+                cx.position.waker,
+                &event,
+                self.policy,
+                stop_propagation.clone(),
+            ),
+            event,
+            stop_propagation,
+            action: self.action,
+            kind: PhantomData,
+        }
+    }
+
+    fn rebuild(self, _: RebuildCx, state: &mut Self::State) {
+        state.stop_propagation.set(self.stop_propagation);
         state.action = self.action;
     }
 }
 
+pub(crate) fn listen<Kind: EventKind>(
+    target: &web_sys::EventTarget,
+    waker: &Arc<AtomicWaker>,
+    cell: &EventCell,
+    mut policy: impl QueuePolicy,
+    stop_propagation: Rc<Cell<bool>>,
+) -> gloo_events::EventListener {
+    let waker = waker.clone();
+    let cell = cell.clone();
+
+    gloo_events::EventListener::new_with_options(
+        target,
+        Kind::NAME,
+        gloo_events::EventListenerOptions {
+            passive: !Kind::ACTIVE,
+            phase: if Kind::CAPTURE {
+                gloo_events::EventListenerPhase::Capture
+            } else {
+                gloo_events::EventListenerPhase::Bubble
+            },
+        },
+        move |e| {
+            if stop_propagation.get() {
+                e.stop_propagation();
+            }
+            cell.put(&mut policy, e.clone());
+            waker.wake();
+        },
+    )
+}
+
 /// The state of an [`On`].
-pub struct OnState<Action> {
+///
+/// Events are passed to the action as [`EventKind::Event`] without a runtime
+/// type check, since browsers sometimes dispatch a plain [`web_sys::Event`]
+/// where a more specific type is usual (for example, `input` on a checkbox).
+pub struct OnState<Kind, Action> {
     event: EventCell,
     _handle: gloo_events::EventListener,
+    stop_propagation: Rc<Cell<bool>>,
     action: Action,
+    kind: PhantomData<Kind>,
 }
 
-impl<Action: 'static + FnMut(&mut Output, web_sys::Event), Output: 'static>
-    State<Output> for OnState<Action>
+impl<Kind, Action, Output> State<Output> for OnState<Kind, Action>
+where
+    Kind: EventKind,
+    Action: 'static + FnMut(&mut Output, Kind::Event),
+    Output: 'static,
 {
     fn run(&mut self, output: &mut Output) {
-        let event = self.event.take();
-        if !event.is_null() {
-            (self.action)(output, event);
+        while let Some(event) = self.event.take() {
+            (self.action)(output, event.unchecked_into());
         }
     }
 }
 
+impl<Kind, Action> MemSize for OnState<Kind, Action> {
+    fn heap_size(&self) -> usize {
+        0
+    }
+}
+
 /// An event handler.
 pub fn on<
     Kind: EventKind,
-    Action: 'static + FnMut(&mut Output, web_sys::Event),
+    Action: 'static + FnMut(&mut Output, Kind::Event),
     Output: 'static,
 >(
     _: Kind,
@@ -95,6 +430,36 @@ pub fn on<
 ) -> On<Kind, Action> {
     On {
         action,
+        policy: KeepAll,
+        stop_propagation: false,
+        fixed: false,
+        kind: PhantomData,
+    }
+}
+
+/// An event handler whose action is installed once, when it is built, and never
+/// replaced.
+///
+/// [`on`] replaces its action on every rebuild, so that it can capture values
+/// from the current frame. When the action doesn't capture anything (for
+/// example, it only uses the event and the model), this skips that work, which
+/// adds up for large lists of handlers.
+///
+/// In debug builds, a warning is logged to the console if the action captures
+/// anything, since any changes to those captures would be ignored.
+pub fn static_handler<
+    Kind: EventKind,
+    Action: 'static + FnMut(&mut Output, Kind::Event),
+    Output: 'static,
+>(
+    _: Kind,
+    action: Action,
+) -> On<Kind, Action> {
+    On {
+        action,
+        policy: KeepAll,
+        stop_propagation: false,
+        fixed: true,
         kind: PhantomData,
     }
 }
@@ -107,44 +472,395 @@ pub fn on_<
 >(
     _: Kind,
     mut action: Action,
-) -> On<Kind, impl 'static + FnMut(&mut Output, web_sys::Event)> {
+) -> On<Kind, impl 'static + FnMut(&mut Output, Kind::Event)> {
     On {
         action: move |o: &mut _, _: _| action(o),
+        policy: KeepAll,
+        stop_propagation: false,
+        fixed: false,
         kind: PhantomData,
     }
 }
 
+/// An event handler which may return a command, to be executed by the event
+/// loop after the rebuild.
+///
+/// See [`command`].
+pub fn on_command<
+    Kind: EventKind,
+    Action: 'static + FnMut(&mut Output, Kind::Event) -> Option<Cmd>,
+    Output: 'static,
+    Cmd: 'static,
+>(
+    _: Kind,
+    mut action: Action,
+) -> On<Kind, impl 'static + FnMut(&mut Output, Kind::Event)> {
+    On {
+        action: move |o: &mut _, e: _| {
+            if let Some(command) = action(o, e) {
+                command::emit(command)
+            }
+        },
+        policy: KeepAll,
+        stop_propagation: false,
+        fixed: false,
+        kind: PhantomData,
+    }
+}
+
+/// Like [`on_command`], for handlers which do not need access to the
+/// [`web_sys::Event`] data.
+pub fn on_command_<
+    Kind: EventKind,
+    Action: 'static + FnMut(&mut Output) -> Option<Cmd>,
+    Output: 'static,
+    Cmd: 'static,
+>(
+    kind: Kind,
+    mut action: Action,
+) -> On<Kind, impl 'static + FnMut(&mut Output, Kind::Event)> {
+    on_command(kind, move |o: &mut _, _: _| action(o))
+}
+
+/// An event handler attached to the global `window`.
+///
+/// This is useful for events which are not specific to any element, such as
+/// `resize` or `keydown` shortcuts. The listener is removed when the component
+/// is removed.
+pub fn on_window<
+    Kind: EventKind,
+    Action: 'static + FnMut(&mut Output, Kind::Event),
+    Output: 'static,
+>(
+    _: Kind,
+    action: Action,
+) -> OnWindow<Kind, Action> {
+    OnWindow {
+        action,
+        policy: KeepAll,
+        stop_propagation: false,
+        kind: PhantomData,
+    }
+}
+
+/// An error which escaped all application code, reported on the global
+/// `window`.
+#[derive(Clone, Debug)]
+pub enum GlobalError {
+    /// An uncaught exception (the `error` event).
+    Error(web_sys::ErrorEvent),
+    /// A rejected promise without a handler (the `unhandledrejection` event).
+    UnhandledRejection(web_sys::PromiseRejectionEvent),
+}
+
+impl GlobalError {
+    /// A human readable description of the error.
+    pub fn message(&self) -> String {
+        match self {
+            GlobalError::Error(e) => e.message(),
+            GlobalError::UnhandledRejection(e) => {
+                let reason = e.reason();
+                match reason.dyn_ref::<js_sys::Error>() {
+                    Some(error) => error.message().into(),
+                    None => reason
+                        .as_string()
+                        .unwrap_or_else(|| format!("{reason:?}")),
+                }
+            }
+        }
+    }
+}
+
+/// A handler for uncaught errors and unhandled promise rejections anywhere in
+/// the page, created with [`on_global_error`].
+pub struct OnGlobalError<Action> {
+    action: Action,
+}
+
+impl<Action> Builder<Ssr> for OnGlobalError<Action> {
+    type State = ();
+
+    fn build(self, _: SsrCx) -> Self::State {}
+
+    fn rebuild(self, cx: Infallible, _: &mut Self::State) {
+        match cx {}
+    }
+}
+
+impl<Action: 'static> Builder<Web> for OnGlobalError<Action> {
+    type State = GlobalErrorState<Action>;
+
+    fn build(self, cx: BuildCx) -> Self::State {
+        let window = gloo_utils::window();
+        let event = EventCell::new();
+
+        GlobalErrorState {
+            _handles: [
+                listen::<Error>(
+                    &window,
+                    cx.position.waker,
+                    &event,
+                    KeepAll,
+                    Rc::default(),
+                ),
+                listen::<UnhandledRejection>(
+                    &window,
+                    cx.position.waker,
+                    &event,
+                    KeepAll,
+                    Rc::default(),
+                ),
+            ],
+            event,
+            action: self.action,
+        }
+    }
+
+    fn rebuild(self, _: RebuildCx, state: &mut Self::State) {
+        state.action = self.action;
+    }
+}
+
+/// The state of an [`OnGlobalError`].
+pub struct GlobalErrorState<Action> {
+    event: EventCell,
+    _handles: [gloo_events::EventListener; 2],
+    action: Action,
+}
+
+impl<Action> MemSize for GlobalErrorState<Action> {
+    fn heap_size(&self) -> usize {
+        0
+    }
+}
+
+impl<Action: 'static + FnMut(&mut Output, GlobalError), Output: 'static>
+    State<Output> for GlobalErrorState<Action>
+{
+    fn run(&mut self, output: &mut Output) {
+        while let Some(event) = self.event.take() {
+            // Classify by the event name, and skip anything of an unexpected
+            // type (such as a plain `Event` dispatched by a script).
+            let name = event.type_();
+            let error = if name == Error::NAME {
+                event.dyn_into().ok().map(GlobalError::Error)
+            } else if name == UnhandledRejection::NAME {
+                event.dyn_into().ok().map(GlobalError::UnhandledRejection)
+            } else {
+                None
+            };
+
+            if let Some(error) = error {
+                (self.action)(output, error);
+            }
+        }
+    }
+}
+
+/// Handles uncaught errors and unhandled promise rejections anywhere in the
+/// page, for example to show a "something went wrong" message.
+///
+/// Errors are delivered in the order they occurred, and are never dropped.
+pub fn on_global_error<
+    Action: 'static + FnMut(&mut Output, GlobalError),
+    Output: 'static,
+>(
+    action: Action,
+) -> OnGlobalError<Action> {
+    OnGlobalError { action }
+}
+
+/// A [`Change`] event handler, which receives the committed `value` of an
+/// `<input>`, `<select>` or `<textarea>`.
+pub fn on_change_value<
+    Action: 'static + FnMut(&mut Output, String),
+    Output: 'static,
+>(
+    mut action: Action,
+) -> On<Change, impl 'static + FnMut(&mut Output, web_sys::Event)> {
+    on(Change, move |o: &mut _, e| {
+        if let Some(value) = target_value(&e) {
+            action(o, value)
+        }
+    })
+}
+
+/// A [`Change`] event handler, which receives the committed `checked` state of
+/// a checkbox or radio `<input>`.
+pub fn on_change_checked<
+    Action: 'static + FnMut(&mut Output, bool),
+    Output: 'static,
+>(
+    mut action: Action,
+) -> On<Change, impl 'static + FnMut(&mut Output, web_sys::Event)> {
+    on(Change, move |o: &mut _, e| {
+        if let Some(checked) = target_checked(&e) {
+            action(o, checked)
+        }
+    })
+}
+
+/// Reads the current `value` of an event's target, if it is an `<input>`,
+/// `<select>` or `<textarea>`.
+pub fn target_value(e: &web_sys::Event) -> Option<String> {
+    value_of(&e.target()?)
+}
+
+/// Reads the current `checked` state of an event's target, if it is an
+/// `<input>`.
+pub fn target_checked(e: &web_sys::Event) -> Option<bool> {
+    checked_of(&e.target()?)
+}
+
+/// The default maximum number of events buffered between frames by a
+/// [`Bounded`] queue.
+pub const EVENT_QUEUE_CAPACITY: usize = 32;
+
+type Queue = RefCell<VecDeque<web_sys::Event>>;
+
+/// Events received since the last frame, in the order they occurred.
 #[derive(Clone)]
-struct EventCell(Rc<RefCell<web_sys::Event>>);
+pub(crate) struct EventCell {
+    queue: Rc<Queue>,
+    gate: Option<Rc<Gate>>,
+}
 
 impl EventCell {
-    fn new() -> Self {
-        Self(Rc::new(RefCell::new(JsValue::NULL.into())))
+    pub(crate) fn new() -> Self {
+        let queue = Rc::new(Queue::default());
+        let gate = GATE.with_borrow(Clone::clone);
+        if let Some(gate) = &gate {
+            gate.register(&queue);
+        }
+
+        Self { queue, gate }
     }
 
-    fn take(&self) -> web_sys::Event {
-        std::mem::replace(self.0.borrow_mut().deref_mut(), JsValue::NULL.into())
+    pub(crate) fn take(&self) -> Option<web_sys::Event> {
+        self.queue.borrow_mut().pop_front()
     }
 
-    fn put(&self, event: web_sys::Event) {
-        let event = std::mem::replace(self.0.borrow_mut().deref_mut(), event);
-        debug_assert!(event.is_null())
+    fn put(&self, policy: &mut impl QueuePolicy, event: web_sys::Event) {
+        if self.gate.as_ref().is_none_or(|gate| gate.is_open()) {
+            policy.push(&mut self.queue.borrow_mut(), event);
+        }
+    }
+}
+
+thread_local! {
+    static GATE: RefCell<Option<Rc<Gate>>> = const { RefCell::new(None) };
+}
+
+/// Discards the events of a part of the tree which is set aside, such as a
+/// state kept by [`AnyView::pooled`](crate::AnyView::pooled).
+///
+/// Closing a gate clears every [`EventCell`] created in its
+/// [`scope`](Self::scope), including in nested gates, and they drop any event
+/// which arrives until it is opened again.
+pub(crate) struct Gate {
+    open: Cell<bool>,
+    parent: Option<Rc<Gate>>,
+    queues: RefCell<Vec<Weak<Queue>>>,
+}
+
+impl Gate {
+    /// An open gate, nested in the one whose scope this is called in.
+    pub(crate) fn new() -> Rc<Self> {
+        Rc::new(Self {
+            open: Cell::new(true),
+            parent: GATE.with_borrow(Clone::clone),
+            queues: RefCell::default(),
+        })
+    }
+
+    /// Runs `f`, attaching the event cells it creates to this gate.
+    pub(crate) fn scope<R>(self: &Rc<Self>, f: impl FnOnce() -> R) -> R {
+        struct Restore(Option<Rc<Gate>>);
+
+        impl Drop for Restore {
+            fn drop(&mut self) {
+                GATE.set(self.0.take());
+            }
+        }
+
+        let _restore = Restore(GATE.replace(Some(self.clone())));
+        f()
+    }
+
+    pub(crate) fn open(&self) {
+        self.open.set(true);
+    }
+
+    pub(crate) fn close(&self) {
+        self.open.set(false);
+        self.queues
+            .borrow_mut()
+            .retain(|queue| match queue.upgrade() {
+                Some(queue) => {
+                    queue.borrow_mut().clear();
+                    true
+                }
+                None => false,
+            });
+    }
+
+    fn is_open(&self) -> bool {
+        self.open.get() && self.parent.as_ref().is_none_or(|p| p.is_open())
+    }
+
+    fn register(&self, queue: &Rc<Queue>) {
+        let mut gate = Some(self);
+        while let Some(g) = gate {
+            let mut queues = g.queues.borrow_mut();
+            if queues.len() == queues.capacity() {
+                queues.retain(|queue| queue.strong_count() > 0);
+            }
+            queues.push(Rc::downgrade(queue));
+            gate = g.parent.as_deref();
+        }
     }
 }
 
 macro_rules! make_event {
-    ($name:ident, $t:ident) => {
-        #[doc = concat!("`", stringify!($name), "` event.")]
+    ($name:ident, $t:ident, $event:ty) => {
+        make_event!(
+            $name,
+            $t,
+            $event,
+            concat!("`", stringify!($name), "` event.")
+        );
+    };
+    ($name:ident, $t:ident, $event:ty, $doc:expr) => {
+        #[doc = $doc]
         #[derive(Copy, Clone)]
         pub struct $t;
 
         impl EventKind for $t {
             const NAME: &'static str = stringify!($name);
+            type Event = $event;
         }
     };
 }
 
-make_event!(dblclick, DblClick);
-make_event!(click, Click);
-make_event!(input, InputEvent);
-make_event!(submit, Submit);
+make_event!(
+    input,
+    InputEvent,
+    web_sys::InputEvent,
+    "`input` event.\n\n\
+    Fires on every edit of an `<input>` or `<textarea>` (for example, each \
+    keystroke), and whenever a `<select>` or checkbox changes. Use this to \
+    keep the model in sync while the user is typing. See also [`Change`]."
+);
+make_event!(
+    change,
+    Change,
+    web_sys::Event,
+    "`change` event.\n\n\
+    Fires when the user commits a change to a form control: when a text \
+    field loses focus after being edited, or immediately for a `<select>`, \
+    checkbox or radio button. Use this for commit-time handling, such as \
+    validation. See also [`InputEvent`], [`on_change_value`] and \
+    [`on_change_checked`]."
+);
+
+include!(concat!(env!("OUT_DIR"), "/gen_event.rs"));
@@ -0,0 +1,247 @@
+//! Files pasted from the clipboard or dropped onto elements.
+
+use std::{
+    cell::RefCell, collections::VecDeque, convert::Infallible, rc::Rc,
+    sync::Arc,
+};
+
+use atomic_waker::AtomicWaker;
+use ravel::{Builder, MemSize, State};
+use web_sys::wasm_bindgen::{JsCast as _, JsValue, UnwrapThrowExt as _};
+
+use crate::{
+    ssr::{Ssr, SsrCx},
+    BuildCx, RebuildCx, Web,
+};
+
+/// A file received from the clipboard or a drop, with an object URL which can
+/// be used as the `src` of an `<img>`, for example.
+///
+/// The object URL is revoked when the last clone is dropped.
+#[derive(Clone, Debug)]
+pub struct ReceivedFile {
+    file: web_sys::File,
+    url: Rc<ObjectUrl>,
+}
+
+#[derive(Debug)]
+struct ObjectUrl(String);
+
+impl Drop for ObjectUrl {
+    fn drop(&mut self) {
+        let _ = web_sys::Url::revoke_object_url(&self.0);
+    }
+}
+
+impl ReceivedFile {
+    fn new(file: web_sys::File) -> Self {
+        let url =
+            web_sys::Url::create_object_url_with_blob(&file).unwrap_throw();
+
+        Self {
+            file,
+            url: Rc::new(ObjectUrl(url)),
+        }
+    }
+
+    /// The underlying [`web_sys::File`].
+    pub fn file(&self) -> &web_sys::File {
+        &self.file
+    }
+
+    /// The name of the file, without a path.
+    pub fn name(&self) -> String {
+        self.file.name()
+    }
+
+    /// The MIME type of the file, which may be empty if unknown.
+    pub fn mime_type(&self) -> String {
+        self.file.type_()
+    }
+
+    /// The size of the file, in bytes.
+    pub fn size(&self) -> f64 {
+        self.file.size()
+    }
+
+    /// Whether the file is an image, according to its MIME type.
+    pub fn is_image(&self) -> bool {
+        self.mime_type().starts_with("image/")
+    }
+
+    /// An object URL referring to the file contents.
+    pub fn url(&self) -> &str {
+        &self.url.0
+    }
+
+    /// Reads the contents of the file.
+    pub async fn bytes(&self) -> Result<Vec<u8>, JsValue> {
+        let buffer =
+            wasm_bindgen_futures::JsFuture::from(self.file.array_buffer())
+                .await?;
+        Ok(js_sys::Uint8Array::new(&buffer).to_vec())
+    }
+}
+
+fn received_files(files: Option<web_sys::FileList>) -> Vec<ReceivedFile> {
+    let Some(files) = files else { return vec![] };
+
+    (0..files.length())
+        .filter_map(|i| files.get(i))
+        .map(ReceivedFile::new)
+        .collect()
+}
+
+type FilesCell = Rc<RefCell<VecDeque<Vec<ReceivedFile>>>>;
+
+#[derive(Copy, Clone, Debug)]
+enum Source {
+    Paste,
+    Drop,
+}
+
+/// A handler for files pasted or dropped onto the parent element, created with
+/// [`on_paste_files`] or [`on_drop_files`].
+pub struct OnFiles<Action> {
+    source: Source,
+    action: Action,
+}
+
+impl<Action: 'static> Builder<Web> for OnFiles<Action> {
+    type State = FilesState<Action>;
+
+    fn build(self, cx: BuildCx) -> Self::State {
+        let files = FilesCell::default();
+        let target = cx.position.parent;
+        let waker = cx.position.waker;
+
+        // The clipboard and drag data are only accessible while the event is
+        // being dispatched, so the files must be read immediately, rather than
+        // when the action runs.
+        let _handles = match self.source {
+            Source::Paste => {
+                vec![listen(target, "paste", waker, &files, |e| {
+                    let e = e.dyn_ref::<web_sys::ClipboardEvent>()?;
+                    let files = received_files(e.clipboard_data()?.files());
+                    (!files.is_empty()).then_some(files)
+                })]
+            }
+            Source::Drop => vec![
+                // Dropping is only permitted if `dragover` is cancelled.
+                listen(target, "dragover", waker, &files, |e| {
+                    e.prevent_default();
+                    None
+                }),
+                listen(target, "drop", waker, &files, |e| {
+                    e.prevent_default();
+                    let e = e.dyn_ref::<web_sys::DragEvent>()?;
+                    Some(received_files(e.data_transfer()?.files()))
+                }),
+            ],
+        };
+
+        FilesState {
+            files,
+            _handles,
+            action: self.action,
+        }
+    }
+
+    fn rebuild(self, _: RebuildCx, state: &mut Self::State) {
+        state.action = self.action;
+    }
+}
+
+impl<Action> Builder<Ssr> for OnFiles<Action> {
+    type State = ();
+
+    fn build(self, _: SsrCx) -> Self::State {}
+
+    fn rebuild(self, cx: Infallible, _: &mut Self::State) {
+        match cx {}
+    }
+}
+
+fn listen(
+    target: &web_sys::EventTarget,
+    name: &'static str,
+    waker: &Arc<AtomicWaker>,
+    files: &FilesCell,
+    read: impl 'static + Fn(&web_sys::Event) -> Option<Vec<ReceivedFile>>,
+) -> gloo_events::EventListener {
+    let waker = waker.clone();
+    let files = files.clone();
+
+    gloo_events::EventListener::new_with_options(
+        target,
+        name,
+        gloo_events::EventListenerOptions::enable_prevent_default(),
+        move |e| {
+            if let Some(received) = read(e) {
+                files.borrow_mut().push_back(received);
+                waker.wake();
+            }
+        },
+    )
+}
+
+/// The state of an [`OnFiles`].
+pub struct FilesState<Action> {
+    files: FilesCell,
+    _handles: Vec<gloo_events::EventListener>,
+    action: Action,
+}
+
+impl<Action, Output> State<Output> for FilesState<Action>
+where
+    Action: 'static + FnMut(&mut Output, Vec<ReceivedFile>),
+    Output: 'static,
+{
+    fn run(&mut self, output: &mut Output) {
+        loop {
+            let Some(files) = self.files.borrow_mut().pop_front() else {
+                break;
+            };
+            (self.action)(output, files);
+        }
+    }
+}
+
+impl<Action> MemSize for FilesState<Action> {
+    /// Received files which haven't been passed to the action yet aren't
+    /// counted.
+    fn heap_size(&self) -> usize {
+        self._handles.capacity() * size_of::<gloo_events::EventListener>()
+    }
+}
+
+/// A handler for files (such as images) pasted from the clipboard into the
+/// parent element.
+///
+/// The action is only called if the clipboard contains at least one file, so
+/// ordinary text pastes are unaffected.
+pub fn on_paste_files<Action, Output>(action: Action) -> OnFiles<Action>
+where
+    Action: 'static + FnMut(&mut Output, Vec<ReceivedFile>),
+    Output: 'static,
+{
+    OnFiles {
+        source: Source::Paste,
+        action,
+    }
+}
+
+/// A handler for files dropped onto the parent element.
+///
+/// This prevents the browser's default behavior of navigating to the dropped
+/// file.
+pub fn on_drop_files<Action, Output>(action: Action) -> OnFiles<Action>
+where
+    Action: 'static + FnMut(&mut Output, Vec<ReceivedFile>),
+    Output: 'static,
+{
+    OnFiles {
+        source: Source::Drop,
+        action,
+    }
+}
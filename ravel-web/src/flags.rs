@@ -0,0 +1,129 @@
+//! Feature flags and experiment gating.
+//!
+//! A set of enabled [`Flags`] is provided to a subtree with [`provide_flags`]
+//! (or for a whole application, with [`Flags::scope`] in
+//! [`crate::run::around`] middleware). Components inside can then switch
+//! between views with [`flag`], or query flags directly with [`enabled`].
+
+use std::{cell::RefCell, collections::HashSet, rc::Rc};
+
+use ravel::{with, Builder, State};
+
+use crate::{any, AnyState, BuildCx, RebuildCx, View, Web};
+
+/// A set of enabled feature flags.
+///
+/// This is cheap to clone.
+#[derive(Clone, Default, Debug, PartialEq, Eq)]
+pub struct Flags(Rc<HashSet<String>>);
+
+impl Flags {
+    /// An empty set, with all flags disabled.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Whether the named flag is enabled.
+    pub fn is_enabled(&self, name: &str) -> bool {
+        self.0.contains(name)
+    }
+
+    /// Enables or disables the named flag.
+    pub fn set(&mut self, name: impl Into<String>, enabled: bool) {
+        let flags = Rc::make_mut(&mut self.0);
+        let name = name.into();
+
+        if enabled {
+            flags.insert(name);
+        } else {
+            flags.remove(&name);
+        }
+    }
+
+    /// Runs `f` with these flags provided to any [`flag`] or [`enabled`]
+    /// calls.
+    pub fn scope<R>(&self, f: impl FnOnce() -> R) -> R {
+        struct Pop;
+
+        impl Drop for Pop {
+            fn drop(&mut self) {
+                STACK.with_borrow_mut(|stack| stack.pop());
+            }
+        }
+
+        STACK.with_borrow_mut(|stack| stack.push(self.clone()));
+        let _pop = Pop;
+        f()
+    }
+}
+
+impl<S: Into<String>> FromIterator<S> for Flags {
+    fn from_iter<T: IntoIterator<Item = S>>(iter: T) -> Self {
+        Self(Rc::new(iter.into_iter().map(Into::into).collect()))
+    }
+}
+
+thread_local! {
+    static STACK: RefCell<Vec<Flags>> = const { RefCell::new(Vec::new()) };
+}
+
+/// Whether the named flag is enabled in the innermost provided [`Flags`].
+///
+/// This is only meaningful while building or rebuilding a component inside
+/// [`provide_flags`] or [`Flags::scope`]. Otherwise, all flags are disabled.
+pub fn enabled(name: &str) -> bool {
+    STACK.with_borrow(|stack| {
+        stack.last().is_some_and(|flags| flags.is_enabled(name))
+    })
+}
+
+/// A [`Builder`] created from [`provide_flags`].
+pub struct ProvideFlags<B> {
+    flags: Flags,
+    body: B,
+}
+
+impl<B: Builder<Web>> Builder<Web> for ProvideFlags<B> {
+    type State = B::State;
+
+    fn build(self, cx: BuildCx) -> Self::State {
+        let body = self.body;
+        self.flags.scope(|| body.build(cx))
+    }
+
+    fn rebuild(self, cx: RebuildCx, state: &mut Self::State) {
+        let body = self.body;
+        self.flags.scope(|| body.rebuild(cx, state))
+    }
+}
+
+/// Provides a set of [`Flags`] to all components in `body`.
+pub fn provide_flags<B: Builder<Web>>(
+    flags: Flags,
+    body: B,
+) -> ProvideFlags<B> {
+    ProvideFlags { flags, body }
+}
+
+/// Shows `enabled_view` if the named flag is [`enabled`], and `fallback_view`
+/// otherwise.
+///
+/// When the flag changes, the previous view is removed and the other is built
+/// from scratch, as with [`any`].
+pub fn flag<A: View, B: View, Output: 'static>(
+    name: &'static str,
+    enabled_view: A,
+    fallback_view: B,
+) -> impl Builder<Web, State = AnyState<Output>>
+where
+    A::State: State<Output>,
+    B::State: State<Output>,
+{
+    with(move |cx| {
+        if enabled(name) {
+            cx.build(any(enabled_view))
+        } else {
+            cx.build(any(fallback_view))
+        }
+    })
+}
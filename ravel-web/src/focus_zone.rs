@@ -0,0 +1,328 @@
+//! Keyboard navigation between the items of a composite widget, such as a
+//! toolbar, a listbox or a grid.
+//!
+//! Following the
+//! [WAI-ARIA practices](https://www.w3.org/WAI/ARIA/apg/practices/keyboard-interface/#kbd_roving_tabindex),
+//! a focus zone is a single stop in the tab order. Within it, the arrow keys
+//! move focus between the elements marked with [`focus_item`], and `Home` and
+//! `End` move it to the first and last of them. When focus leaves the zone
+//! and comes back, it returns to the item which had it last:
+//!
+//! ```ignore
+//! el::div((
+//!     attr::Role("toolbar"),
+//!     focus_zone(Orientation::Horizontal),
+//!     el::button((focus_item(), "Bold")),
+//!     el::button((focus_item(), "Italic")),
+//! ))
+//! ```
+//!
+//! In an [`Orientation::Grid`], focus moves to the nearest item on screen in
+//! the direction of the arrow key, so items can be laid out in any way.
+//!
+//! Focus moves without involving the model, so navigating doesn't cause a
+//! rebuild.
+
+use std::{cell::Cell, convert::Infallible, rc::Rc};
+
+use ravel::{Builder, MemSize, State};
+use web_sys::wasm_bindgen::JsCast as _;
+
+use crate::{
+    direction::Direction,
+    el,
+    ssr::{Ssr, SsrCx},
+    BuildCx, RebuildCx, Web,
+};
+
+/// The attribute marking a focus zone's element.
+const ZONE: &str = "data-focus-zone";
+
+/// The attribute marking the items of a focus zone.
+const ITEM: &str = "data-focus-item";
+
+/// Which arrow keys move focus within a [`focus_zone`].
+#[derive(Copy, Clone, PartialEq, Eq, Hash, Debug)]
+pub enum Orientation {
+    /// Left and right move to the previous and next item, as in a toolbar.
+    /// In right-to-left text, this is reversed.
+    Horizontal,
+    /// Up and down move to the previous and next item, as in a listbox.
+    Vertical,
+    /// All four move to the nearest item in their direction, as laid out on
+    /// screen.
+    Grid,
+}
+
+/// A [`Builder`] created from [`focus_zone`].
+#[derive(Copy, Clone, Debug)]
+pub struct FocusZone {
+    orientation: Orientation,
+    wrap: bool,
+}
+
+/// Makes the parent element a focus zone, in which the arrow keys move focus
+/// between its [`focus_item`]s.
+///
+/// Only one item of the zone is in the tab order at a time: initially the
+/// first, and then the last one focused. Zones may be nested, in which case
+/// each item belongs to the nearest zone around it.
+///
+/// This must be used as (or in) the body of an element, like
+/// [`with_ref`](crate::el::with_ref).
+pub fn focus_zone(orientation: Orientation) -> FocusZone {
+    FocusZone {
+        orientation,
+        wrap: false,
+    }
+}
+
+impl FocusZone {
+    /// Moves focus from the last item to the first and vice versa, rather than
+    /// stopping at the ends. This has no effect on an [`Orientation::Grid`].
+    pub fn wrapping(self) -> Self {
+        Self { wrap: true, ..self }
+    }
+}
+
+impl Builder<Web> for FocusZone {
+    type State = FocusZoneState;
+
+    fn build(self, cx: BuildCx) -> Self::State {
+        let element = cx.position.parent;
+        let _ = element.set_attribute(ZONE, "");
+        schedule_roving(element);
+
+        let config = Rc::new(Cell::new(self));
+        let zone = element.clone();
+        let _keydown = gloo_events::EventListener::new_with_options(
+            element,
+            "keydown",
+            gloo_events::EventListenerOptions::enable_prevent_default(),
+            {
+                let config = config.clone();
+                move |e| {
+                    let e = e.unchecked_ref::<web_sys::KeyboardEvent>();
+                    if e.alt_key() || e.ctrl_key() || e.meta_key() {
+                        return;
+                    }
+                    if navigate(&zone, config.get(), &e.key()) {
+                        e.prevent_default();
+                    }
+                }
+            },
+        );
+
+        let zone = element.clone();
+        let _focusin =
+            gloo_events::EventListener::new(element, "focusin", move |e| {
+                let target = e.target().and_then(|t| t.dyn_into().ok());
+                if let Some(item) = target.and_then(|t| item_of(&zone, &t)) {
+                    make_roving(&zone, &item);
+                }
+            });
+
+        FocusZoneState {
+            config,
+            element: element.clone(),
+            _keydown,
+            _focusin,
+        }
+    }
+
+    fn rebuild(self, _: RebuildCx, state: &mut Self::State) {
+        state.config.set(self);
+        // Items may have been added or removed.
+        schedule_roving(&state.element);
+    }
+}
+
+impl Builder<Ssr> for FocusZone {
+    type State = ();
+
+    fn build(self, _: SsrCx) -> Self::State {}
+
+    fn rebuild(self, cx: Infallible, _: &mut Self::State) {
+        match cx {}
+    }
+}
+
+/// The state of a [`FocusZone`].
+pub struct FocusZoneState {
+    config: Rc<Cell<FocusZone>>,
+    element: web_sys::Element,
+    _keydown: gloo_events::EventListener,
+    _focusin: gloo_events::EventListener,
+}
+
+impl<Output> State<Output> for FocusZoneState {
+    fn run(&mut self, _: &mut Output) {}
+}
+
+impl MemSize for FocusZoneState {
+    fn heap_size(&self) -> usize {
+        0
+    }
+}
+
+/// A [`Builder`] created from [`focus_item`].
+#[derive(Copy, Clone, Debug)]
+pub struct FocusItem;
+
+/// Marks the parent element as an item of the nearest [`focus_zone`] around
+/// it.
+///
+/// This must be used as (or in) the body of an element, like
+/// [`with_ref`](crate::el::with_ref).
+pub fn focus_item() -> FocusItem {
+    FocusItem
+}
+
+impl Builder<Web> for FocusItem {
+    type State = ();
+
+    fn build(self, cx: BuildCx) -> Self::State {
+        let _ = cx.position.parent.set_attribute(ITEM, "");
+        let _ = cx.position.parent.set_attribute("tabindex", "-1");
+    }
+
+    fn rebuild(self, _: RebuildCx, _: &mut Self::State) {}
+}
+
+impl Builder<Ssr> for FocusItem {
+    type State = ();
+
+    fn build(self, _: SsrCx) -> Self::State {}
+
+    fn rebuild(self, cx: Infallible, _: &mut Self::State) {
+        match cx {}
+    }
+}
+
+/// The items of `zone`, in document order.
+fn items(zone: &web_sys::Element) -> Vec<web_sys::HtmlElement> {
+    let Ok(nodes) = zone.query_selector_all(&format!("[{ITEM}]")) else {
+        return Vec::new();
+    };
+
+    (0..nodes.length())
+        .filter_map(|i| nodes.item(i)?.dyn_into::<web_sys::HtmlElement>().ok())
+        .filter(|item| nearest_zone(item).as_ref() == Some(zone))
+        .collect()
+}
+
+fn nearest_zone(item: &web_sys::Element) -> Option<web_sys::Element> {
+    item.parent_element()?.closest(&format!("[{ZONE}]")).ok()?
+}
+
+/// The item of `zone` containing `element`, if any.
+fn item_of(
+    zone: &web_sys::Element,
+    element: &web_sys::Element,
+) -> Option<web_sys::HtmlElement> {
+    let item = element.closest(&format!("[{ITEM}]")).ok()??;
+    (nearest_zone(&item).as_ref() == Some(zone))
+        .then(|| item.dyn_into().ok())?
+}
+
+/// Puts `item` in the tab order, and takes every other item of `zone` out of
+/// it.
+fn make_roving(zone: &web_sys::Element, item: &web_sys::HtmlElement) {
+    for other in items(zone) {
+        other.set_tab_index(if &other == item { 0 } else { -1 });
+    }
+}
+
+/// Once the current pass has finished rebuilding, makes sure that exactly one
+/// item of `zone` is in the tab order, keeping the current one if it is still
+/// there.
+fn schedule_roving(zone: &web_sys::Element) {
+    el::schedule(zone, |zone| {
+        let items = items(zone);
+        let current = items.iter().find(|item| item.tab_index() == 0);
+        if let Some(item) = current.or(items.first()) {
+            make_roving(zone, item);
+        }
+    })
+}
+
+/// Moves focus in response to `key`, returning whether it was handled.
+fn navigate(zone: &web_sys::Element, config: FocusZone, key: &str) -> bool {
+    let items = items(zone);
+    let Some(active) = gloo_utils::document().active_element() else {
+        return false;
+    };
+    let Some(current) = item_of(zone, &active) else {
+        return false;
+    };
+    let Some(index) = items.iter().position(|item| *item == current) else {
+        return false;
+    };
+
+    // In right-to-left text, the next item is to the left.
+    let rtl = || Direction::of(zone).is_rtl();
+    let step = |forward: bool| {
+        let last = items.len() - 1;
+        match (forward, index) {
+            (true, i) if i == last => config.wrap.then_some(0),
+            (true, i) => Some(i + 1),
+            (false, 0) => config.wrap.then_some(last),
+            (false, i) => Some(i - 1),
+        }
+    };
+
+    let target = match (config.orientation, key) {
+        (_, "Home") => Some(0),
+        (_, "End") => Some(items.len() - 1),
+        (Orientation::Horizontal, "ArrowLeft") => step(rtl()),
+        (Orientation::Horizontal, "ArrowRight") => step(!rtl()),
+        (Orientation::Vertical, "ArrowUp") => step(false),
+        (Orientation::Vertical, "ArrowDown") => step(true),
+        (Orientation::Grid, "ArrowLeft") => nearest(&items, index, -1., 0.),
+        (Orientation::Grid, "ArrowRight") => nearest(&items, index, 1., 0.),
+        (Orientation::Grid, "ArrowUp") => nearest(&items, index, 0., -1.),
+        (Orientation::Grid, "ArrowDown") => nearest(&items, index, 0., 1.),
+        _ => return false,
+    };
+
+    if let Some(target) = target {
+        let item = &items[target];
+        make_roving(zone, item);
+        let _ = item.focus();
+    }
+    true
+}
+
+/// The index of the item nearest to `items[index]` in the direction `(dx,
+/// dy)`, comparing the centers of their boxes.
+///
+/// Distance across the direction counts double, so that items in the same row
+/// or column are preferred.
+fn nearest(
+    items: &[web_sys::HtmlElement],
+    index: usize,
+    dx: f64,
+    dy: f64,
+) -> Option<usize> {
+    let center = |item: &web_sys::HtmlElement| {
+        let rect = item.get_bounding_client_rect();
+        (
+            rect.left() + rect.width() / 2.,
+            rect.top() + rect.height() / 2.,
+        )
+    };
+
+    let (x, y) = center(&items[index]);
+    items
+        .iter()
+        .enumerate()
+        .filter(|&(i, _)| i != index)
+        .filter_map(|(i, item)| {
+            let (ix, iy) = center(item);
+            let along = (ix - x) * dx + (iy - y) * dy;
+            let across = (ix - x) * dy + (iy - y) * dx;
+            (along > 0.5).then_some((i, along + 2. * across.abs()))
+        })
+        .min_by(|(_, a), (_, b)| a.total_cmp(b))
+        .map(|(i, _)| i)
+}
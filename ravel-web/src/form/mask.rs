@@ -0,0 +1,204 @@
+//! Input masks, which format text as it is typed.
+//!
+//! A [`Mask`] is applied with [`masked`], which reformats the input on every
+//! keystroke while keeping the caret next to the character the user just
+//! typed.
+
+use web_sys::wasm_bindgen::JsCast as _;
+
+use ravel::{Builder, State};
+
+use crate::{
+    event::{on, InputEvent},
+    Web,
+};
+
+use super::controlled_value;
+
+/// Trait for input masks.
+///
+/// A mask distinguishes "significant" characters, which are typed by the user,
+/// from any literal characters inserted by the formatting. The caret is
+/// positioned relative to significant characters, so it stays in place even as
+/// literals appear or disappear around it.
+pub trait Mask {
+    /// Whether a character of raw input is kept. All other characters are
+    /// discarded before formatting.
+    fn is_significant(&self, c: char) -> bool;
+
+    /// Formats a string consisting only of significant characters.
+    ///
+    /// The output must contain the significant characters in their original
+    /// order, though it may drop a suffix of them (for example, when a maximum
+    /// length is reached).
+    fn format(&self, significant: &str) -> String;
+
+    /// Applies the mask to raw input, given a caret position in UTF-16 code
+    /// units (as used by the DOM selection APIs). Returns the formatted text and
+    /// the corresponding caret position.
+    fn apply(&self, raw: &str, caret: u32) -> (String, u32) {
+        let mut significant = String::with_capacity(raw.len());
+        let mut before_caret = 0;
+        let mut offset = 0;
+
+        for c in raw.chars() {
+            if self.is_significant(c) {
+                significant.push(c);
+                if offset < caret {
+                    before_caret += 1;
+                }
+            }
+            offset += c.len_utf16() as u32;
+        }
+
+        let formatted = self.format(&significant);
+
+        let mut caret = 0;
+        let mut remaining = before_caret;
+        for c in formatted.chars() {
+            if remaining == 0 {
+                break;
+            }
+            if self.is_significant(c) {
+                remaining -= 1;
+            }
+            caret += c.len_utf16() as u32;
+        }
+
+        (formatted, caret)
+    }
+}
+
+/// A mask which only accepts digits, optionally grouping them in threes with a
+/// separator. Created with [`numeric`] or [`grouped`].
+#[derive(Copy, Clone, Debug)]
+pub struct Numeric {
+    separator: Option<char>,
+}
+
+impl Mask for Numeric {
+    fn is_significant(&self, c: char) -> bool {
+        c.is_ascii_digit()
+    }
+
+    fn format(&self, significant: &str) -> String {
+        let Some(separator) = self.separator else {
+            return significant.to_string();
+        };
+
+        let mut s = String::with_capacity(significant.len() * 4 / 3);
+        for (i, c) in significant.chars().enumerate() {
+            if i > 0 && (significant.len() - i).is_multiple_of(3) {
+                s.push(separator);
+            }
+            s.push(c);
+        }
+
+        s
+    }
+}
+
+/// A mask which only accepts digits.
+pub fn numeric() -> Numeric {
+    Numeric { separator: None }
+}
+
+/// A mask which only accepts digits, grouping them in threes (for example,
+/// `1,234,567`).
+pub fn grouped(separator: char) -> Numeric {
+    Numeric {
+        separator: Some(separator),
+    }
+}
+
+/// A mask which fills digits into a fixed pattern, created with [`pattern`].
+#[derive(Copy, Clone, Debug)]
+pub struct Pattern {
+    pattern: &'static str,
+}
+
+impl Mask for Pattern {
+    fn is_significant(&self, c: char) -> bool {
+        c.is_ascii_digit()
+    }
+
+    fn format(&self, significant: &str) -> String {
+        let mut digits = significant.chars().peekable();
+        let mut s = String::with_capacity(self.pattern.len());
+
+        for p in self.pattern.chars() {
+            if digits.peek().is_none() {
+                break;
+            }
+
+            s.push(match p {
+                '#' => digits.next().unwrap(),
+                literal => literal,
+            });
+        }
+
+        s
+    }
+}
+
+/// A mask which fills digits into a pattern, where each `#` is a digit and any
+/// other character is a literal. Literals are only shown up to the last digit
+/// entered, and digits beyond the end of the pattern are dropped.
+///
+/// For example, `pattern("####-##-##")` formats a date.
+pub fn pattern(pattern: &'static str) -> Pattern {
+    Pattern { pattern }
+}
+
+/// A mask for North American phone numbers, formatted as `(555) 123-4567`.
+pub fn phone() -> Pattern {
+    pattern("(###) ###-####")
+}
+
+/// A controlled `<input>` which formats its text with a [`Mask`] on every
+/// keystroke.
+///
+/// This must be used in the body of an [`crate::el::input`]. The `value` is the
+/// current (formatted) model value, as with [`controlled_value`]. After each
+/// edit, the input is reformatted in place, preserving the caret position, and
+/// `action` is called with the new formatted value.
+pub fn masked<M, V, Action, Output>(
+    mask: M,
+    value: V,
+    mut action: Action,
+) -> impl Builder<Web, State = impl State<Output>>
+where
+    M: 'static + Mask,
+    V: AsRef<str>,
+    Action: 'static + FnMut(&mut Output, String),
+    Output: 'static,
+{
+    (
+        controlled_value(value),
+        on(
+            InputEvent,
+            move |output: &mut Output, e: web_sys::InputEvent| {
+                let Some(input) = e.target().and_then(|t| {
+                    t.dyn_into::<web_sys::HtmlInputElement>().ok()
+                }) else {
+                    return;
+                };
+
+                let raw = input.value();
+                let caret = input
+                    .selection_start()
+                    .ok()
+                    .flatten()
+                    .unwrap_or(raw.encode_utf16().count() as u32);
+
+                let (formatted, caret) = mask.apply(&raw, caret);
+                if formatted != raw {
+                    input.set_value(&formatted);
+                    let _ = input.set_selection_range(caret, caret);
+                }
+
+                action(output, formatted)
+            },
+        ),
+    )
+}
@@ -0,0 +1,392 @@
+//! Form controls.
+
+use std::{convert::Infallible, marker::PhantomData, rc::Rc};
+
+use ravel::{with, Builder, MemSize, State, Token};
+use web_sys::wasm_bindgen::JsCast as _;
+
+use crate::{
+    attr::{types::BooleanAttrValue, CloneString},
+    event::{
+        listen, target_checked, target_value, Bounded, EventCell, InputEvent,
+    },
+    ssr::{Ssr, SsrCx},
+    BuildCx, Cx, RebuildCx, Web,
+};
+
+pub mod mask;
+
+/// The live DOM state of a form control, as provided by [`input_state`].
+#[derive(Copy, Clone)]
+pub struct InputState<'a> {
+    element: &'a web_sys::Element,
+}
+
+impl<'a> InputState<'a> {
+    /// The underlying element.
+    pub fn element(&self) -> &'a web_sys::Element {
+        self.element
+    }
+
+    /// The current `value`, if the element is an `<input>`, `<select>` or
+    /// `<textarea>`.
+    pub fn value(&self) -> Option<String> {
+        value_of(self.element)
+    }
+
+    /// The current `checked` state, if the element is an `<input>`.
+    pub fn checked(&self) -> Option<bool> {
+        checked_of(self.element)
+    }
+}
+
+/// A [`Builder`] created from [`input_state`].
+pub struct WithInputState<F, S> {
+    f: F,
+    phantom: PhantomData<S>,
+}
+
+impl<F, S> Builder<Web> for WithInputState<F, S>
+where
+    F: FnOnce(Cx<S, Web>, InputState) -> Token<S>,
+{
+    type State = S;
+
+    fn build(self, cx: BuildCx) -> Self::State {
+        let state = InputState {
+            element: cx.position.parent,
+        };
+        with(|cx| (self.f)(cx, state)).build(cx)
+    }
+
+    fn rebuild(self, cx: RebuildCx, state: &mut Self::State) {
+        let input = InputState { element: cx.parent };
+        with(|cx| (self.f)(cx, input)).rebuild(cx, state)
+    }
+}
+
+/// Reads back the current DOM state of the parent form control during render.
+///
+/// This must be used in the body of an element such as [`crate::el::input`].
+/// The callback receives the live state, which reflects any changes made by
+/// the user, and like [`ravel::with`] must return `cx.build(...)`.
+///
+/// This makes it possible to render based on an uncontrolled input (for
+/// example, marking it invalid) without copying its value into the model from
+/// an event handler.
+///
+/// When the element is first built, the state reflects only the attributes
+/// built before this component.
+pub fn input_state<F, S>(f: F) -> WithInputState<F, S>
+where
+    F: FnOnce(Cx<S, Web>, InputState) -> Token<S>,
+{
+    WithInputState {
+        f,
+        phantom: PhantomData,
+    }
+}
+
+/// A [`Builder`] created from [`controlled_value`].
+pub struct ControlledValue<V: AsRef<str>> {
+    value: V,
+}
+
+impl<V: AsRef<str>> Builder<Web> for ControlledValue<V> {
+    type State = ControlledValueState;
+
+    fn build(self, cx: BuildCx) -> Self::State {
+        set_value_of(cx.position.parent, self.value.as_ref());
+        ControlledValueState
+    }
+
+    fn rebuild(self, cx: RebuildCx, _: &mut Self::State) {
+        let value = self.value.as_ref();
+        if value_of(cx.parent).as_deref() != Some(value) {
+            set_value_of(cx.parent, value);
+        }
+    }
+}
+
+/// The state of a [`ControlledValue`].
+pub struct ControlledValueState;
+
+impl<Output> State<Output> for ControlledValueState {
+    fn run(&mut self, _: &mut Output) {}
+}
+
+impl MemSize for ControlledValueState {
+    fn heap_size(&self) -> usize {
+        0
+    }
+}
+
+/// Binds the `value` property of the parent `<input>`, `<select>` or
+/// `<textarea>` to a value derived from the model (a "controlled" input).
+///
+/// Unlike the [`crate::attr::Value`] attribute, which only sets the initial
+/// value, this compares against the live value on every rebuild and writes it
+/// back whenever they differ. Since a rebuild follows every event, any edit
+/// which is not reflected in the model (for example, because an
+/// [`crate::event::InputEvent`] handler rejected or reformatted it) is
+/// immediately reverted to the model value.
+pub fn controlled_value<V: AsRef<str>>(value: V) -> ControlledValue<V> {
+    ControlledValue { value }
+}
+
+/// A [`Builder`] created from [`controlled_checked`].
+pub struct ControlledChecked {
+    checked: bool,
+}
+
+impl Builder<Web> for ControlledChecked {
+    type State = ControlledValueState;
+
+    fn build(self, cx: BuildCx) -> Self::State {
+        set_checked_of(cx.position.parent, self.checked);
+        ControlledValueState
+    }
+
+    fn rebuild(self, cx: RebuildCx, _: &mut Self::State) {
+        if checked_of(cx.parent) != Some(self.checked) {
+            set_checked_of(cx.parent, self.checked);
+        }
+    }
+}
+
+/// Binds the `checked` property of the parent `<input>` to a value derived
+/// from the model.
+///
+/// Unlike the [`crate::attr::Checked`] attribute, which only sets the default
+/// state, this compares against the live state on every rebuild and writes it
+/// back whenever they differ, like [`controlled_value`].
+pub fn controlled_checked(checked: bool) -> ControlledChecked {
+    ControlledChecked { checked }
+}
+
+/// A [`Builder`] created from [`input_value`].
+pub struct InputValue<V: AsRef<str>, Action> {
+    value: V,
+    action: Action,
+}
+
+impl<V: AsRef<str>, Action: 'static> Builder<Web> for InputValue<V, Action> {
+    type State = InputValueState<Action>;
+
+    fn build(self, cx: BuildCx) -> Self::State {
+        set_value_of(cx.position.parent, self.value.as_ref());
+
+        InputValueState {
+            input: InputListener::new(cx),
+            action: self.action,
+        }
+    }
+
+    fn rebuild(self, cx: RebuildCx, state: &mut Self::State) {
+        controlled_value(self.value).rebuild(cx, &mut ControlledValueState);
+        state.action = self.action;
+    }
+}
+
+/// Renders the value as the `value` attribute, so it only shows on an
+/// `<input>`.
+impl<V: AsRef<str>, Action> Builder<Ssr> for InputValue<V, Action> {
+    type State = ();
+
+    fn build(self, cx: SsrCx) -> Self::State {
+        cx.attr("value", CloneString(self.value))
+    }
+
+    fn rebuild(self, cx: Infallible, _: &mut Self::State) {
+        match cx {}
+    }
+}
+
+/// The state of an [`InputValue`].
+pub struct InputValueState<Action> {
+    input: InputListener,
+    action: Action,
+}
+
+impl<Action, Output> State<Output> for InputValueState<Action>
+where
+    Action: 'static + FnMut(&mut Output, String),
+    Output: 'static,
+{
+    fn run(&mut self, output: &mut Output) {
+        while let Some(e) = self.input.event.take() {
+            if let Some(value) = target_value(&e) {
+                (self.action)(output, value)
+            }
+        }
+    }
+}
+
+impl<Action> MemSize for InputValueState<Action> {
+    fn heap_size(&self) -> usize {
+        0
+    }
+}
+
+/// A controlled text input, which must be used in the body of an
+/// [`crate::el::input`] or [`crate::el::textarea`].
+///
+/// The `value` is written to the element as with [`controlled_value`], and
+/// `action` is called with the new value on every edit. The model and the DOM
+/// never diverge: clearing the input, for example, is just a matter of
+/// clearing the model value.
+pub fn input_value<V, Action, Output>(
+    value: V,
+    action: Action,
+) -> InputValue<V, Action>
+where
+    V: AsRef<str>,
+    Action: 'static + FnMut(&mut Output, String),
+    Output: 'static,
+{
+    InputValue { value, action }
+}
+
+/// A [`Builder`] created from [`checkbox_checked`].
+pub struct CheckboxChecked<Action> {
+    checked: bool,
+    action: Action,
+}
+
+impl<Action: 'static> Builder<Web> for CheckboxChecked<Action> {
+    type State = CheckboxCheckedState<Action>;
+
+    fn build(self, cx: BuildCx) -> Self::State {
+        set_checked_of(cx.position.parent, self.checked);
+
+        CheckboxCheckedState {
+            input: InputListener::new(cx),
+            action: self.action,
+        }
+    }
+
+    fn rebuild(self, cx: RebuildCx, state: &mut Self::State) {
+        controlled_checked(self.checked).rebuild(cx, &mut ControlledValueState);
+        state.action = self.action;
+    }
+}
+
+impl<Action> Builder<Ssr> for CheckboxChecked<Action> {
+    type State = ();
+
+    fn build(self, cx: SsrCx) -> Self::State {
+        cx.attr("checked", BooleanAttrValue(self.checked))
+    }
+
+    fn rebuild(self, cx: Infallible, _: &mut Self::State) {
+        match cx {}
+    }
+}
+
+/// The state of a [`CheckboxChecked`].
+pub struct CheckboxCheckedState<Action> {
+    input: InputListener,
+    action: Action,
+}
+
+impl<Action, Output> State<Output> for CheckboxCheckedState<Action>
+where
+    Action: 'static + FnMut(&mut Output, bool),
+    Output: 'static,
+{
+    fn run(&mut self, output: &mut Output) {
+        while let Some(e) = self.input.event.take() {
+            if let Some(checked) = target_checked(&e) {
+                (self.action)(output, checked)
+            }
+        }
+    }
+}
+
+impl<Action> MemSize for CheckboxCheckedState<Action> {
+    fn heap_size(&self) -> usize {
+        0
+    }
+}
+
+/// A controlled checkbox, which must be used in the body of an
+/// [`crate::el::input`] with `type="checkbox"`.
+///
+/// The `checked` state is written to the element as with
+/// [`controlled_checked`], and `action` is called with the new state whenever
+/// the user toggles it.
+pub fn checkbox_checked<Action, Output>(
+    checked: bool,
+    action: Action,
+) -> CheckboxChecked<Action>
+where
+    Action: 'static + FnMut(&mut Output, bool),
+    Output: 'static,
+{
+    CheckboxChecked { checked, action }
+}
+
+/// Listens for [`InputEvent`]s on the parent element.
+struct InputListener {
+    event: EventCell,
+    _handle: gloo_events::EventListener,
+}
+
+impl InputListener {
+    fn new(cx: BuildCx) -> Self {
+        let event = EventCell::new();
+
+        Self {
+            _handle: listen::<InputEvent>(
+                cx.position.parent,
+                cx.position.waker,
+                &event,
+                Bounded::default(),
+                Rc::default(),
+            ),
+            event,
+        }
+    }
+}
+
+/// Reads the current `value` of an `<input>`, `<select>` or `<textarea>`.
+pub(crate) fn value_of(target: &web_sys::EventTarget) -> Option<String> {
+    if let Some(input) = target.dyn_ref::<web_sys::HtmlInputElement>() {
+        Some(input.value())
+    } else if let Some(select) = target.dyn_ref::<web_sys::HtmlSelectElement>()
+    {
+        Some(select.value())
+    } else {
+        target
+            .dyn_ref::<web_sys::HtmlTextAreaElement>()
+            .map(|textarea| textarea.value())
+    }
+}
+
+/// Sets the `value` property of an `<input>`, `<select>` or `<textarea>`.
+pub(crate) fn set_value_of(target: &web_sys::EventTarget, value: &str) {
+    if let Some(input) = target.dyn_ref::<web_sys::HtmlInputElement>() {
+        input.set_value(value)
+    } else if let Some(select) = target.dyn_ref::<web_sys::HtmlSelectElement>()
+    {
+        select.set_value(value)
+    } else if let Some(textarea) =
+        target.dyn_ref::<web_sys::HtmlTextAreaElement>()
+    {
+        textarea.set_value(value)
+    }
+}
+
+/// Reads the current `checked` state of an `<input>`.
+pub(crate) fn checked_of(target: &web_sys::EventTarget) -> Option<bool> {
+    target
+        .dyn_ref::<web_sys::HtmlInputElement>()
+        .map(|input| input.checked())
+}
+
+/// Sets the `checked` state of an `<input>`.
+fn set_checked_of(target: &web_sys::EventTarget, checked: bool) {
+    if let Some(input) = target.dyn_ref::<web_sys::HtmlInputElement>() {
+        input.set_checked(checked)
+    }
+}
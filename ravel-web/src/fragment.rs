@@ -0,0 +1,109 @@
+use std::convert::Infallible;
+
+use ravel::{MemSize, State};
+
+use crate::{
+    dom::{clear, detach, marker},
+    ssr::{Ssr, SsrCx},
+    BuildCx, Builder, RebuildCx, View, ViewMarker, Web,
+};
+
+/// A [`Builder`] created from [`fragment`].
+pub struct Fragment<B> {
+    body: B,
+}
+
+/// Groups the nodes built by `body` (typically a tuple of several elements)
+/// between a pair of markers, so that they form a single range of the DOM.
+///
+/// Components returning several root nodes compose without this, since the
+/// combinators which replace their contents, like [`Option`] and
+/// [`any`](crate::any), already delimit them. This is for code which needs to
+/// address all of a component's nodes at once, through the
+/// [`FragmentState`].
+pub fn fragment<B: View>(body: B) -> Fragment<B> {
+    Fragment { body }
+}
+
+impl<B: View> Builder<Web> for Fragment<B> {
+    type State = FragmentState<B::State>;
+
+    fn build(self, cx: BuildCx) -> Self::State {
+        let start = marker("[");
+        let end = marker("]");
+
+        cx.position.insert(&start);
+        let body = self.body.build(cx);
+        cx.position.insert(&end);
+
+        FragmentState { body, start, end }
+    }
+
+    fn rebuild(self, cx: RebuildCx, state: &mut Self::State) {
+        self.body.rebuild(cx, &mut state.body)
+    }
+}
+
+impl<B: Builder<Ssr>> Builder<Ssr> for Fragment<B> {
+    type State = B::State;
+
+    fn build(self, cx: SsrCx) -> Self::State {
+        cx.comment("[");
+        let state = self.body.build(cx);
+        cx.comment("]");
+
+        state
+    }
+
+    fn rebuild(self, cx: Infallible, _: &mut Self::State) {
+        match cx {}
+    }
+}
+
+/// The state of a [`Fragment`].
+pub struct FragmentState<S> {
+    body: S,
+    start: web_sys::CharacterData,
+    end: web_sys::CharacterData,
+}
+
+impl<S> FragmentState<S> {
+    /// The marker before the fragment's nodes.
+    pub fn start(&self) -> &web_sys::Node {
+        &self.start
+    }
+
+    /// The marker after the fragment's nodes.
+    pub fn end(&self) -> &web_sys::Node {
+        &self.end
+    }
+
+    /// Removes the fragment's nodes (but not its markers) from the document,
+    /// returning them in a [`web_sys::DocumentFragment`].
+    ///
+    /// The fragment must be reinserted between its markers before it is next
+    /// rebuilt.
+    pub fn detach(&self) -> web_sys::DocumentFragment {
+        detach(&self.start, &self.end)
+    }
+
+    /// Removes the fragment's nodes (but not its markers), for example before
+    /// dropping this state when its parent doesn't remove them itself.
+    pub fn clear(&self, parent: &web_sys::Node) {
+        clear(parent, &self.start, &self.end)
+    }
+}
+
+impl<S: State<Output>, Output> State<Output> for FragmentState<S> {
+    fn run(&mut self, output: &mut Output) {
+        self.body.run(output)
+    }
+}
+
+impl<S: MemSize> MemSize for FragmentState<S> {
+    fn heap_size(&self) -> usize {
+        self.body.heap_size()
+    }
+}
+
+impl<S> ViewMarker for FragmentState<S> {}
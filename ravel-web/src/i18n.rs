@@ -0,0 +1,483 @@
+//! Localization of text.
+//!
+//! The [`Locale`] of the application is provided to components with
+//! [`provide_locale`], and read with [`use_locale`]. Text which depends on a
+//! number is written with [`plural!`](crate::plural!), which picks the form
+//! matching the
+//! [plural rules](https://cldr.unicode.org/index/cldr-spec/plural-rules) of
+//! the locale's language:
+//!
+//! ```ignore
+//! let count = model.count();
+//! plural!(count, one: "{count} item left", other: "{count} items left")
+//! ```
+//!
+//! Languages whose rules aren't known use the English ones.
+
+use std::{borrow::Cow, fmt::Arguments};
+
+use ravel::{provide_context, use_context, Cx, CxRep, MemSize, ProvideContext};
+
+use crate::direction::Direction;
+
+/// A language, identified by a [BCP 47](https://www.rfc-editor.org/info/bcp47)
+/// tag such as `"en-US"`.
+#[derive(Clone, PartialEq, Eq, Hash, Debug)]
+pub struct Locale {
+    tag: Cow<'static, str>,
+}
+
+impl Locale {
+    /// The locale with the tag `tag`.
+    pub fn new(tag: impl Into<Cow<'static, str>>) -> Self {
+        Self { tag: tag.into() }
+    }
+
+    /// The preferred language of the browser's user, or English if it isn't
+    /// known.
+    pub fn browser() -> Self {
+        match gloo_utils::window().navigator().language() {
+            Some(tag) => Self::new(tag),
+            None => Self::default(),
+        }
+    }
+
+    /// The BCP 47 tag of the locale.
+    pub fn tag(&self) -> &str {
+        &self.tag
+    }
+
+    /// The primary language subtag, in lower case, such as `"en"`.
+    fn language(&self) -> String {
+        let language = self.tag.split(['-', '_']).next().unwrap_or_default();
+        language.to_ascii_lowercase()
+    }
+
+    /// The usual direction of text in the locale's language.
+    pub fn direction(&self) -> Direction {
+        Direction::for_lang(&self.tag)
+    }
+
+    /// The plural category of `count` in the locale's language.
+    pub fn plural(&self, count: u64) -> PluralCategory {
+        plural_rule(&self.language())(count)
+    }
+}
+
+impl Default for Locale {
+    /// English.
+    fn default() -> Self {
+        Self::new("en")
+    }
+}
+
+impl MemSize for Locale {
+    fn heap_size(&self) -> usize {
+        self.tag.heap_size()
+    }
+}
+
+/// Makes `locale` available to every component in `body` with
+/// [`use_locale`].
+pub fn provide_locale<B>(locale: Locale, body: B) -> ProvideContext<Locale, B> {
+    provide_context(locale, body)
+}
+
+/// The locale given to the innermost enclosing [`provide_locale`], or English
+/// if there is none.
+pub fn use_locale<S, R: CxRep>(cx: &Cx<S, R>) -> Locale {
+    use_context::<Locale, _, _>(cx)
+        .map_or_else(Locale::default, |l| (*l).clone())
+}
+
+/// A class of numbers which share a grammatical form in some language, as
+/// defined by the CLDR plural rules.
+#[derive(Copy, Clone, PartialEq, Eq, Hash, Debug)]
+pub enum PluralCategory {
+    /// For example, 0 in Arabic.
+    Zero,
+    /// For example, 1 in English.
+    One,
+    /// For example, 2 in Arabic.
+    Two,
+    /// For example, 2 to 4 in Czech.
+    Few,
+    /// For example, 5 in Russian.
+    Many,
+    /// Every number not in another category, which is the only category in
+    /// some languages, like Japanese.
+    Other,
+}
+
+impl PluralCategory {
+    /// The name of the category in the CLDR, such as `"one"`.
+    pub fn keyword(self) -> &'static str {
+        match self {
+            PluralCategory::Zero => "zero",
+            PluralCategory::One => "one",
+            PluralCategory::Two => "two",
+            PluralCategory::Few => "few",
+            PluralCategory::Many => "many",
+            PluralCategory::Other => "other",
+        }
+    }
+}
+
+/// An integer which can be passed to [`plural!`](crate::plural!).
+pub trait PluralCount: Copy {
+    /// The absolute value of the integer.
+    fn plural_count(self) -> u64;
+}
+
+macro_rules! plural_count {
+    ($($t:ty),*) => {
+        $(impl PluralCount for $t {
+            fn plural_count(self) -> u64 {
+                self.unsigned_abs() as u64
+            }
+        })*
+    };
+}
+
+plural_count!(i8, i16, i32, i64, isize);
+
+macro_rules! plural_count_unsigned {
+    ($($t:ty),*) => {
+        $(impl PluralCount for $t {
+            fn plural_count(self) -> u64 {
+                self as u64
+            }
+        })*
+    };
+}
+
+plural_count_unsigned!(u8, u16, u32, u64, usize);
+
+/// The cardinal plural rule for integers in `language`.
+fn plural_rule(language: &str) -> fn(u64) -> PluralCategory {
+    use PluralCategory::*;
+
+    match language {
+        "id" | "ja" | "km" | "ko" | "lo" | "ms" | "my" | "th" | "vi" | "zh" => {
+            |_| Other
+        }
+        "am" | "bn" | "fa" | "gu" | "hi" | "kn" | "zu" => |n| match n {
+            0 | 1 => One,
+            _ => Other,
+        },
+        "fr" | "pt" => |n| match n {
+            0 | 1 => One,
+            _ if n % 1_000_000 == 0 => Many,
+            _ => Other,
+        },
+        "ca" | "es" | "it" => |n| match n {
+            1 => One,
+            _ if n != 0 && n % 1_000_000 == 0 => Many,
+            _ => Other,
+        },
+        "be" | "ru" | "uk" => |n| match (n % 10, n % 100) {
+            (1, r) if r != 11 => One,
+            (2..=4, r) if !(12..=14).contains(&r) => Few,
+            _ => Many,
+        },
+        "pl" => |n| match (n, n % 10, n % 100) {
+            (1, _, _) => One,
+            (_, 2..=4, r) if !(12..=14).contains(&r) => Few,
+            _ => Many,
+        },
+        "bs" | "hr" | "sr" => |n| match (n % 10, n % 100) {
+            (1, r) if r != 11 => One,
+            (2..=4, r) if !(12..=14).contains(&r) => Few,
+            _ => Other,
+        },
+        "cs" | "sk" => |n| match n {
+            1 => One,
+            2..=4 => Few,
+            _ => Other,
+        },
+        "lt" => |n| match (n % 10, n % 100) {
+            (_, 11..=19) => Other,
+            (1, _) => One,
+            (2..=9, _) => Few,
+            _ => Other,
+        },
+        "lv" => |n| match (n % 10, n % 100) {
+            (0, _) | (_, 11..=19) => Zero,
+            (1, _) => One,
+            _ => Other,
+        },
+        "ro" => |n| match (n, n % 100) {
+            (1, _) => One,
+            (0, _) | (_, 1..=19) => Few,
+            _ => Other,
+        },
+        "sl" => |n| match n % 100 {
+            1 => One,
+            2 => Two,
+            3 | 4 => Few,
+            _ => Other,
+        },
+        "he" | "iw" => |n| match n {
+            1 => One,
+            2 => Two,
+            _ => Other,
+        },
+        "ar" => |n| match (n, n % 100) {
+            (0, _) => Zero,
+            (1, _) => One,
+            (2, _) => Two,
+            (_, 3..=10) => Few,
+            (_, 11..=99) => Many,
+            _ => Other,
+        },
+        "ga" => |n| match n {
+            1 => One,
+            2 => Two,
+            3..=6 => Few,
+            7..=10 => Many,
+            _ => Other,
+        },
+        "cy" => |n| match n {
+            0 => Zero,
+            1 => One,
+            2 => Two,
+            3 => Few,
+            6 => Many,
+            _ => Other,
+        },
+        _ => |n| match n {
+            1 => One,
+            _ => Other,
+        },
+    }
+}
+
+/// Picks the form for `category` out of `forms`, falling back to the `other`
+/// form (or else the last one) if it is missing.
+#[doc(hidden)]
+pub fn choose<'a, const N: usize>(
+    category: PluralCategory,
+    forms: [(PluralCategory, Arguments<'a>); N],
+) -> Arguments<'a> {
+    let form = forms.iter().find(|(c, _)| *c == category);
+    let other = || forms.iter().find(|(c, _)| *c == PluralCategory::Other);
+    match form.or_else(other).or(forms.last()) {
+        Some((_, form)) => *form,
+        None => format_args!(""),
+    }
+}
+
+#[doc(hidden)]
+pub mod reexport {
+    pub use ravel::with;
+}
+
+/// Displays text which depends on a count, in the form matching its plural
+/// category in the current [`Locale`].
+///
+/// Each form is a format string, which can refer to the count by name:
+///
+/// ```ignore
+/// plural!(count, one: "{count} item left", other: "{count} items left")
+/// plural!(n = model.count(), one: "{n} item left", other: "{n} items left")
+/// ```
+///
+/// The forms are named after the CLDR categories (`zero`, `one`, `two`, `few`,
+/// `many` and `other`). Only those used by the locale's language are needed,
+/// and any missing form falls back to `other`, which should always be given.
+/// Any other name is a compile error.
+#[macro_export]
+macro_rules! plural {
+    ($count:ident, $($category:ident: $fmt:literal),+ $(,)?) => {
+        $crate::plural!($count = $count, $($category: $fmt),+)
+    };
+    ($name:ident = $count:expr, $($category:ident: $fmt:literal),+ $(,)?) => {
+        $crate::i18n::reexport::with(move |cx| {
+            let $name = $count;
+            let category = $crate::i18n::use_locale(&cx)
+                .plural($crate::i18n::PluralCount::plural_count($name));
+            cx.build($crate::i18n::choose(
+                category,
+                [$((
+                    $crate::__plural_category!($category),
+                    ::std::format_args!($fmt),
+                )),+],
+            ))
+        })
+    };
+}
+
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __plural_category {
+    (zero) => {
+        $crate::i18n::PluralCategory::Zero
+    };
+    (one) => {
+        $crate::i18n::PluralCategory::One
+    };
+    (two) => {
+        $crate::i18n::PluralCategory::Two
+    };
+    (few) => {
+        $crate::i18n::PluralCategory::Few
+    };
+    (many) => {
+        $crate::i18n::PluralCategory::Many
+    };
+    (other) => {
+        $crate::i18n::PluralCategory::Other
+    };
+    ($category:ident) => {
+        ::std::compile_error!(::std::concat!(
+            "unknown plural category `",
+            ::std::stringify!($category),
+            "`, expected `zero`, `one`, `two`, `few`, `many` or `other`",
+        ))
+    };
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{plural_rule, PluralCategory::*};
+
+    /// The integer samples from the CLDR plural rules, by language and
+    /// category.
+    #[test]
+    fn cldr_samples() {
+        let samples: &[(&str, &[(_, &[u64])])] = &[
+            (
+                "en",
+                &[(One, &[1]), (Other, &[0, 2, 16, 100, 1000, 1000000])],
+            ),
+            ("ja", &[(Other, &[0, 1, 2, 15, 100, 1000])]),
+            ("hi", &[(One, &[0, 1]), (Other, &[2, 17, 100, 1000])]),
+            (
+                "fr",
+                &[
+                    (One, &[0, 1]),
+                    (Many, &[1000000]),
+                    (Other, &[2, 17, 100, 1000, 100000]),
+                ],
+            ),
+            (
+                "es",
+                &[
+                    (One, &[1]),
+                    (Many, &[1000000]),
+                    (Other, &[0, 2, 16, 100, 1000, 100000]),
+                ],
+            ),
+            (
+                "ru",
+                &[
+                    (One, &[1, 21, 31, 81, 101, 1001]),
+                    (Few, &[2, 3, 4, 22, 24, 32, 62, 102, 1002]),
+                    (Many, &[0, 5, 11, 12, 14, 19, 100, 1000, 1000000]),
+                ],
+            ),
+            (
+                "pl",
+                &[
+                    (One, &[1]),
+                    (Few, &[2, 3, 4, 22, 24, 32, 62, 102, 1002]),
+                    (Many, &[0, 5, 12, 19, 21, 100, 1000, 10000]),
+                ],
+            ),
+            (
+                "hr",
+                &[
+                    (One, &[1, 21, 31, 101, 1001]),
+                    (Few, &[2, 4, 22, 24, 102, 1002]),
+                    (Other, &[0, 5, 11, 19, 100, 1000]),
+                ],
+            ),
+            (
+                "cs",
+                &[
+                    (One, &[1]),
+                    (Few, &[2, 3, 4]),
+                    (Other, &[0, 5, 19, 100, 1000]),
+                ],
+            ),
+            (
+                "lt",
+                &[
+                    (One, &[1, 21, 31, 81, 101, 1001]),
+                    (Few, &[2, 9, 22, 29, 102, 1002]),
+                    (Other, &[0, 10, 11, 19, 20, 30, 100, 1000]),
+                ],
+            ),
+            (
+                "lv",
+                &[
+                    (Zero, &[0, 10, 11, 19, 20, 30, 100, 1000]),
+                    (One, &[1, 21, 31, 81, 101, 1001]),
+                    (Other, &[2, 9, 22, 29, 102, 1002]),
+                ],
+            ),
+            (
+                "ro",
+                &[
+                    (One, &[1]),
+                    (Few, &[0, 2, 16, 19, 101, 119, 1001]),
+                    (Other, &[20, 35, 100, 120, 1000, 1000000]),
+                ],
+            ),
+            (
+                "sl",
+                &[
+                    (One, &[1, 101, 201, 1001]),
+                    (Two, &[2, 102, 202, 1002]),
+                    (Few, &[3, 4, 103, 104, 1003]),
+                    (Other, &[0, 5, 19, 100, 1000]),
+                ],
+            ),
+            (
+                "he",
+                &[(One, &[1]), (Two, &[2]), (Other, &[0, 3, 17, 100, 1000])],
+            ),
+            (
+                "ar",
+                &[
+                    (Zero, &[0]),
+                    (One, &[1]),
+                    (Two, &[2]),
+                    (Few, &[3, 10, 103, 110, 1003]),
+                    (Many, &[11, 26, 111, 1011]),
+                    (Other, &[100, 102, 200, 202, 600, 1000, 10000]),
+                ],
+            ),
+            (
+                "ga",
+                &[
+                    (One, &[1]),
+                    (Two, &[2]),
+                    (Few, &[3, 6]),
+                    (Many, &[7, 10]),
+                    (Other, &[0, 11, 25, 100, 1000]),
+                ],
+            ),
+            (
+                "cy",
+                &[
+                    (Zero, &[0]),
+                    (One, &[1]),
+                    (Two, &[2]),
+                    (Few, &[3]),
+                    (Many, &[6]),
+                    (Other, &[4, 5, 7, 20, 100, 1000]),
+                ],
+            ),
+        ];
+
+        for (language, categories) in samples {
+            let rule = plural_rule(language);
+            for (category, ns) in *categories {
+                for &n in *ns {
+                    assert_eq!(rule(n), *category, "{language} {n}");
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,117 @@
+use std::{cell::Cell, fmt, marker::PhantomData};
+
+use ravel::{with, Builder, Cx, CxRep, MemSize, State, Token};
+
+use crate::ViewMarker;
+
+thread_local! {
+    static NEXT_ID: Cell<u32> = const { Cell::new(0) };
+}
+
+/// A unique element id, generated by [`auto_id`].
+///
+/// This is [`Copy`], and can be used directly as the value of attributes like
+/// [`Id`](crate::attr::Id) and [`For`](crate::attr::For), or
+/// formatted into a list for attributes like `aria-describedby`.
+#[derive(Copy, Clone, PartialEq, Eq, Hash, Debug)]
+pub struct AutoId(u32);
+
+impl fmt::Display for AutoId {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "ravel-{}", self.0)
+    }
+}
+
+/// Generates a new [`AutoId`], distinct from every other one generated by the
+/// current thread.
+///
+/// Ids are numbered in the order they are generated, so building the same
+/// components always generates the same ids. [`ssr::render_to_string`]
+/// numbers each page from the start, so server rendered ids match those of the
+/// first client build.
+///
+/// Usually, [`with_auto_id`] is more convenient.
+///
+/// [`ssr::render_to_string`]: crate::ssr::render_to_string
+pub fn auto_id() -> AutoId {
+    AutoId(NEXT_ID.replace(NEXT_ID.get() + 1))
+}
+
+/// Calls `f` with ids numbered from the start, restoring the previous
+/// numbering afterwards.
+pub(crate) fn fresh_ids<T>(f: impl FnOnce() -> T) -> T {
+    let next = NEXT_ID.replace(0);
+    let result = f();
+    NEXT_ID.set(next);
+    result
+}
+
+/// A [`Builder`] created from [`with_auto_id`].
+pub struct WithAutoId<F, S> {
+    f: F,
+    phantom: PhantomData<S>,
+}
+
+/// Creates a [`Builder`] which has access to an [`AutoId`], generated when the
+/// component is initially built and kept until it is removed.
+///
+/// This replaces hand written id strings for wiring elements together, which
+/// would collide between several instances of the same component:
+///
+/// ```ignore
+/// with_auto_id(|cx, id| {
+///     cx.build((
+///         label((For(id), "Name")),
+///         input((Id(id), Type("text"))),
+///     ))
+/// })
+/// ```
+///
+/// Like [`with`], `f` must call [`Cx::build`] to return a [`Token`].
+pub fn with_auto_id<F, S, R: CxRep>(f: F) -> WithAutoId<F, S>
+where
+    F: FnOnce(Cx<S, R>, AutoId) -> Token<S>,
+{
+    WithAutoId {
+        f,
+        phantom: PhantomData,
+    }
+}
+
+impl<R: CxRep, F, S> Builder<R> for WithAutoId<F, S>
+where
+    F: FnOnce(Cx<S, R>, AutoId) -> Token<S>,
+{
+    type State = WithAutoIdState<S>;
+
+    fn build(self, cx: R::BuildCx<'_>) -> Self::State {
+        let id = auto_id();
+        let inner = with(|cx| (self.f)(cx, id)).build(cx);
+        WithAutoIdState { id, inner }
+    }
+
+    fn rebuild(self, cx: R::RebuildCx<'_>, state: &mut Self::State) {
+        let id = state.id;
+        with(|cx| (self.f)(cx, id)).rebuild(cx, &mut state.inner)
+    }
+}
+
+/// The state of a [`WithAutoId`].
+pub struct WithAutoIdState<S> {
+    id: AutoId,
+    inner: S,
+}
+
+impl<S: State<Output>, Output> State<Output> for WithAutoIdState<S> {
+    fn run(&mut self, output: &mut Output) {
+        self.inner.run(output)
+    }
+}
+
+impl<S: MemSize> MemSize for WithAutoIdState<S> {
+    fn heap_size(&self) -> usize {
+        self.inner.heap_size()
+    }
+}
+
+impl<S: ViewMarker> ViewMarker for WithAutoIdState<S> {}
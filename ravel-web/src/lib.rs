@@ -3,21 +3,65 @@
 use std::sync::Arc;
 
 use atomic_waker::AtomicWaker;
-use dom::Position;
-use ravel::{AdaptState, Builder, Cx, CxRep, WithLocalState};
+use ravel::{
+    AdaptState, Builder, ContextState, Cx, CxRep, MemoState, TrackedState,
+    WithLocalKeyedState, WithLocalState, WithStoredState,
+};
 
+pub mod anchor;
+pub mod animate;
 mod any;
 pub mod attr;
+pub mod boundary;
 pub mod collections;
+pub mod command;
+pub mod css;
+pub mod devtools;
+pub mod direction;
 mod dom;
+mod either;
 pub mod el;
+pub mod env;
 pub mod event;
+pub mod file;
+pub mod flags;
+pub mod focus_zone;
+pub mod form;
+mod fragment;
+pub mod i18n;
+mod id;
+pub mod media;
+pub mod mem;
+pub mod observe;
 mod option;
+#[cfg(feature = "persist")]
+pub mod persist;
+pub mod portal;
+pub mod profile;
+pub mod prop;
 pub mod run;
+pub mod scroll;
+pub mod services;
+pub mod spring;
+pub mod ssr;
+pub mod stats;
+pub mod style;
+mod suspense;
 pub mod text;
+pub mod timer;
+pub mod transition;
+pub mod units;
+mod vec;
+pub mod widgets;
 
 pub use any::*;
+pub use dom::{set_markers, Markers, Position};
+pub use either::*;
+pub use fragment::*;
+pub use id::*;
 pub use option::*;
+pub use suspense::*;
+pub use vec::*;
 
 /// A dummy type representing the web backend.
 pub struct Web;
@@ -41,11 +85,52 @@ pub struct RebuildCx<'cx> {
     waker: &'cx Arc<AtomicWaker>,
 }
 
+impl<'cx> BuildCx<'cx> {
+    /// A context for building components at `position`.
+    pub fn new(position: Position<'cx>) -> Self {
+        Self { position }
+    }
+
+    /// Where new nodes are inserted.
+    pub fn position(&self) -> Position<'cx> {
+        self.position
+    }
+}
+
+impl<'cx> RebuildCx<'cx> {
+    /// The element containing the component being rebuilt.
+    pub fn parent(&self) -> &'cx web_sys::Element {
+        self.parent
+    }
+
+    /// A context for building new components in the same parent, before
+    /// `insert_before` (for example, an end marker from
+    /// [`anchor::Anchors`]).
+    pub fn build_before(
+        self,
+        insert_before: &'cx web_sys::Node,
+    ) -> BuildCx<'cx> {
+        BuildCx {
+            position: Position {
+                parent: self.parent,
+                insert_before,
+                waker: self.waker,
+            },
+        }
+    }
+}
+
 /// A marker trait for the [`ravel::State`] types of a [`trait@View`].
 pub trait ViewMarker {}
 
 impl<T: 'static, S: ViewMarker> ViewMarker for WithLocalState<T, S> {}
+impl<K, T: 'static, S: ViewMarker> ViewMarker for WithLocalKeyedState<K, T, S> {}
 impl<S: ViewMarker, F> ViewMarker for AdaptState<S, F> {}
+impl<T, S: ViewMarker> ViewMarker for MemoState<T, S> {}
+impl<S: ViewMarker> ViewMarker for TrackedState<S> {}
+impl<T, S: ViewMarker> ViewMarker for ContextState<T, S> {}
+impl<S: ViewMarker, const N: usize> ViewMarker for [S; N] {}
+impl<F, S: ViewMarker> ViewMarker for WithStoredState<F, S> {}
 
 macro_rules! tuple_state {
     ($($a:ident),*) => {
@@ -67,6 +152,14 @@ tuple_state!(a, b, c, d, e);
 tuple_state!(a, b, c, d, e, f);
 tuple_state!(a, b, c, d, e, f, g);
 tuple_state!(a, b, c, d, e, f, g, h);
+tuple_state!(a, b, c, d, e, f, g, h, i);
+tuple_state!(a, b, c, d, e, f, g, h, i, j);
+tuple_state!(a, b, c, d, e, f, g, h, i, j, k);
+tuple_state!(a, b, c, d, e, f, g, h, i, j, k, l);
+tuple_state!(a, b, c, d, e, f, g, h, i, j, k, l, m);
+tuple_state!(a, b, c, d, e, f, g, h, i, j, k, l, m, n);
+tuple_state!(a, b, c, d, e, f, g, h, i, j, k, l, m, n, o);
+tuple_state!(a, b, c, d, e, f, g, h, i, j, k, l, m, n, o, p);
 
 /// Trait for DOM fragments.
 ///
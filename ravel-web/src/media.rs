@@ -0,0 +1,193 @@
+//! Responsive views driven by media queries.
+
+use std::sync::Arc;
+
+use atomic_waker::AtomicWaker;
+use ravel::{MemSize, State};
+use web_sys::wasm_bindgen::UnwrapThrowExt as _;
+
+use crate::{
+    dom::{detach, marker, Position},
+    BuildCx, Builder, RebuildCx, View, ViewMarker, Web,
+};
+
+/// A CSS media query, such as `(min-width: 768px)`.
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct MediaQuery(pub String);
+
+/// Common viewport width breakpoints.
+#[derive(Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash, Debug)]
+pub enum Breakpoint {
+    /// 640px.
+    Sm,
+    /// 768px.
+    Md,
+    /// 1024px.
+    Lg,
+    /// 1280px.
+    Xl,
+}
+
+impl Breakpoint {
+    /// The minimum viewport width of this breakpoint, in pixels.
+    pub fn min_width(self) -> u32 {
+        match self {
+            Breakpoint::Sm => 640,
+            Breakpoint::Md => 768,
+            Breakpoint::Lg => 1024,
+            Breakpoint::Xl => 1280,
+        }
+    }
+
+    fn next(self) -> Option<Self> {
+        match self {
+            Breakpoint::Sm => Some(Breakpoint::Md),
+            Breakpoint::Md => Some(Breakpoint::Lg),
+            Breakpoint::Lg => Some(Breakpoint::Xl),
+            Breakpoint::Xl => None,
+        }
+    }
+
+    /// Viewports at least as wide as this breakpoint.
+    pub fn and_up(self) -> MediaQuery {
+        MediaQuery(format!("(min-width: {}px)", self.min_width()))
+    }
+
+    /// Viewports narrower than this breakpoint.
+    pub fn below(self) -> MediaQuery {
+        MediaQuery(format!("(max-width: {}.98px)", self.min_width() - 1))
+    }
+
+    /// Viewports at least as wide as this breakpoint, but narrower than the
+    /// next.
+    pub fn only(self) -> MediaQuery {
+        match self.next() {
+            Some(next) => MediaQuery(format!(
+                "(min-width: {}px) and (max-width: {}.98px)",
+                self.min_width(),
+                next.min_width() - 1,
+            )),
+            None => self.and_up(),
+        }
+    }
+}
+
+/// A [`Builder`] created from [`at_breakpoint`].
+pub struct AtBreakpoint<V> {
+    query: MediaQuery,
+    view: V,
+}
+
+impl<V: View> Builder<Web> for AtBreakpoint<V> {
+    type State = AtBreakpointState<V::State>;
+
+    fn build(self, cx: BuildCx) -> Self::State {
+        let start = marker("{");
+        let end = marker("}");
+
+        cx.position.insert(&start);
+        let state = self.view.build(cx);
+        cx.position.insert(&end);
+
+        let list = MediaList::new(self.query, cx.position.waker);
+        let detached = (!list.matches()).then(|| detach(&start, &end));
+
+        AtBreakpointState {
+            list,
+            start,
+            end,
+            detached,
+            state,
+        }
+    }
+
+    fn rebuild(self, cx: RebuildCx, state: &mut Self::State) {
+        if self.query != state.list.query {
+            state.list = MediaList::new(self.query, cx.waker);
+        }
+
+        if !state.list.matches() {
+            if state.detached.is_none() {
+                state.detached = Some(detach(&state.start, &state.end));
+            }
+            return;
+        }
+
+        if let Some(fragment) = state.detached.take() {
+            Position {
+                parent: cx.parent,
+                insert_before: &state.end,
+                waker: cx.waker,
+            }
+            .insert(&fragment);
+        }
+
+        self.view.rebuild(cx, &mut state.state)
+    }
+}
+
+/// A subscription to a media query.
+struct MediaList {
+    query: MediaQuery,
+    list: web_sys::MediaQueryList,
+    _handle: gloo_events::EventListener,
+}
+
+impl MediaList {
+    fn new(query: MediaQuery, waker: &Arc<AtomicWaker>) -> Self {
+        let list = gloo_utils::window()
+            .match_media(&query.0)
+            .unwrap_throw()
+            .unwrap_throw();
+
+        let waker = waker.clone();
+        let _handle =
+            gloo_events::EventListener::new(&list, "change", move |_| {
+                waker.wake()
+            });
+
+        Self {
+            query,
+            list,
+            _handle,
+        }
+    }
+
+    fn matches(&self) -> bool {
+        self.list.matches()
+    }
+}
+
+/// The state of an [`AtBreakpoint`].
+pub struct AtBreakpointState<S> {
+    list: MediaList,
+    start: web_sys::CharacterData,
+    end: web_sys::CharacterData,
+    detached: Option<web_sys::DocumentFragment>,
+    state: S,
+}
+
+impl<S: State<Output>, Output> State<Output> for AtBreakpointState<S> {
+    fn run(&mut self, output: &mut Output) {
+        self.state.run(output)
+    }
+}
+
+impl<S: MemSize> MemSize for AtBreakpointState<S> {
+    fn heap_size(&self) -> usize {
+        self.state.heap_size()
+    }
+}
+
+impl<S> ViewMarker for AtBreakpointState<S> {}
+
+/// Shows `view` only while the media `query` matches, such as
+/// `Breakpoint::Md.and_up()`.
+///
+/// Unlike an [`Option`], the view is not destroyed when the query stops
+/// matching: its nodes are detached from the document and its state is kept
+/// alive, so it is restored as it was when the query matches again. While
+/// detached, the view is not rebuilt.
+pub fn at_breakpoint<V: View>(query: MediaQuery, view: V) -> AtBreakpoint<V> {
+    AtBreakpoint { query, view }
+}
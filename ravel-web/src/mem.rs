@@ -0,0 +1,172 @@
+//! Estimates of the memory retained by component state.
+//!
+//! Components wrapped with [`measure`] record the size of their state, as
+//! estimated by [`MemSize`], each time they are built or rebuilt. [`report`]
+//! then sums these by name, which helps to find the parts of a large app (for
+//! example, long lists) which retain the most memory:
+//!
+//! ```ignore
+//! for usage in mem::report() {
+//!     log::info!("{}: {} bytes in {}", usage.name, usage.bytes, usage.instances);
+//! }
+//! ```
+//!
+//! Measuring walks the whole state of the wrapped component, so it is best
+//! reserved for debugging, or for components which are rebuilt rarely.
+//!
+//! Only components whose state type is known can be measured: a function
+//! returning [`View!`](crate::View!) hides it, so wrap the body of the
+//! function rather than its result.
+
+use std::{
+    cell::RefCell, cmp::Reverse, collections::BTreeMap, convert::Infallible,
+};
+
+use ravel::{MemSize, State};
+
+use crate::{
+    ssr::{Ssr, SsrCx},
+    BuildCx, Builder, RebuildCx, ViewMarker, Web,
+};
+
+/// A [`Builder`] created from [`measure`].
+pub struct Measure<B> {
+    name: &'static str,
+    inner: B,
+}
+
+/// Wraps a component so that the size of its state is included in [`report`]
+/// as `name`.
+///
+/// Measured components may be nested, in which case the outer one's size
+/// includes the inner one's.
+pub fn measure<B: Builder<Web>>(name: &'static str, inner: B) -> Measure<B>
+where
+    B::State: MemSize,
+{
+    Measure { name, inner }
+}
+
+impl<B: Builder<Web>> Builder<Web> for Measure<B>
+where
+    B::State: MemSize,
+{
+    type State = MeasureState<B::State>;
+
+    fn build(self, cx: BuildCx) -> Self::State {
+        let inner = self.inner.build(cx);
+        let id = REGISTRY.with_borrow_mut(|r| r.insert(self.name));
+
+        let state = MeasureState { id, inner };
+        state.record();
+        state
+    }
+
+    fn rebuild(self, cx: RebuildCx, state: &mut Self::State) {
+        self.inner.rebuild(cx, &mut state.inner);
+        state.record();
+    }
+}
+
+impl<B: Builder<Ssr>> Builder<Ssr> for Measure<B> {
+    type State = B::State;
+
+    fn build(self, cx: SsrCx) -> Self::State {
+        self.inner.build(cx)
+    }
+
+    fn rebuild(self, cx: Infallible, _: &mut Self::State) {
+        match cx {}
+    }
+}
+
+/// The state of a [`Measure`].
+pub struct MeasureState<S: MemSize> {
+    id: u32,
+    inner: S,
+}
+
+impl<S: MemSize> MeasureState<S> {
+    fn record(&self) {
+        let bytes = self.mem_size();
+        REGISTRY.with_borrow_mut(|r| {
+            if let Some(entry) = r.entries.get_mut(&self.id) {
+                entry.1 = bytes;
+            }
+        })
+    }
+}
+
+impl<S: MemSize> Drop for MeasureState<S> {
+    fn drop(&mut self) {
+        REGISTRY.with_borrow_mut(|r| r.entries.remove(&self.id));
+    }
+}
+
+impl<S: MemSize + State<Output>, Output> State<Output> for MeasureState<S> {
+    fn run(&mut self, output: &mut Output) {
+        self.inner.run(output)
+    }
+}
+
+impl<S: MemSize> MemSize for MeasureState<S> {
+    fn heap_size(&self) -> usize {
+        self.inner.heap_size()
+    }
+}
+
+impl<S: MemSize + ViewMarker> ViewMarker for MeasureState<S> {}
+
+/// The memory retained by the mounted components measured with a name.
+#[derive(Copy, Clone, PartialEq, Eq, Hash, Debug)]
+pub struct Usage {
+    /// The name passed to [`measure`].
+    pub name: &'static str,
+    /// The number of mounted components with the name.
+    pub instances: usize,
+    /// The estimated size of their state in bytes, as of their last build or
+    /// rebuild.
+    pub bytes: usize,
+}
+
+/// The memory retained by each name passed to [`measure`], largest first.
+///
+/// Components which have been removed aren't included.
+pub fn report() -> Vec<Usage> {
+    let mut usages = BTreeMap::<&'static str, Usage>::new();
+    REGISTRY.with_borrow(|r| {
+        for &(name, bytes) in r.entries.values() {
+            let usage = usages.entry(name).or_insert(Usage {
+                name,
+                instances: 0,
+                bytes: 0,
+            });
+            usage.instances += 1;
+            usage.bytes += bytes;
+        }
+    });
+
+    let mut usages: Vec<_> = usages.into_values().collect();
+    usages.sort_by_key(|usage| Reverse(usage.bytes));
+    usages
+}
+
+#[derive(Default)]
+struct Registry {
+    next_id: u32,
+    /// The name and latest size of each mounted component.
+    entries: BTreeMap<u32, (&'static str, usize)>,
+}
+
+impl Registry {
+    fn insert(&mut self, name: &'static str) -> u32 {
+        let id = self.next_id;
+        self.next_id += 1;
+        self.entries.insert(id, (name, 0));
+        id
+    }
+}
+
+thread_local! {
+    static REGISTRY: RefCell<Registry> = RefCell::default();
+}
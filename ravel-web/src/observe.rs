@@ -0,0 +1,225 @@
+//! Observing the size and visibility of elements.
+//!
+//! These wrap
+//! [`ResizeObserver`](https://developer.mozilla.org/en-US/docs/Web/API/ResizeObserver)
+//! and
+//! [`IntersectionObserver`](https://developer.mozilla.org/en-US/docs/Web/API/IntersectionObserver),
+//! observing the parent element from when they are built until they are
+//! removed. Like event handlers, their actions run on the next frame, with
+//! mutable access to the model. If several observations arrive before then,
+//! only the latest is passed to the action.
+
+use std::{
+    cell::Cell, convert::Infallible, marker::PhantomData, rc::Rc, sync::Arc,
+};
+
+use atomic_waker::AtomicWaker;
+use ravel::{Builder, MemSize, State};
+use web_sys::wasm_bindgen::{
+    closure::Closure, JsCast as _, JsValue, UnwrapThrowExt as _,
+};
+
+use crate::{
+    ssr::{Ssr, SsrCx},
+    BuildCx, RebuildCx, Web,
+};
+
+/// The size of an element's content box, in CSS pixels.
+#[derive(Copy, Clone, PartialEq, Debug, Default)]
+pub struct Size {
+    /// The width.
+    pub width: f64,
+    /// The height.
+    pub height: f64,
+}
+
+/// A kind of browser observer, which can be used with [`Observe`].
+pub trait Observer: 'static {
+    /// What is passed to actions for each observation.
+    type Value: Copy;
+
+    /// Creates an observer which calls `callback`, and observes `element`.
+    fn new(callback: &js_sys::Function, element: &web_sys::Element) -> Self;
+
+    /// Extracts the value of an entry passed to the callback.
+    fn value(entry: JsValue) -> Self::Value;
+
+    /// Stops observing.
+    fn disconnect(&self);
+}
+
+impl Observer for web_sys::ResizeObserver {
+    type Value = Size;
+
+    fn new(callback: &js_sys::Function, element: &web_sys::Element) -> Self {
+        let observer = web_sys::ResizeObserver::new(callback).unwrap_throw();
+        observer.observe(element);
+        observer
+    }
+
+    fn value(entry: JsValue) -> Size {
+        let rect = entry
+            .unchecked_into::<web_sys::ResizeObserverEntry>()
+            .content_rect();
+        Size {
+            width: rect.width(),
+            height: rect.height(),
+        }
+    }
+
+    fn disconnect(&self) {
+        self.disconnect()
+    }
+}
+
+impl Observer for web_sys::IntersectionObserver {
+    type Value = bool;
+
+    fn new(callback: &js_sys::Function, element: &web_sys::Element) -> Self {
+        let observer =
+            web_sys::IntersectionObserver::new(callback).unwrap_throw();
+        observer.observe(element);
+        observer
+    }
+
+    fn value(entry: JsValue) -> bool {
+        entry
+            .unchecked_into::<web_sys::IntersectionObserverEntry>()
+            .is_intersecting()
+    }
+
+    fn disconnect(&self) {
+        self.disconnect()
+    }
+}
+
+/// An observer of the parent element, created with [`observe_resize`] or
+/// [`observe_visibility`].
+pub struct Observe<O, Action> {
+    action: Action,
+    observer: PhantomData<O>,
+}
+
+/// Calls `action` with the [`Size`] of the parent element's content box when
+/// it is first laid out, and whenever it changes.
+///
+/// This is for components which adapt to the space they are given, rather
+/// than the size of the viewport as with
+/// [`at_breakpoint`](crate::media::at_breakpoint).
+///
+/// This must be used as (or in) the body of an element, like
+/// [`with_ref`](crate::el::with_ref).
+pub fn observe_resize<Action, Output>(
+    action: Action,
+) -> Observe<web_sys::ResizeObserver, Action>
+where
+    Action: 'static + FnMut(&mut Output, Size),
+    Output: 'static,
+{
+    Observe {
+        action,
+        observer: PhantomData,
+    }
+}
+
+/// Calls `action` with whether the parent element intersects the viewport when
+/// it is first laid out, and whenever that changes.
+///
+/// This is for lazy loading, for example of an image or the next page of a
+/// list, once it is scrolled into view.
+///
+/// This must be used as (or in) the body of an element, like
+/// [`with_ref`](crate::el::with_ref).
+pub fn observe_visibility<Action, Output>(
+    action: Action,
+) -> Observe<web_sys::IntersectionObserver, Action>
+where
+    Action: 'static + FnMut(&mut Output, bool),
+    Output: 'static,
+{
+    Observe {
+        action,
+        observer: PhantomData,
+    }
+}
+
+impl<O: Observer, Action: 'static> Builder<Web> for Observe<O, Action> {
+    type State = ObserveState<O, Action>;
+
+    fn build(self, cx: BuildCx) -> Self::State {
+        let latest = Rc::new(Cell::new(None));
+        let callback = callback::<O>(&latest, cx.position.waker);
+        let observer =
+            O::new(callback.as_ref().unchecked_ref(), cx.position.parent);
+
+        ObserveState {
+            latest,
+            observer,
+            _callback: callback,
+            action: self.action,
+        }
+    }
+
+    fn rebuild(self, _: RebuildCx, state: &mut Self::State) {
+        state.action = self.action;
+    }
+}
+
+fn callback<O: Observer>(
+    latest: &Rc<Cell<Option<O::Value>>>,
+    waker: &Arc<AtomicWaker>,
+) -> Closure<dyn FnMut(js_sys::Array)> {
+    let latest = latest.clone();
+    let waker = waker.clone();
+
+    Closure::new(move |entries: js_sys::Array| {
+        let entry = entries.at(-1);
+        if !entry.is_undefined() {
+            latest.set(Some(O::value(entry)));
+            waker.wake();
+        }
+    })
+}
+
+impl<O, Action> Builder<Ssr> for Observe<O, Action> {
+    type State = ();
+
+    fn build(self, _: SsrCx) -> Self::State {}
+
+    fn rebuild(self, cx: Infallible, _: &mut Self::State) {
+        match cx {}
+    }
+}
+
+/// The state of an [`Observe`].
+pub struct ObserveState<O: Observer, Action> {
+    latest: Rc<Cell<Option<O::Value>>>,
+    observer: O,
+    _callback: Closure<dyn FnMut(js_sys::Array)>,
+    action: Action,
+}
+
+impl<O: Observer, Action> Drop for ObserveState<O, Action> {
+    fn drop(&mut self) {
+        self.observer.disconnect()
+    }
+}
+
+impl<O: Observer, Action> MemSize for ObserveState<O, Action> {
+    fn heap_size(&self) -> usize {
+        0
+    }
+}
+
+impl<O, Action, Output> State<Output> for ObserveState<O, Action>
+where
+    O: Observer,
+    Action: 'static + FnMut(&mut Output, O::Value),
+    Output: 'static,
+{
+    fn run(&mut self, output: &mut Output) {
+        if let Some(value) = self.latest.take() {
+            (self.action)(output, value)
+        }
+    }
+}
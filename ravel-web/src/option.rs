@@ -1,8 +1,10 @@
-use ravel::State;
-use web_sys::wasm_bindgen::UnwrapThrowExt as _;
+use std::convert::Infallible;
+
+use ravel::{MemSize, State};
 
 use crate::{
-    dom::{clear, Position},
+    dom::{clear, marker, Position},
+    ssr::{Ssr, SsrCx},
     BuildCx, Builder, RebuildCx, View, ViewMarker, Web,
 };
 
@@ -10,8 +12,8 @@ impl<V: View> Builder<Web> for Option<V> {
     type State = OptionState<V::State>;
 
     fn build(self, cx: BuildCx) -> Self::State {
-        let start = web_sys::Comment::new_with_data("{").unwrap_throw();
-        let end = web_sys::Comment::new_with_data("}").unwrap_throw();
+        let start = marker("{");
+        let end = marker("}");
 
         cx.position.insert(&start);
         let state = self.map(|b| b.build(cx));
@@ -41,11 +43,27 @@ impl<V: View> Builder<Web> for Option<V> {
     }
 }
 
+impl<B: Builder<Ssr>> Builder<Ssr> for Option<B> {
+    type State = Option<B::State>;
+
+    fn build(self, cx: SsrCx) -> Self::State {
+        cx.comment("{");
+        let state = self.map(|b| b.build(cx));
+        cx.comment("}");
+
+        state
+    }
+
+    fn rebuild(self, cx: Infallible, _: &mut Self::State) {
+        match cx {}
+    }
+}
+
 /// The state for an [`Option`]al component.
 pub struct OptionState<S> {
     state: Option<S>,
-    start: web_sys::Comment,
-    end: web_sys::Comment,
+    start: web_sys::CharacterData,
+    end: web_sys::CharacterData,
 }
 
 impl<S, Output> State<Output> for OptionState<S>
@@ -58,4 +76,10 @@ where
     }
 }
 
+impl<S: MemSize> MemSize for OptionState<S> {
+    fn heap_size(&self) -> usize {
+        self.state.heap_size()
+    }
+}
+
 impl<S> ViewMarker for OptionState<S> {}
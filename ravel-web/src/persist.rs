@@ -0,0 +1,253 @@
+//! Local state persisted to browser storage.
+//!
+//! Saved values are stored as JSON along with a schema version. When the
+//! schema of a persisted type changes, add a step to its [`Migrations`] to
+//! upgrade previously saved data as it is loaded.
+
+use std::{marker::PhantomData, rc::Rc};
+
+use ravel::{with, Builder, Cx, MemSize, State, Token};
+use serde::{de::DeserializeOwned, Serialize};
+use serde_json::Value;
+
+use crate::{BuildCx, RebuildCx, ViewMarker, Web};
+
+const VERSION: &str = "$version";
+const VALUE: &str = "$value";
+
+/// A chain of migrations for a persisted type.
+///
+/// The schema version is the number of migrations. Data saved at version `n`
+/// is upgraded when loaded by running migrations `n` onwards, in order. Data
+/// saved without a version (or before any migrations were added) is version
+/// `0`.
+#[derive(Clone, Default)]
+pub struct Migrations(Vec<Rc<dyn Fn(Value) -> Value>>);
+
+impl Migrations {
+    /// No migrations: the schema is at version `0`.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds a migration from the current version to the next.
+    pub fn then(mut self, migrate: impl 'static + Fn(Value) -> Value) -> Self {
+        self.0.push(Rc::new(migrate));
+        self
+    }
+
+    /// The current schema version.
+    pub fn version(&self) -> usize {
+        self.0.len()
+    }
+
+    fn upgrade(&self, json: Value) -> Option<Value> {
+        let (version, mut value) = match json {
+            Value::Object(mut object)
+                if object.len() == 2 && object.contains_key(VALUE) =>
+            {
+                let version = object.get(VERSION)?.as_u64()? as usize;
+                (version, object.remove(VALUE)?)
+            }
+            value => (0, value),
+        };
+
+        // Data from a newer version of the application can't be downgraded.
+        for migrate in self.0.get(version..)? {
+            value = migrate(value);
+        }
+
+        Some(value)
+    }
+}
+
+fn storage() -> Option<web_sys::Storage> {
+    gloo_utils::window().local_storage().ok().flatten()
+}
+
+/// Loads a value saved with [`save`], running any pending [`Migrations`].
+///
+/// Returns [`None`] if there is no saved value. If there is a saved value which
+/// can't be loaded (for example, because a migration is missing), it is copied
+/// to `"{key}.backup"` so it is not lost when a new value is saved.
+pub fn load<T: DeserializeOwned>(
+    key: &str,
+    migrations: &Migrations,
+) -> Option<T> {
+    let storage = storage()?;
+    let raw = storage.get_item(key).ok().flatten()?;
+
+    let value = serde_json::from_str(&raw)
+        .ok()
+        .and_then(|json| migrations.upgrade(json))
+        .and_then(|value| serde_json::from_value(value).ok());
+
+    if value.is_none() {
+        let _ = storage.set_item(&format!("{key}.backup"), &raw);
+    }
+
+    value
+}
+
+fn serialize<T: Serialize>(
+    value: &T,
+    migrations: &Migrations,
+) -> Option<String> {
+    let value = serde_json::to_value(value).ok()?;
+    let mut object = serde_json::Map::new();
+    object.insert(VERSION.to_string(), migrations.version().into());
+    object.insert(VALUE.to_string(), value);
+    serde_json::to_string(&object).ok()
+}
+
+/// Saves a value under `key`, tagged with the current version of
+/// `migrations`.
+pub fn save<T: Serialize>(key: &str, value: &T, migrations: &Migrations) {
+    let Some(json) = serialize(value, migrations) else {
+        return;
+    };
+
+    if let Some(storage) = storage() {
+        let _ = storage.set_item(key, &json);
+    }
+}
+
+/// A [`Builder`] created from [`with_local_persisted`].
+pub struct WithLocalPersisted<Init, F, S> {
+    key: &'static str,
+    migrations: Migrations,
+    init: Init,
+    f: F,
+    phantom: PhantomData<S>,
+}
+
+impl<T, Init, F, S> Builder<Web> for WithLocalPersisted<Init, F, S>
+where
+    T: Clone + PartialEq + Serialize + DeserializeOwned,
+    Init: FnOnce() -> T,
+    F: FnOnce(Cx<S, Web>, &T) -> Token<S>,
+{
+    type State = WithLocalPersistedState<T, S>;
+
+    fn build(self, cx: BuildCx) -> Self::State {
+        let value = load(self.key, &self.migrations).unwrap_or_else(self.init);
+        let saved = Some(value.clone());
+        let inner = with(|cx| (self.f)(cx, &value)).build(cx);
+
+        WithLocalPersistedState {
+            key: self.key,
+            migrations: self.migrations,
+            value,
+            saved,
+            inner,
+        }
+    }
+
+    fn rebuild(self, cx: RebuildCx, state: &mut Self::State) {
+        state.migrations = self.migrations;
+
+        if self.key != state.key {
+            state.key = self.key;
+            if let Some(value) = load(self.key, &state.migrations) {
+                state.value = value;
+            }
+            state.save();
+        }
+
+        with(|cx| (self.f)(cx, &state.value)).rebuild(cx, &mut state.inner)
+    }
+}
+
+/// The state of a [`WithLocalPersisted`].
+pub struct WithLocalPersistedState<T, S> {
+    key: &'static str,
+    migrations: Migrations,
+    value: T,
+    /// The last value written to (or read from) storage, to avoid serializing
+    /// the value on frames where it didn't change.
+    saved: Option<T>,
+    inner: S,
+}
+
+impl<T: Clone + PartialEq + Serialize, S> WithLocalPersistedState<T, S> {
+    fn save(&mut self) {
+        if self.saved.as_ref() == Some(&self.value) {
+            return;
+        }
+
+        let Some(json) = serialize(&self.value, &self.migrations) else {
+            return;
+        };
+
+        if let Some(storage) = storage() {
+            let _ = storage.set_item(self.key, &json);
+        }
+        self.saved = Some(self.value.clone());
+    }
+}
+
+impl<Output: Default, T, S> State<Output> for WithLocalPersistedState<T, S>
+where
+    T: 'static + Default + Clone + PartialEq + Serialize,
+    S: State<(Output, T)>,
+{
+    fn run(&mut self, output: &mut Output) {
+        let mut data =
+            (std::mem::take(output), std::mem::take(&mut self.value));
+        self.inner.run(&mut data);
+        (*output, self.value) = data;
+
+        self.save();
+    }
+}
+
+impl<T, S: ViewMarker> ViewMarker for WithLocalPersistedState<T, S> {}
+
+impl<Init, F, S> WithLocalPersisted<Init, F, S> {
+    /// Sets the [`Migrations`] used to upgrade previously saved data.
+    pub fn migrations(self, migrations: Migrations) -> Self {
+        Self { migrations, ..self }
+    }
+}
+
+impl<T: MemSize, S: MemSize> MemSize for WithLocalPersistedState<T, S> {
+    fn heap_size(&self) -> usize {
+        self.migrations.0.heap_size()
+            + self.value.heap_size()
+            + self.saved.heap_size()
+            + self.inner.heap_size()
+    }
+}
+
+/// Like [`ravel::with_local`], but the local state is saved to
+/// `localStorage` under `key` (as JSON) whenever it changes, and restored from
+/// there when the component is built.
+///
+/// Changes are detected by comparing the state with a clone of the last saved
+/// value after each frame, so it is only serialized when it differs.
+///
+/// This is useful for UI state which should survive reloads, such as collapsed
+/// panels or column widths, without storing it in the application model.
+///
+/// The `init` callback is only used if there is no valid saved value. If
+/// storage is unavailable, this behaves like [`ravel::with_local`]. If the
+/// type of the state changes, use [`WithLocalPersisted::migrations`] to
+/// upgrade old data (see [`load`]).
+pub fn with_local_persisted<T, Init, F, S>(
+    key: &'static str,
+    init: Init,
+    f: F,
+) -> WithLocalPersisted<Init, F, S>
+where
+    T: Clone + PartialEq + Serialize + DeserializeOwned,
+    Init: FnOnce() -> T,
+    F: FnOnce(Cx<S, Web>, &T) -> Token<S>,
+{
+    WithLocalPersisted {
+        key,
+        migrations: Migrations::new(),
+        init,
+        f,
+        phantom: PhantomData,
+    }
+}
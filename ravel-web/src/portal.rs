@@ -0,0 +1,99 @@
+//! Rendering into a different part of the document.
+
+use ravel::{MemSize, State};
+use web_sys::wasm_bindgen::UnwrapThrowExt as _;
+
+use crate::{
+    dom::{clear, detach, marker, Position},
+    BuildCx, Builder, RebuildCx, View, ViewMarker, Web,
+};
+
+/// A [`Builder`] created from [`portal`].
+pub struct Portal<V> {
+    target: web_sys::Element,
+    body: V,
+}
+
+/// Builds `body` at the end of `target`, rather than in place, while its state
+/// stays in the component tree as usual.
+///
+/// This lets modals and tooltips escape the overflow and stacking context of
+/// their ancestors, for example with `gloo_utils::body()` as the `target`. When
+/// the portal is removed (for example, by an [`Option`] becoming [`None`]), its
+/// nodes are removed from `target`. If `target` changes, the nodes are moved.
+pub fn portal<V: View>(target: web_sys::Element, body: V) -> Portal<V> {
+    Portal { target, body }
+}
+
+impl<V: View> Builder<Web> for Portal<V> {
+    type State = PortalState<V::State>;
+
+    fn build(self, cx: BuildCx) -> Self::State {
+        let start = marker("<");
+        let end = marker(">");
+
+        self.target.append_with_node_2(&start, &end).unwrap_throw();
+        let state = self.body.build(BuildCx {
+            position: Position {
+                parent: &self.target,
+                insert_before: &end,
+                waker: cx.position.waker,
+            },
+        });
+
+        PortalState {
+            target: self.target,
+            start,
+            end,
+            state,
+        }
+    }
+
+    fn rebuild(self, cx: RebuildCx, state: &mut Self::State) {
+        if self.target != state.target {
+            let fragment = detach(&state.start, &state.end);
+            self.target.append_with_node_1(&state.start).unwrap_throw();
+            self.target.append_with_node_1(&fragment).unwrap_throw();
+            self.target.append_with_node_1(&state.end).unwrap_throw();
+            state.target = self.target;
+        }
+
+        self.body.rebuild(
+            RebuildCx {
+                parent: &state.target,
+                waker: cx.waker,
+            },
+            &mut state.state,
+        )
+    }
+}
+
+/// The state of a [`Portal`].
+pub struct PortalState<S> {
+    target: web_sys::Element,
+    start: web_sys::CharacterData,
+    end: web_sys::CharacterData,
+    state: S,
+}
+
+impl<S> Drop for PortalState<S> {
+    fn drop(&mut self) {
+        clear(&self.target, &self.start, &self.end);
+        self.start.remove();
+        self.end.remove();
+    }
+}
+
+impl<S: MemSize> MemSize for PortalState<S> {
+    fn heap_size(&self) -> usize {
+        self.state.heap_size()
+    }
+}
+
+impl<S: State<Output>, Output> State<Output> for PortalState<S> {
+    fn run(&mut self, output: &mut Output) {
+        self.state.run(output)
+    }
+}
+
+impl<S> ViewMarker for PortalState<S> {}
@@ -0,0 +1,62 @@
+//! Component profiling with the
+//! [User Timing API](https://developer.mozilla.org/en-US/docs/Web/API/Performance_API/User_timing).
+//!
+//! Instrumentation is only emitted when the `profile` feature is enabled.
+//! Otherwise, [`profile`] is a zero cost wrapper.
+
+use crate::{BuildCx, Builder, RebuildCx, Web};
+
+/// A [`Builder`] created from [`profile`].
+pub struct Profile<B> {
+    name: &'static str,
+    inner: B,
+}
+
+impl<B: Builder<Web>> Builder<Web> for Profile<B> {
+    type State = B::State;
+
+    fn build(self, cx: BuildCx) -> Self::State {
+        let inner = self.inner;
+        measure(self.name, "build", || inner.build(cx))
+    }
+
+    fn rebuild(self, cx: RebuildCx, state: &mut Self::State) {
+        let inner = self.inner;
+        measure(self.name, "rebuild", || inner.rebuild(cx, state))
+    }
+}
+
+/// Wraps a component so that each build and rebuild is recorded as a
+/// `performance.measure` entry named `"{name} build"` or `"{name} rebuild"`.
+///
+/// These show up in the timings track of browser devtools performance traces.
+pub fn profile<B: Builder<Web>>(name: &'static str, inner: B) -> Profile<B> {
+    Profile { name, inner }
+}
+
+#[cfg(feature = "profile")]
+fn measure<R>(name: &str, phase: &str, f: impl FnOnce() -> R) -> R {
+    let Some(performance) = gloo_utils::window().performance() else {
+        return f();
+    };
+
+    let measure = format!("{name} {phase}");
+    let start = format!("{measure} start");
+    let end = format!("{measure} end");
+
+    let _ = performance.mark(&start);
+    let result = f();
+    let _ = performance.mark(&end);
+    let _ = performance
+        .measure_with_start_mark_and_end_mark(&measure, &start, &end);
+
+    performance.clear_marks_with_mark_name(&start);
+    performance.clear_marks_with_mark_name(&end);
+
+    result
+}
+
+#[cfg(not(feature = "profile"))]
+fn measure<R>(_: &str, _: &str, f: impl FnOnce() -> R) -> R {
+    f()
+}
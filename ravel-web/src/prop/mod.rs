@@ -0,0 +1,85 @@
+//! DOM properties.
+//!
+//! Unlike [attributes](crate::attr), which only reflect the initial state of
+//! some elements, properties are the live state of the element (for example,
+//! the current `value` of an `<input>` after the user has typed in it).
+//! Property builders compare against the live value on every rebuild, and
+//! write it whenever they differ.
+
+use std::{convert::Infallible, marker::PhantomData};
+
+use ravel::Builder;
+
+use crate::{
+    ssr::{Ssr, SsrCx},
+    BuildCx, RebuildCx, Web,
+};
+
+use self::types::*;
+
+pub mod types;
+
+/// An arbitrary property.
+pub fn prop<Kind: PropKind, Value: PropValue>(
+    _: Kind,
+    value: Value,
+) -> Prop<Kind, Value> {
+    Prop {
+        value,
+        kind: PhantomData,
+    }
+}
+
+macro_rules! make_prop_value_type {
+    ($name:literal, $t:ident) => {
+        impl Builder<Web> for $t {
+            type State = PropState;
+
+            fn build(self, cx: BuildCx) -> Self::State {
+                PropState::build(cx.position.parent, $name, self.0)
+            }
+
+            fn rebuild(self, cx: RebuildCx, state: &mut Self::State) {
+                state.rebuild(cx.parent, $name, self.0)
+            }
+        }
+
+        impl Builder<Ssr> for $t {
+            type State = ();
+
+            fn build(self, _: SsrCx) -> Self::State {}
+
+            fn rebuild(self, cx: Infallible, _: &mut Self::State) {
+                match cx {}
+            }
+        }
+    };
+}
+
+macro_rules! make_prop_value_trait {
+    ($name:literal, $t:ident) => {
+        impl<V: PropValue> Builder<Web> for $t<V> {
+            type State = PropState;
+
+            fn build(self, cx: BuildCx) -> Self::State {
+                PropState::build(cx.position.parent, $name, self.0)
+            }
+
+            fn rebuild(self, cx: RebuildCx, state: &mut Self::State) {
+                state.rebuild(cx.parent, $name, self.0)
+            }
+        }
+
+        impl<V: PropValue> Builder<Ssr> for $t<V> {
+            type State = ();
+
+            fn build(self, _: SsrCx) -> Self::State {}
+
+            fn rebuild(self, cx: Infallible, _: &mut Self::State) {
+                match cx {}
+            }
+        }
+    };
+}
+
+include!(concat!(env!("OUT_DIR"), "/gen_prop.rs"));
@@ -0,0 +1,145 @@
+//! DOM property types.
+//!
+//! Usually you shouldn't need to import or reference these directly.
+
+use std::{convert::Infallible, marker::PhantomData};
+
+use ravel::{Builder, MemSize, State};
+use web_sys::wasm_bindgen::JsValue;
+
+use crate::{
+    attr::CloneString,
+    ssr::{Ssr, SsrCx},
+    BuildCx, RebuildCx, Web,
+};
+
+/// Trait to identify property types.
+pub trait PropKind: 'static {
+    /// The name of the property.
+    const NAME: &'static str;
+}
+
+/// Trait for values which can be assigned to a property.
+pub trait PropValue {
+    fn to_js(&self) -> JsValue;
+}
+
+impl PropValue for bool {
+    fn to_js(&self) -> JsValue {
+        JsValue::from_bool(*self)
+    }
+}
+
+impl PropValue for &'static str {
+    fn to_js(&self) -> JsValue {
+        JsValue::from_str(self)
+    }
+}
+
+impl PropValue for String {
+    fn to_js(&self) -> JsValue {
+        JsValue::from_str(self)
+    }
+}
+
+impl<V: AsRef<str>> PropValue for CloneString<V> {
+    fn to_js(&self) -> JsValue {
+        JsValue::from_str(self.0.as_ref())
+    }
+}
+
+macro_rules! make_prop_value_number {
+    ($t:ty) => {
+        impl PropValue for $t {
+            fn to_js(&self) -> JsValue {
+                JsValue::from(*self)
+            }
+        }
+    };
+}
+
+make_prop_value_number!(f32);
+make_prop_value_number!(f64);
+make_prop_value_number!(i8);
+make_prop_value_number!(i16);
+make_prop_value_number!(i32);
+make_prop_value_number!(u8);
+make_prop_value_number!(u16);
+make_prop_value_number!(u32);
+
+/// The state of a property.
+pub struct PropState;
+
+impl PropState {
+    pub(crate) fn build(
+        parent: &web_sys::Element,
+        name: &'static str,
+        value: impl PropValue,
+    ) -> Self {
+        set_property(parent, name, &value.to_js());
+        Self
+    }
+
+    pub(crate) fn rebuild(
+        &mut self,
+        parent: &web_sys::Element,
+        name: &'static str,
+        value: impl PropValue,
+    ) {
+        let value = value.to_js();
+        let current = js_sys::Reflect::get(parent, &JsValue::from_str(name))
+            .unwrap_or(JsValue::UNDEFINED);
+
+        if !js_sys::Object::is(&current, &value) {
+            set_property(parent, name, &value);
+        }
+    }
+}
+
+fn set_property(
+    parent: &web_sys::Element,
+    name: &'static str,
+    value: &JsValue,
+) {
+    let _ = js_sys::Reflect::set(parent, &JsValue::from_str(name), value);
+}
+
+impl<Output> State<Output> for PropState {
+    fn run(&mut self, _: &mut Output) {}
+}
+
+impl MemSize for PropState {
+    fn heap_size(&self) -> usize {
+        0
+    }
+}
+
+/// An arbitrary property.
+#[repr(transparent)]
+#[derive(Copy, Clone, Debug)]
+pub struct Prop<Kind: PropKind, Value> {
+    pub(crate) value: Value,
+    pub(crate) kind: PhantomData<Kind>,
+}
+
+impl<Kind: PropKind, Value: PropValue> Builder<Web> for Prop<Kind, Value> {
+    type State = PropState;
+
+    fn build(self, cx: BuildCx) -> Self::State {
+        PropState::build(cx.position.parent, Kind::NAME, self.value)
+    }
+
+    fn rebuild(self, cx: RebuildCx, state: &mut Self::State) {
+        state.rebuild(cx.parent, Kind::NAME, self.value)
+    }
+}
+
+impl<Kind: PropKind, Value: PropValue> Builder<Ssr> for Prop<Kind, Value> {
+    type State = ();
+
+    fn build(self, _: SsrCx) -> Self::State {}
+
+    fn rebuild(self, cx: Infallible, _: &mut Self::State) {
+        match cx {}
+    }
+}
@@ -1,11 +1,27 @@
 //! Run an event loop for a top-level component.
-use std::sync::Arc;
+use std::{
+    any::Any,
+    cell::Cell,
+    future::Future,
+    ops::ControlFlow,
+    panic::{catch_unwind, resume_unwind, AssertUnwindSafe},
+    pin::Pin,
+    rc::Rc,
+    sync::Arc,
+};
 
 use atomic_waker::AtomicWaker;
 use ravel::{with, Builder, State, Token};
-use web_sys::wasm_bindgen::JsValue;
+use web_sys::wasm_bindgen::{JsValue, UnwrapThrowExt as _};
 
-use crate::{dom::Position, BuildCx, Cx, RebuildCx, Web};
+use crate::{
+    command,
+    dom::{clear, Position},
+    el::finish_render,
+    env,
+    services::Services,
+    BuildCx, Cx, RebuildCx, Web,
+};
 
 /// Runs a component on an arbitrary [`web_sys::Element`].
 ///
@@ -21,48 +37,400 @@ use crate::{dom::Position, BuildCx, Cx, RebuildCx, Web};
 /// 1. `render` the `Data`.
 /// 1. Suspend the `async` task until awoken.
 /// 1. `sync` the `Data` (for example, write updates to an external data store).
+///
+/// # Batching
+///
+/// Every event which arrives while the task is suspended is handled in a
+/// single pass once it wakes: all pending event handlers run in the order the
+/// events occurred, followed by one `sync` and one rebuild. So handlers never
+/// observe each other's effects on the DOM, and the cost of a rebuild is paid
+/// once, no matter how many handlers fired. See [`Timing`] to also batch
+/// events across a whole animation frame, [`request_render`] for handlers
+/// which need another pass straight away, and
+/// [`after_render`](crate::el::after_render) to measure layout after a
+/// rebuild.
 pub async fn run<Data, Sync, Render, S, R>(
     parent: &web_sys::Element,
     data: &mut Data,
-    mut sync: Sync,
+    sync: Sync,
+    render: Render,
+) -> R
+where
+    S: State<Data>,
+    Sync: FnMut(&mut Data) -> Option<R>,
+    Render: FnMut(Cx<S, Web>, &Data) -> Token<S>,
+{
+    run_with_options(parent, data, RunOptions::default(), sync, render).await
+}
+
+/// When the event loop rebuilds after being woken, as configured with
+/// [`RunOptions`].
+#[derive(Copy, Clone, PartialEq, Eq, Debug, Default)]
+pub enum Timing {
+    /// Rebuild as soon as the browser yields to the event loop. Under rapid
+    /// events (like `mousemove`), this may rebuild several times per frame.
+    #[default]
+    Immediate,
+    /// Wait for the next animation frame, and handle every event which arrived
+    /// in the meantime with a single rebuild. This adds up to a frame of
+    /// latency, but never does more than one rebuild per frame.
+    FrameAligned,
+}
+
+/// Options for the event loop, as passed to [`run_with_options`] and the other
+/// entry points of this module.
+#[derive(Clone, Default)]
+pub struct RunOptions {
+    /// When to rebuild after being woken.
+    pub timing: Timing,
+    /// Services provided to the whole application, including event handlers
+    /// and the `sync` callback. See [`crate::services`] for details.
+    pub services: Services,
+    /// What to do when the application panics. This only applies to
+    /// [`spawn_body_with_options`], since the `run` functions let panics unwind
+    /// to their caller.
+    pub panic_policy: PanicPolicy,
+}
+
+/// Like [`run`], but with [`RunOptions`].
+pub async fn run_with_options<Data, Sync, Render, S, R>(
+    parent: &web_sys::Element,
+    data: &mut Data,
+    options: RunOptions,
+    sync: Sync,
     mut render: Render,
 ) -> R
 where
     S: State<Data>,
     Sync: FnMut(&mut Data) -> Option<R>,
     Render: FnMut(Cx<S, Web>, &Data) -> Token<S>,
+{
+    let insert_before = &JsValue::NULL.into();
+    let position = (parent, insert_before);
+    run_loop(
+        position,
+        data,
+        options,
+        sync,
+        Unprepared,
+        || {},
+        Propagate,
+        |cx, data, _: &()| render(cx, data),
+    )
+    .await
+}
+
+/// Like [`run_with_options`], but executes the commands emitted by event
+/// handlers.
+///
+/// Commands of type `Cmd`, returned by handlers created with
+/// [`on_command`](crate::event::on_command) or passed to
+/// [`command::emit`], are collected while the handlers run. Once the following
+/// rebuild is done, they are passed to `execute` in the order they were
+/// emitted. The executor typically starts asynchronous work, whose results are
+/// fed back into the `Data`, for example through a shared queue which `sync`
+/// drains.
+pub async fn run_with_commands<Data, Cmd, Sync, Execute, Render, S, R>(
+    parent: &web_sys::Element,
+    data: &mut Data,
+    options: RunOptions,
+    sync: Sync,
+    mut execute: Execute,
+    mut render: Render,
+) -> R
+where
+    Cmd: 'static,
+    S: State<Data>,
+    Sync: FnMut(&mut Data) -> Option<R>,
+    Execute: FnMut(Cmd),
+    Render: FnMut(Cx<S, Web>, &Data) -> Token<S>,
+{
+    command::listen::<Cmd>();
+
+    let after_render = || {
+        for command in command::take::<Cmd>() {
+            execute(command)
+        }
+    };
+    let insert_before = &JsValue::NULL.into();
+    let position = (parent, insert_before);
+    run_loop(
+        position,
+        data,
+        options,
+        sync,
+        Unprepared,
+        after_render,
+        Propagate,
+        |cx, data, _: &()| render(cx, data),
+    )
+    .await
+}
+
+/// How [`run_loop`] runs each pass, and recovers if one panics.
+trait Recover<Data, R> {
+    /// Runs a pass: the initial build, or the event handlers, `sync` and
+    /// rebuild which follow an event.
+    fn pass<T>(&mut self, f: impl FnOnce() -> T) -> Result<T, Panic>;
+
+    /// Called after every pass which completed.
+    fn completed(&mut self, data: &Data);
+
+    /// Called after a pass panicked and its state was dropped, to either
+    /// build again from `data` or stop with a result.
+    fn recover(
+        &mut self,
+        data: &mut Data,
+        panic: Panic,
+        rebuilding: bool,
+    ) -> ControlFlow<R>;
+}
+
+type Panic = Box<dyn Any + Send>;
+
+/// Computes the value passed to `render` before each build or rebuild of
+/// [`run_loop`].
+trait Prepare<Data> {
+    type Output;
+
+    async fn prepare(&mut self, data: &Data) -> Self::Output;
+}
+
+/// Renders from the `Data` alone.
+struct Unprepared;
+
+impl<Data> Prepare<Data> for Unprepared {
+    type Output = ();
+
+    async fn prepare(&mut self, _: &Data) {}
+}
+
+impl<Data, P, F> Prepare<Data> for F
+where
+    F: FnMut(&Data) -> Pin<Box<dyn '_ + Future<Output = P>>>,
+{
+    type Output = P;
+
+    async fn prepare(&mut self, data: &Data) -> P {
+        self(data).await
+    }
+}
+
+/// Lets panics unwind out of the event loop.
+struct Propagate;
+
+impl<Data, R> Recover<Data, R> for Propagate {
+    fn pass<T>(&mut self, f: impl FnOnce() -> T) -> Result<T, Panic> {
+        Ok(f())
+    }
+
+    fn completed(&mut self, _: &Data) {}
+
+    fn recover(
+        &mut self,
+        _: &mut Data,
+        panic: Panic,
+        _: bool,
+    ) -> ControlFlow<R> {
+        resume_unwind(panic)
+    }
+}
+
+/// The event loop shared by every entry point, which builds the component
+/// into `position` (a parent and the node to insert before).
+///
+/// Each pass runs the event handlers and `sync`, then awaits `prepare`, then
+/// rebuilds. Panics while preparing are not recovered by the `guard`.
+#[allow(clippy::too_many_arguments)]
+async fn run_loop<Data, Sync, Prep, AfterRender, Guard, Render, S, R>(
+    (parent, insert_before): (&web_sys::Element, &web_sys::Node),
+    data: &mut Data,
+    options: RunOptions,
+    mut sync: Sync,
+    mut prepare: Prep,
+    mut after_render: AfterRender,
+    mut guard: Guard,
+    mut render: Render,
+) -> R
+where
+    S: State<Data>,
+    Sync: FnMut(&mut Data) -> Option<R>,
+    Prep: Prepare<Data>,
+    AfterRender: FnMut(),
+    Guard: Recover<Data, R>,
+    Render: FnMut(Cx<S, Web>, &Data, &Prep::Output) -> Token<S>,
 {
     let waker = &Arc::new(AtomicWaker::new());
     waker.register(&futures_micro::waker().await);
 
-    let mut state = with(|cx| render(cx, data)).build(BuildCx {
-        position: Position {
-            parent,
-            insert_before: &JsValue::NULL.into(),
-            waker,
-        },
-    });
+    let mut state: Option<S> = None;
+    let mut again = true;
 
     loop {
-        futures_micro::sleep().await;
+        if !again {
+            futures_micro::sleep().await;
 
-        state.run(data);
-        if let Some(result) = sync(data) {
-            return result;
+            if options.timing == Timing::FrameAligned {
+                next_frame().await;
+            }
         }
 
-        with(|cx| render(cx, data))
-            .rebuild(RebuildCx { parent, waker }, &mut state);
+        let rebuilding = state.is_some();
+        let mut result = match &mut state {
+            None => Ok(None),
+            Some(state) => guard.pass(|| {
+                options.services.scope(|| {
+                    state.run(data);
+                    sync(data)
+                })
+            }),
+        };
 
+        let mut woken = false;
+        if let Ok(None) = result {
+            waker.register(&futures_micro::waker().await);
+            let prepared = prepare.prepare(data).await;
+            // If the waker was already taken, an event arrived while preparing.
+            woken = waker.take().is_none();
+
+            result = guard.pass(|| {
+                options.services.scope(|| match &mut state {
+                    None => {
+                        let position = Position {
+                            parent,
+                            insert_before,
+                            waker,
+                        };
+                        state = Some(
+                            with(|cx| render(cx, data, &prepared))
+                                .build(BuildCx { position }),
+                        );
+                    }
+                    Some(state) => {
+                        with(|cx| render(cx, data, &prepared))
+                            .rebuild(RebuildCx { parent, waker }, state);
+                    }
+                });
+                None
+            });
+        }
+
+        match result {
+            Ok(Some(result)) => return result,
+            Ok(None) => guard.completed(data),
+            Err(panic) => {
+                let _ = guard.pass(|| state = None);
+
+                match guard.recover(data, panic, rebuilding) {
+                    ControlFlow::Continue(()) => {
+                        again = true;
+                        waker.register(&futures_micro::waker().await);
+                        continue;
+                    }
+                    ControlFlow::Break(result) => return result,
+                }
+            }
+        }
+
+        again = finish_render() | woken;
+        after_render();
+        again |= RENDER_REQUESTED.take();
         waker.register(&futures_micro::waker().await);
     }
 }
 
+thread_local! {
+    static RENDER_REQUESTED: Cell<bool> = const { Cell::new(false) };
+}
+
+/// Requests another pass of the event loop immediately after the current one,
+/// without waiting for an event or animation frame.
+///
+/// This is for event handlers whose changes need to be rendered before
+/// anything else happens, for example to read back the resulting layout on the
+/// following pass. The extra pass runs after the current rebuild, in the same
+/// task, so the browser doesn't paint in between. Calling this more than once
+/// in a pass only causes one extra pass.
+pub fn request_render() {
+    RENDER_REQUESTED.set(true)
+}
+
+/// Like [`run_with_options`], but with a `prepare` phase before each build or
+/// rebuild.
+///
+/// `prepare` computes derived data (such as layout or formatted text) from
+/// the `Data`, which is then passed to `render`. Since it is asynchronous, it
+/// can split expensive work into chunks with [`yield_now`], or hand it off to
+/// a worker, without blocking the browser. DOM changes are still only made
+/// by `render`, all at once.
+///
+/// The event loop repeats the following steps:
+///
+/// 1. `prepare` the `Data`.
+/// 1. `render` the `Data` and the prepared value.
+/// 1. Suspend the `async` task until awoken.
+/// 1. `sync` the `Data`.
+///
+/// Events which arrive while preparing are handled on the following frame.
+pub async fn run_prepared<Data, P, Sync, Prepare, Render, S, R>(
+    parent: &web_sys::Element,
+    data: &mut Data,
+    options: RunOptions,
+    sync: Sync,
+    prepare: Prepare,
+    render: Render,
+) -> R
+where
+    S: State<Data>,
+    Sync: FnMut(&mut Data) -> Option<R>,
+    Prepare: FnMut(&Data) -> Pin<Box<dyn '_ + Future<Output = P>>>,
+    Render: FnMut(Cx<S, Web>, &Data, &P) -> Token<S>,
+{
+    let insert_before = &JsValue::NULL.into();
+    let position = (parent, insert_before);
+    run_loop(
+        position,
+        data,
+        options,
+        sync,
+        prepare,
+        || {},
+        Propagate,
+        render,
+    )
+    .await
+}
+
+/// Yields to the browser, so that it can handle input and paint before the
+/// current task continues.
+///
+/// This is useful for splitting up long computations, for example in the
+/// `prepare` callback of [`run_prepared`].
+pub async fn yield_now() {
+    let promise = js_sys::Promise::new(&mut |resolve, _| {
+        gloo_utils::window()
+            .set_timeout_with_callback(&resolve)
+            .unwrap_throw();
+    });
+    let _ = wasm_bindgen_futures::JsFuture::from(promise).await;
+}
+
+/// Waits until the browser is about to paint the next frame, using
+/// [`requestAnimationFrame`](https://developer.mozilla.org/en-US/docs/Web/API/Window/requestAnimationFrame).
+pub async fn next_frame() {
+    let promise = js_sys::Promise::new(&mut |resolve, _| {
+        gloo_utils::window()
+            .request_animation_frame(&resolve)
+            .unwrap_throw();
+    });
+    let _ = wasm_bindgen_futures::JsFuture::from(promise).await;
+}
+
 /// Spawns a component in the HTML `<body>` in a new [`wasm_bindgen_futures`]
 /// task.
 ///
 /// This is a convenience wrapper around [`run`], to run a complete application,
-/// which will never abort.
+/// which will never abort. See [`spawn_body_with_options`] to configure it.
 pub fn spawn_body<Data: 'static, Sync, Render, S>(
     mut data: Data,
     mut sync: Sync,
@@ -86,3 +454,276 @@ pub fn spawn_body<Data: 'static, Sync, Render, S>(
         .await
     });
 }
+
+/// What to do when a component panics, as configured with
+/// [`RunOptions::panic_policy`].
+///
+/// Recovery relies on [`std::panic::catch_unwind`], so on `wasm32` it only
+/// takes effect when compiled with `panic = "unwind"`. Otherwise, panics
+/// always abort.
+#[derive(Copy, Clone, PartialEq, Eq, Debug, Default)]
+pub enum PanicPolicy {
+    /// Stop the application, leaving the page as it is. This is the behavior
+    /// of [`spawn_body`].
+    #[default]
+    Abort,
+    /// Replace the application with the panic message, in a
+    /// `<pre class="ravel-panic">` element.
+    ErrorScreen,
+    /// Discard the application's DOM and build it again from the last `Data`
+    /// which was rendered without panicking. If that fails too, this falls
+    /// back to [`PanicPolicy::ErrorScreen`].
+    Remount,
+}
+
+/// Like [`spawn_body`], but with [`RunOptions`], including the
+/// [`Services`] provided to the application and its [`PanicPolicy`].
+///
+/// For [`PanicPolicy::Remount`], a clone of the `Data` is kept after every
+/// successful frame.
+pub fn spawn_body_with_options<Data, Sync, Render, S>(
+    options: RunOptions,
+    mut data: Data,
+    mut sync: Sync,
+    mut render: Render,
+) where
+    Data: 'static + Clone,
+    S: 'static + State<Data>,
+    Sync: 'static + FnMut(&mut Data),
+    Render: 'static + FnMut(Cx<S, Web>, &Data) -> Token<S>,
+{
+    let body = gloo_utils::body();
+    let start = gloo_utils::document().create_comment("ravel");
+    let end = gloo_utils::document().create_comment("/ravel");
+    body.append_with_node_2(&start, &end).unwrap_throw();
+
+    wasm_bindgen_futures::spawn_local(async move {
+        let policy = options.panic_policy;
+        let guard = Recovery {
+            policy,
+            last_good: (policy == PanicPolicy::Remount).then(|| data.clone()),
+            parent: body.clone().into(),
+            start,
+            end: end.clone(),
+        };
+
+        run_loop(
+            (&body, &end),
+            &mut data,
+            options,
+            move |data| {
+                sync(data);
+                None
+            },
+            Unprepared,
+            || {},
+            guard,
+            |cx, data, _: &()| render(cx, data),
+        )
+        .await
+    });
+}
+
+/// Recovers from panics according to a [`PanicPolicy`], for
+/// [`spawn_body_with_options`].
+///
+/// The application is built between the `start` and `end` markers, so that it
+/// can be cleared after a panic.
+struct Recovery<Data> {
+    policy: PanicPolicy,
+    last_good: Option<Data>,
+    parent: web_sys::Element,
+    start: web_sys::Comment,
+    end: web_sys::Comment,
+}
+
+impl<Data: Clone> Recover<Data, ()> for Recovery<Data> {
+    fn pass<T>(&mut self, f: impl FnOnce() -> T) -> Result<T, Panic> {
+        catch_unwind(AssertUnwindSafe(f))
+    }
+
+    fn completed(&mut self, data: &Data) {
+        if let Some(last_good) = &mut self.last_good {
+            last_good.clone_from(data);
+        }
+    }
+
+    fn recover(
+        &mut self,
+        data: &mut Data,
+        panic: Panic,
+        rebuilding: bool,
+    ) -> ControlFlow<()> {
+        clear(&self.parent, &self.start, &self.end);
+
+        match (self.policy, &self.last_good) {
+            (PanicPolicy::Abort, _) => resume_unwind(panic),
+            (PanicPolicy::Remount, Some(last_good)) if rebuilding => {
+                data.clone_from(last_good);
+                ControlFlow::Continue(())
+            }
+            _ => {
+                show_panic(&self.parent, &self.end, panic.as_ref());
+                ControlFlow::Break(())
+            }
+        }
+    }
+}
+
+fn show_panic(
+    parent: &web_sys::Element,
+    insert_before: &web_sys::Node,
+    panic: &(dyn Any + Send),
+) {
+    let message = panic_message(panic).unwrap_or("the application panicked");
+
+    let pre = gloo_utils::document().create_element("pre").unwrap_throw();
+    pre.set_class_name("ravel-panic");
+    pre.set_text_content(Some(message));
+    parent
+        .insert_before(&pre, Some(insert_before))
+        .unwrap_throw();
+}
+
+/// The message passed to [`panic!`], if it was a string.
+pub(crate) fn panic_message(panic: &(dyn Any + Send)) -> Option<&str> {
+    panic
+        .downcast_ref::<&str>()
+        .copied()
+        .or_else(|| panic.downcast_ref::<String>().map(String::as_str))
+}
+
+/// Wraps a `render` callback (as passed to [`run`] or [`spawn_body`]) with
+/// middleware.
+///
+/// The middleware `f` receives the `Data` and a `next` callback, which it must
+/// call exactly once to perform the actual render. Code before and after
+/// `next` runs around the whole build or rebuild of the component tree, which
+/// makes this suitable for cross-cutting concerns like timing, logging, or
+/// installing context for the rendered components.
+///
+/// Middleware can be stacked by nesting calls, with the outermost call running
+/// first.
+///
+/// # Panics
+///
+/// If `f` does not call `next`, or calls it more than once.
+pub fn around<Data, S, F, Render>(
+    mut f: F,
+    mut render: Render,
+) -> impl FnMut(Cx<S, Web>, &Data) -> Token<S>
+where
+    F: FnMut(&Data, &mut dyn FnMut()),
+    Render: FnMut(Cx<S, Web>, &Data) -> Token<S>,
+{
+    move |cx, data| {
+        let mut cx = Some(cx);
+        let mut token = None;
+
+        f(data, &mut || {
+            let cx = cx.take().expect("middleware called next more than once");
+            token = Some(render(cx, data));
+        });
+
+        token.expect("middleware did not call next")
+    }
+}
+
+/// Middleware which measures the duration of each render, passing it in
+/// milliseconds to `report`.
+///
+/// Time is measured with [`env::now`].
+pub fn timed<Data, S, Report, Render>(
+    mut report: Report,
+    render: Render,
+) -> impl FnMut(Cx<S, Web>, &Data) -> Token<S>
+where
+    Report: FnMut(f64),
+    Render: FnMut(Cx<S, Web>, &Data) -> Token<S>,
+{
+    around(
+        move |_, next| {
+            let start = env::now();
+            next();
+            report(env::now() - start);
+        },
+        render,
+    )
+}
+
+/// A startup metric, reported by [`first_frame_metrics`].
+#[derive(Copy, Clone, PartialEq, Debug)]
+pub enum Metric {
+    /// The first build of the component tree has finished.
+    FirstBuild {
+        /// Milliseconds from the start of navigation to the end of the build.
+        since_navigation: f64,
+        /// Milliseconds spent in the build itself.
+        duration: f64,
+    },
+    /// The first rebuild following the user's first input (a key or pointer
+    /// press) has finished.
+    FirstInput {
+        /// Milliseconds from the input to the end of the rebuild.
+        latency: f64,
+    },
+}
+
+/// Middleware which reports each [`Metric`] once, when it becomes available,
+/// for real user monitoring.
+///
+/// Times are measured with the browser's
+/// [`performance.now()`](https://developer.mozilla.org/en-US/docs/Web/API/Performance/now),
+/// rather than [`env::now`], since they are relative to the start of
+/// navigation. Input latency is measured up to the end of the next rebuild,
+/// which includes the time spent waiting for the next frame.
+pub fn first_frame_metrics<Data, S, Report, Render>(
+    mut report: Report,
+    render: Render,
+) -> impl FnMut(Cx<S, Web>, &Data) -> Token<S>
+where
+    Report: FnMut(Metric),
+    Render: FnMut(Cx<S, Web>, &Data) -> Token<S>,
+{
+    let performance = gloo_utils::window().performance();
+    let now = move || performance.as_ref().map_or(0., |p| p.now());
+
+    let mut built = false;
+    let input = Rc::new(Cell::new(None));
+    let mut listeners = Some(["keydown", "pointerdown"].map(|name| {
+        let input = input.clone();
+        gloo_events::EventListener::new_with_options(
+            &gloo_utils::window(),
+            name,
+            gloo_events::EventListenerOptions::run_in_capture_phase(),
+            move |e| {
+                if input.get().is_none() {
+                    input.set(Some(e.time_stamp()));
+                }
+            },
+        )
+    }));
+
+    around(
+        move |_, next| {
+            let start = now();
+            next();
+            let end = now();
+
+            if !built {
+                built = true;
+                report(Metric::FirstBuild {
+                    since_navigation: end,
+                    duration: end - start,
+                });
+            } else if let Some(time) = input.get() {
+                if listeners.take().is_some() {
+                    report(Metric::FirstInput {
+                        latency: end - time,
+                    });
+                }
+            }
+        },
+        render,
+    )
+}
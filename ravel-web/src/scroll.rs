@@ -0,0 +1,246 @@
+//! Scroll-linked effects, such as shrinking a header or a reading progress bar.
+//!
+//! These write directly to the DOM at most once per animation frame, rather
+//! than updating the model, so scrolling never causes a rebuild.
+
+use std::{
+    cell::{Cell, RefCell},
+    convert::Infallible,
+    rc::Rc,
+};
+
+use ravel::{Builder, MemSize, State};
+use web_sys::wasm_bindgen::{
+    closure::Closure, JsCast as _, UnwrapThrowExt as _,
+};
+
+use crate::{
+    ssr::{Ssr, SsrCx},
+    BuildCx, RebuildCx, Web,
+};
+
+/// What is scrolled to drive a scroll-linked effect.
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+pub enum ScrollSource {
+    /// The page.
+    Window,
+    /// The element the effect is attached to, which should be a scroll
+    /// container.
+    Parent,
+}
+
+/// How far a [`ScrollSource`] is scrolled.
+#[derive(Copy, Clone, PartialEq, Debug)]
+pub struct ScrollPosition {
+    /// The vertical scroll offset, in pixels.
+    pub offset: f64,
+    /// The fraction of the scrollable distance which has been scrolled, from 0
+    /// to 1. This is 1 if the content does not scroll.
+    pub progress: f64,
+}
+
+impl ScrollSource {
+    fn position(self, parent: &web_sys::Element) -> ScrollPosition {
+        let (offset, range) = match self {
+            ScrollSource::Window => {
+                let window = gloo_utils::window();
+                let root = gloo_utils::document_element();
+                let height =
+                    window.inner_height().unwrap_throw().as_f64().unwrap_or(0.);
+
+                (
+                    window.scroll_y().unwrap_throw(),
+                    f64::from(root.scroll_height()) - height,
+                )
+            }
+            ScrollSource::Parent => (
+                f64::from(parent.scroll_top()),
+                f64::from(parent.scroll_height() - parent.client_height()),
+            ),
+        };
+
+        ScrollPosition {
+            offset,
+            progress: if range > 0. {
+                (offset / range).clamp(0., 1.)
+            } else {
+                1.
+            },
+        }
+    }
+}
+
+/// A scroll-linked effect, created with [`scroll_linked`].
+pub struct ScrollLinked<F> {
+    source: ScrollSource,
+    f: F,
+}
+
+/// Calls `f` with the parent element and the [`ScrollPosition`] of `source`
+/// when the component is built, and then at most once per animation frame
+/// while it is scrolled or resized.
+///
+/// `f` should update the element directly, for example by setting a style
+/// property. See [`scroll_vars`] and [`scrolled_past`] for common cases.
+pub fn scroll_linked<F>(source: ScrollSource, f: F) -> ScrollLinked<F>
+where
+    F: 'static + Fn(&web_sys::Element, ScrollPosition),
+{
+    ScrollLinked { source, f }
+}
+
+struct Inner<F> {
+    parent: web_sys::Element,
+    source: ScrollSource,
+    f: RefCell<F>,
+    frame: Cell<Option<i32>>,
+}
+
+impl<F: Fn(&web_sys::Element, ScrollPosition)> Inner<F> {
+    fn apply(&self) {
+        (self.f.borrow())(&self.parent, self.source.position(&self.parent))
+    }
+}
+
+impl<F> Builder<Web> for ScrollLinked<F>
+where
+    F: 'static + Fn(&web_sys::Element, ScrollPosition),
+{
+    type State = ScrollLinkedState<F>;
+
+    fn build(self, cx: BuildCx) -> Self::State {
+        let inner = Rc::new(Inner {
+            parent: cx.position.parent.clone(),
+            source: self.source,
+            f: RefCell::new(self.f),
+            frame: Cell::new(None),
+        });
+        inner.apply();
+
+        let callback = Closure::<dyn FnMut()>::new({
+            let inner = inner.clone();
+            move || {
+                inner.frame.set(None);
+                inner.apply();
+            }
+        });
+
+        let schedule = {
+            let inner = inner.clone();
+            let callback: js_sys::Function =
+                callback.as_ref().clone().unchecked_into();
+            move |_: &web_sys::Event| {
+                if inner.frame.get().is_none() {
+                    let frame = gloo_utils::window()
+                        .request_animation_frame(&callback)
+                        .unwrap_throw();
+                    inner.frame.set(Some(frame));
+                }
+            }
+        };
+
+        let window = gloo_utils::window();
+        let scroll_target: &web_sys::EventTarget = match self.source {
+            ScrollSource::Window => &window,
+            ScrollSource::Parent => cx.position.parent,
+        };
+
+        let _handles = [
+            gloo_events::EventListener::new(
+                scroll_target,
+                "scroll",
+                schedule.clone(),
+            ),
+            gloo_events::EventListener::new(&window, "resize", schedule),
+        ];
+
+        ScrollLinkedState {
+            inner,
+            _handles,
+            _callback: callback,
+        }
+    }
+
+    fn rebuild(self, _: RebuildCx, state: &mut Self::State) {
+        *state.inner.f.borrow_mut() = self.f;
+        state.inner.apply();
+    }
+}
+
+impl<F> Builder<Ssr> for ScrollLinked<F> {
+    type State = ();
+
+    fn build(self, _: SsrCx) -> Self::State {}
+
+    fn rebuild(self, cx: Infallible, _: &mut Self::State) {
+        match cx {}
+    }
+}
+
+/// The state of a [`ScrollLinked`].
+pub struct ScrollLinkedState<F> {
+    inner: Rc<Inner<F>>,
+    _handles: [gloo_events::EventListener; 2],
+    _callback: Closure<dyn FnMut()>,
+}
+
+impl<F> Drop for ScrollLinkedState<F> {
+    fn drop(&mut self) {
+        // The callback is about to be freed, so it must not be called.
+        if let Some(frame) = self.inner.frame.take() {
+            let _ = gloo_utils::window().cancel_animation_frame(frame);
+        }
+    }
+}
+
+impl<F> MemSize for ScrollLinkedState<F> {
+    fn heap_size(&self) -> usize {
+        0
+    }
+}
+
+impl<F: 'static, Output> State<Output> for ScrollLinkedState<F> {
+    fn run(&mut self, _: &mut Output) {}
+}
+
+/// Sets the `--scroll-offset` (in pixels, without a unit) and
+/// `--scroll-progress` (from 0 to 1) custom properties on the parent element,
+/// so that its style can depend on the [`ScrollPosition`].
+///
+/// For example, a reading progress bar can use
+/// `transform: scaleX(var(--scroll-progress))`, and a shrinking header
+/// `height: calc(80px - min(var(--scroll-offset), 40) * 1px)`.
+///
+/// Writing the `style` attribute clears these properties, so this should come
+/// after any [`Style`](crate::attr::Style) attribute on the same element,
+/// which ensures they are set again on every rebuild.
+pub fn scroll_vars(
+    source: ScrollSource,
+) -> ScrollLinked<impl 'static + Fn(&web_sys::Element, ScrollPosition)> {
+    scroll_linked(source, |parent, position| {
+        let Some(parent) = parent.dyn_ref::<web_sys::HtmlElement>() else {
+            return;
+        };
+
+        let style = parent.style();
+        let _ =
+            style.set_property("--scroll-offset", &position.offset.to_string());
+        let _ = style
+            .set_property("--scroll-progress", &position.progress.to_string());
+    })
+}
+
+/// Sets the `data-scrolled` attribute on the parent element while `source` is
+/// scrolled more than `threshold` pixels, for example to switch a header to a
+/// compact style with a `[data-scrolled]` selector.
+pub fn scrolled_past(
+    source: ScrollSource,
+    threshold: f64,
+) -> ScrollLinked<impl 'static + Fn(&web_sys::Element, ScrollPosition)> {
+    scroll_linked(source, move |parent, position| {
+        let _ = parent.toggle_attribute_with_force(
+            "data-scrolled",
+            position.offset > threshold,
+        );
+    })
+}
@@ -0,0 +1,144 @@
+//! Application-wide services, such as API clients and configuration.
+//!
+//! A [`Services`] registry is provided to an application with
+//! [`RunOptions::services`](crate::run::RunOptions::services), or to a subtree
+//! with [`provide_services`]. Components and event handlers inside can
+//! then look up services by type with [`service`].
+//!
+//! To substitute fakes in tests, register services as trait objects (for
+//! example, `Box<dyn HttpClient>`) and look them up by the same type.
+
+use std::{
+    any::{Any, TypeId},
+    cell::RefCell,
+    collections::HashMap,
+    rc::Rc,
+};
+
+use ravel::{Builder, MemSize, State};
+
+use crate::{BuildCx, RebuildCx, ViewMarker, Web};
+
+/// A registry of services, keyed by type.
+///
+/// This is cheap to clone.
+#[derive(Clone, Default)]
+pub struct Services(Rc<HashMap<TypeId, Rc<dyn Any>>>);
+
+impl Services {
+    /// An empty registry.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Registers a service, replacing any existing service of the same type.
+    pub fn insert<T: 'static>(&mut self, service: T) {
+        Rc::make_mut(&mut self.0).insert(TypeId::of::<T>(), Rc::new(service));
+    }
+
+    /// Registers a service, replacing any existing service of the same type.
+    pub fn with<T: 'static>(mut self, service: T) -> Self {
+        self.insert(service);
+        self
+    }
+
+    /// Looks up a service by type.
+    pub fn get<T: 'static>(&self) -> Option<Rc<T>> {
+        self.0.get(&TypeId::of::<T>())?.clone().downcast().ok()
+    }
+
+    /// Runs `f` with these services provided to any [`service`] calls.
+    pub fn scope<R>(&self, f: impl FnOnce() -> R) -> R {
+        struct Pop;
+
+        impl Drop for Pop {
+            fn drop(&mut self) {
+                STACK.with_borrow_mut(|stack| stack.pop());
+            }
+        }
+
+        STACK.with_borrow_mut(|stack| stack.push(self.clone()));
+        let _pop = Pop;
+        f()
+    }
+}
+
+thread_local! {
+    static STACK: RefCell<Vec<Services>> = const { RefCell::new(Vec::new()) };
+}
+
+/// Looks up a service by type in the innermost provided [`Services`].
+///
+/// This is available while building or rebuilding a component, and while
+/// running its event handlers, inside [`provide_services`] or
+/// [`Services::scope`].
+pub fn try_service<T: 'static>() -> Option<Rc<T>> {
+    STACK.with_borrow(|stack| stack.last()?.get())
+}
+
+/// Looks up a service by type in the innermost provided [`Services`].
+///
+/// # Panics
+///
+/// If no service of type `T` is provided.
+pub fn service<T: 'static>() -> Rc<T> {
+    try_service().unwrap_or_else(|| {
+        panic!("service not provided: {}", std::any::type_name::<T>())
+    })
+}
+
+/// A [`Builder`] created from [`provide_services`].
+pub struct ProvideServices<B> {
+    services: Services,
+    body: B,
+}
+
+impl<B: Builder<Web>> Builder<Web> for ProvideServices<B> {
+    type State = ServicesState<B::State>;
+
+    fn build(self, cx: BuildCx) -> Self::State {
+        let body = self.body;
+        let inner = self.services.scope(|| body.build(cx));
+
+        ServicesState {
+            services: self.services,
+            inner,
+        }
+    }
+
+    fn rebuild(self, cx: RebuildCx, state: &mut Self::State) {
+        let body = self.body;
+        state.services = self.services;
+        state.services.scope(|| body.rebuild(cx, &mut state.inner))
+    }
+}
+
+/// The state of a [`ProvideServices`].
+pub struct ServicesState<S> {
+    services: Services,
+    inner: S,
+}
+
+impl<S: State<Output>, Output> State<Output> for ServicesState<S> {
+    fn run(&mut self, output: &mut Output) {
+        let inner = &mut self.inner;
+        self.services.scope(|| inner.run(output))
+    }
+}
+
+impl<S: MemSize> MemSize for ServicesState<S> {
+    fn heap_size(&self) -> usize {
+        self.inner.heap_size()
+    }
+}
+
+impl<S: ViewMarker> ViewMarker for ServicesState<S> {}
+
+/// Provides a [`Services`] registry to all components in `body`, including
+/// their event handlers.
+pub fn provide_services<B: Builder<Web>>(
+    services: Services,
+    body: B,
+) -> ProvideServices<B> {
+    ProvideServices { services, body }
+}
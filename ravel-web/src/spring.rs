@@ -0,0 +1,213 @@
+//! Values which follow the model with spring physics, for smooth motion.
+//!
+//! An [`animated`] value moves towards its target each animation frame, as if
+//! pulled by a spring, and is passed to a build callback like [`with`]'s, so it
+//! can be bound to style properties:
+//!
+//! ```ignore
+//! let target = if model.open { 240. } else { 0. };
+//! animated(target, SpringConfig::default(), |cx, height| {
+//!     cx.build(el::div((style::height(px(height)), ...)))
+//! })
+//! ```
+//!
+//! While the value is moving, the event loop rebuilds once per frame. Once it
+//! has settled, frames stop until the target changes again.
+
+use std::{cell::Cell, marker::PhantomData, rc::Rc};
+
+use ravel::{with, Builder, Cx, MemSize, State, Token};
+use web_sys::wasm_bindgen::{
+    closure::Closure, JsCast as _, UnwrapThrowExt as _,
+};
+
+use crate::{env, BuildCx, RebuildCx, ViewMarker, Web};
+
+/// The longest time step simulated at once, in seconds. Longer gaps between
+/// frames (for example, while the page is hidden) are simulated as this, so
+/// that the spring doesn't jump.
+const MAX_FRAME: f64 = 0.064;
+
+/// The time step of the simulation, in seconds.
+const STEP: f64 = 0.001;
+
+/// The physical parameters of a spring.
+#[derive(Copy, Clone, PartialEq, Debug)]
+pub struct SpringConfig {
+    /// How strongly the spring pulls towards its target.
+    pub stiffness: f64,
+    /// How strongly motion is resisted. Lower values overshoot and oscillate
+    /// more.
+    pub damping: f64,
+    /// The mass on the spring. Higher values are slower to start and stop.
+    pub mass: f64,
+    /// The distance from the target (and speed, per second) below which the
+    /// spring is considered to have settled.
+    pub precision: f64,
+}
+
+impl SpringConfig {
+    /// A slow spring, which settles without overshooting.
+    pub const GENTLE: Self = Self::new(120., 14.);
+
+    /// A spring which overshoots its target and oscillates noticeably.
+    pub const WOBBLY: Self = Self::new(180., 12.);
+
+    /// A fast spring, which settles with little overshoot.
+    pub const STIFF: Self = Self::new(210., 20.);
+
+    /// A spring with the given `stiffness` and `damping`, a mass of `1.` and a
+    /// precision of `0.01`.
+    pub const fn new(stiffness: f64, damping: f64) -> Self {
+        Self {
+            stiffness,
+            damping,
+            mass: 1.,
+            precision: 0.01,
+        }
+    }
+}
+
+impl Default for SpringConfig {
+    fn default() -> Self {
+        Self::new(170., 26.)
+    }
+}
+
+/// A [`Builder`] created from [`animated`].
+pub struct Animated<F, S> {
+    target: f64,
+    config: SpringConfig,
+    f: F,
+    phantom: PhantomData<S>,
+}
+
+/// Creates a [`Builder`] with access to a value which follows `target` with
+/// the motion of a spring.
+///
+/// The value starts at `target` when the component is built, and moves
+/// towards each new target it is rebuilt with, starting from its current
+/// position and velocity.
+///
+/// Like [`with`], `f` must call [`Cx::build`] to return a [`Token`].
+pub fn animated<F, S>(target: f64, config: SpringConfig, f: F) -> Animated<F, S>
+where
+    F: FnOnce(Cx<S, Web>, f64) -> Token<S>,
+{
+    Animated {
+        target,
+        config,
+        f,
+        phantom: PhantomData,
+    }
+}
+
+impl<F, S> Builder<Web> for Animated<F, S>
+where
+    F: FnOnce(Cx<S, Web>, f64) -> Token<S>,
+{
+    type State = AnimatedState<S>;
+
+    fn build(self, cx: BuildCx) -> Self::State {
+        let frame = Rc::new(Cell::new(None));
+        let callback = Closure::<dyn FnMut()>::new({
+            let frame = frame.clone();
+            let waker = cx.position.waker.clone();
+            move || {
+                frame.set(None);
+                waker.wake();
+            }
+        });
+
+        let inner = with(|cx| (self.f)(cx, self.target)).build(cx);
+
+        AnimatedState {
+            value: self.target,
+            velocity: 0.,
+            last: None,
+            frame,
+            callback,
+            inner,
+        }
+    }
+
+    fn rebuild(self, cx: RebuildCx, state: &mut Self::State) {
+        state.step(self.target, &self.config);
+        with(|cx| (self.f)(cx, state.value)).rebuild(cx, &mut state.inner)
+    }
+}
+
+/// The state of an [`Animated`].
+pub struct AnimatedState<S> {
+    value: f64,
+    /// In units per second.
+    velocity: f64,
+    /// When the value was last updated, in milliseconds, if it is moving.
+    last: Option<f64>,
+    frame: Rc<Cell<Option<i32>>>,
+    /// Wakes the event loop on the next frame.
+    callback: Closure<dyn FnMut()>,
+    inner: S,
+}
+
+impl<S> AnimatedState<S> {
+    /// Moves the value towards `target` by the time since it was last moved,
+    /// and requests another frame if it hasn't settled.
+    fn step(&mut self, target: f64, config: &SpringConfig) {
+        let now = env::now();
+        let elapsed = match self.last {
+            Some(last) => ((now - last) / 1000.).clamp(0., MAX_FRAME),
+            None => 0.,
+        };
+
+        let mut t = 0.;
+        while t < elapsed {
+            let dt = STEP.min(elapsed - t);
+            let force = -config.stiffness * (self.value - target)
+                - config.damping * self.velocity;
+            self.velocity += force / config.mass * dt;
+            self.value += self.velocity * dt;
+            t += dt;
+        }
+
+        if (self.value - target).abs() < config.precision
+            && self.velocity.abs() < config.precision
+        {
+            self.value = target;
+            self.velocity = 0.;
+            self.last = None;
+            return;
+        }
+
+        self.last = Some(now);
+        if self.frame.get().is_none() {
+            let frame = gloo_utils::window()
+                .request_animation_frame(self.callback.as_ref().unchecked_ref())
+                .unwrap_throw();
+            self.frame.set(Some(frame));
+        }
+    }
+}
+
+impl<S> Drop for AnimatedState<S> {
+    fn drop(&mut self) {
+        // The callback is about to be freed, so it must not be called.
+        if let Some(frame) = self.frame.take() {
+            let _ = gloo_utils::window().cancel_animation_frame(frame);
+        }
+    }
+}
+
+impl<S: State<Output>, Output> State<Output> for AnimatedState<S> {
+    fn run(&mut self, output: &mut Output) {
+        self.inner.run(output)
+    }
+}
+
+impl<S: MemSize> MemSize for AnimatedState<S> {
+    fn heap_size(&self) -> usize {
+        self.inner.heap_size()
+    }
+}
+
+impl<S: ViewMarker> ViewMarker for AnimatedState<S> {}
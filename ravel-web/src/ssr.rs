@@ -0,0 +1,331 @@
+//! Server-side rendering to an HTML string.
+//!
+//! Element, attribute, text and [`Option`] builders also implement
+//! [`Builder<Ssr>`], so the same component tree can be rendered on the server
+//! with [`render_to_string`], for example to serve a first paint or content
+//! for crawlers. There is no hydration: the output is static markup, and a
+//! client must still build the component with [`Web`](crate::Web) from
+//! scratch, replacing it.
+//!
+//! The output contains the comment markers of optional views, like the DOM
+//! built by [`Web`](crate::Web). Adjacent text nodes are also separated by an
+//! empty comment, which [`Web`](crate::Web) doesn't need, so that the parser
+//! doesn't merge them. The contents of `<script>` and `<style>` are written
+//! without entity escaping, and markers are left out of those and of
+//! `<textarea>` and `<title>`, where they would be read as text.
+//!
+//! Event handlers render nothing. Type-erased views ([`any`](crate::any())) and
+//! the [`collections`](crate::collections) don't support server rendering yet.
+//! Server rendering is a one-shot build, so [`Ssr`] components are never
+//! rebuilt.
+
+use std::{borrow::Cow, cell::RefCell, convert::Infallible};
+
+use ravel::{Builder, CxRep};
+
+use crate::{attr::types::AttrValue, id::fresh_ids};
+
+/// A dummy type representing the server-side rendering backend.
+pub struct Ssr;
+
+impl CxRep for Ssr {
+    type BuildCx<'a> = SsrCx<'a>;
+    type RebuildCx<'a> = Infallible;
+}
+
+/// The necessary context for building [`Ssr`] components.
+#[derive(Copy, Clone)]
+pub struct SsrCx<'cx> {
+    parent: &'cx RefCell<Element>,
+}
+
+/// An element which is still being rendered.
+///
+/// Attributes may be set at any point while building the body, so they are
+/// collected separately from the rendered children.
+#[derive(Default)]
+struct Element {
+    attrs: Vec<(Cow<'static, str>, String)>,
+    children: String,
+    /// How the children are parsed.
+    content: Content,
+    /// Whether the last child rendered was a text node, which the next one
+    /// must be separated from.
+    after_text: bool,
+}
+
+/// [Void elements](https://html.spec.whatwg.org/multipage/syntax.html#void-elements),
+/// which have no closing tag.
+const VOID_ELEMENTS: &[&str] = &[
+    "area", "base", "br", "col", "embed", "hr", "img", "input", "link", "meta",
+    "source", "track", "wbr",
+];
+
+/// How the contents of an element are parsed, which decides how text is
+/// escaped and whether comments can be written.
+#[derive(Copy, Clone, Default, PartialEq, Eq)]
+enum Content {
+    /// Regular HTML.
+    #[default]
+    Normal,
+    /// A [raw text element](https://html.spec.whatwg.org/multipage/syntax.html#raw-text-elements)
+    /// (`<script>` or `<style>`), whose contents are only ended by its closing
+    /// tag.
+    Raw(&'static str),
+    /// An [escapable raw text element](https://html.spec.whatwg.org/multipage/syntax.html#escapable-raw-text-elements)
+    /// (`<textarea>` or `<title>`), which only decodes entities.
+    EscapableRaw,
+}
+
+impl Content {
+    fn of(name: &'static str) -> Self {
+        match name {
+            "script" | "style" => Content::Raw(name),
+            "textarea" | "title" => Content::EscapableRaw,
+            _ => Content::Normal,
+        }
+    }
+}
+
+impl SsrCx<'_> {
+    /// Renders an element with the given body.
+    pub(crate) fn element<Body: Builder<Ssr>>(
+        self,
+        name: &'static str,
+        body: Body,
+    ) -> Body::State {
+        let el = RefCell::new(Element {
+            content: Content::of(name),
+            ..Element::default()
+        });
+        let state = body.build(SsrCx { parent: &el });
+        let Element {
+            attrs, children, ..
+        } = el.into_inner();
+
+        let mut parent = self.parent.borrow_mut();
+        parent.after_text = false;
+        let out = &mut parent.children;
+
+        out.push('<');
+        out.push_str(name);
+        for (name, value) in attrs {
+            out.push(' ');
+            out.push_str(&name);
+            if !value.is_empty() {
+                out.push_str("=\"");
+                escape(out, &value, true);
+                out.push('"');
+            }
+        }
+        out.push('>');
+
+        if !VOID_ELEMENTS.contains(&name) {
+            out.push_str(&children);
+            out.push_str("</");
+            out.push_str(name);
+            out.push('>');
+        }
+
+        state
+    }
+
+    /// Sets or removes an attribute on the parent element.
+    pub(crate) fn attr(
+        self,
+        name: impl Into<Cow<'static, str>>,
+        value: impl AttrValue,
+    ) {
+        let name = name.into();
+        let attrs = &mut self.parent.borrow_mut().attrs;
+        let existing = attrs.iter().position(|(n, _)| *n == name);
+
+        value.with_str(|value| match (existing, value) {
+            (Some(i), Some(value)) => attrs[i].1 = value.to_string(),
+            (Some(i), None) => {
+                attrs.remove(i);
+            }
+            (None, Some(value)) => attrs.push((name, value.to_string())),
+            (None, None) => {}
+        })
+    }
+
+    /// Appends a declaration to the parent element's `style` attribute.
+    pub(crate) fn style(self, name: &str, value: &str) {
+        let attrs = &mut self.parent.borrow_mut().attrs;
+        let declaration = format!("{name}: {value};");
+
+        match attrs.iter_mut().find(|(n, _)| *n == "style") {
+            Some((_, style)) => {
+                if !style.is_empty() {
+                    style.push(' ');
+                }
+                style.push_str(&declaration)
+            }
+            None => attrs.push(("style".into(), declaration)),
+        }
+    }
+
+    /// Renders a text node.
+    ///
+    /// Adjacent text nodes are separated by an empty comment, since the parser
+    /// would otherwise merge them. The contents of raw text elements aren't
+    /// entity escaped, and are joined without separators.
+    ///
+    /// # Panics
+    ///
+    /// If the text of a `<style>` contains `</style`, in any case, which would
+    /// end the element early. Inside a `<script>`, `</` is written as `<\/`
+    /// instead, which means the same in JavaScript strings and regular
+    /// expressions.
+    pub(crate) fn text(self, data: &str) {
+        let parent = &mut *self.parent.borrow_mut();
+
+        match parent.content {
+            Content::Raw("script") => {
+                parent.children.push_str(&data.replace("</", "<\\/"));
+            }
+            Content::Raw(name) => {
+                let closing = format!("</{name}");
+                assert!(
+                    !data.to_ascii_lowercase().contains(&closing),
+                    "the text of a <{name}> must not contain `{closing}`",
+                );
+                parent.children.push_str(data);
+            }
+            Content::EscapableRaw => {
+                escape(&mut parent.children, data, false);
+            }
+            Content::Normal => {
+                if parent.after_text {
+                    parent.children.push_str("<!---->");
+                }
+                escape(&mut parent.children, data, false);
+                parent.after_text = true;
+            }
+        }
+    }
+
+    /// Renders a comment node.
+    ///
+    /// Comments are dropped inside raw text and escapable raw text elements,
+    /// where they would be parsed as text.
+    pub(crate) fn comment(self, data: &str) {
+        let parent = &mut *self.parent.borrow_mut();
+
+        if parent.content != Content::Normal {
+            return;
+        }
+
+        let out = &mut parent.children;
+        out.push_str("<!--");
+        out.push_str(data);
+        out.push_str("-->");
+        parent.after_text = false;
+    }
+}
+
+fn escape(out: &mut String, s: &str, attr: bool) {
+    for c in s.chars() {
+        match c {
+            '&' => out.push_str("&amp;"),
+            '<' if !attr => out.push_str("&lt;"),
+            '>' if !attr => out.push_str("&gt;"),
+            '"' if attr => out.push_str("&quot;"),
+            '\u{a0}' => out.push_str("&nbsp;"),
+            c => out.push(c),
+        }
+    }
+}
+
+/// Renders a component to an HTML string.
+pub fn render_to_string<B: Builder<Ssr>>(builder: B) -> String {
+    let root = RefCell::new(Element::default());
+    fresh_ids(|| builder.build(SsrCx { parent: &root }));
+    root.into_inner().children
+}
+
+#[cfg(test)]
+mod tests {
+    use super::render_to_string;
+    use crate::{
+        attr::{Class, Title, Type},
+        el::{self, types::ElKind},
+    };
+
+    struct Style;
+
+    impl ElKind for Style {
+        const NAME: &'static str = "style";
+    }
+
+    #[test]
+    fn escapes_text_and_attributes() {
+        assert_eq!(
+            render_to_string(el::p((Title("\"a\" & b"), "<b> & c"))),
+            r#"<p title="&quot;a&quot; &amp; b">&lt;b&gt; &amp; c</p>"#,
+        );
+    }
+
+    #[test]
+    fn void_elements_have_no_closing_tag() {
+        assert_eq!(
+            render_to_string(el::div((
+                Class("a"),
+                el::input(Type("text")),
+                el::br(()),
+            ))),
+            r#"<div class="a"><input type="text"><br></div>"#,
+        );
+    }
+
+    #[test]
+    fn separates_adjacent_text() {
+        assert_eq!(
+            render_to_string(el::p(("a", "b", el::br(()), "c", "", "d"))),
+            "<p>a<!---->b<br>c<!----><!---->d</p>",
+        );
+    }
+
+    #[test]
+    fn raw_text_is_not_escaped() {
+        assert_eq!(
+            render_to_string((
+                el::script(("if (a < b && c) {}", "f();")),
+                el::el(Style, "a > b { color: red }"),
+            )),
+            "<script>if (a < b && c) {}f();</script>\
+             <style>a > b { color: red }</style>",
+        );
+    }
+
+    #[test]
+    fn script_cannot_be_closed_by_text() {
+        assert_eq!(
+            render_to_string(el::script("</script><b>")),
+            "<script><\\/script><b></script>",
+        );
+    }
+
+    #[test]
+    #[should_panic]
+    fn style_cannot_be_closed_by_text() {
+        render_to_string(el::el(Style, "</STYLE><b>"));
+    }
+
+    #[test]
+    fn escapable_raw_text_has_no_separators() {
+        assert_eq!(
+            render_to_string(el::textarea(("a", "<b>", Some("c")))),
+            "<textarea>a&lt;b&gt;c</textarea>",
+        );
+    }
+
+    #[test]
+    fn markers_separate_text() {
+        assert_eq!(
+            render_to_string(el::p(("a", Some("b"), None::<&str>, "c"))),
+            "<p>a<!--{-->b<!--}--><!--{--><!--}-->c</p>",
+        );
+    }
+}
@@ -0,0 +1,94 @@
+//! Counters of the DOM operations made by components.
+//!
+//! These make the cost of a component something a test can assert on, for
+//! example that changing one item of a list only writes one text node:
+//!
+//! ```ignore
+//! stats::take();
+//! backend.rebuild(view(&model), &mut state);
+//! assert_eq!(stats::take().text_writes, 1);
+//! ```
+//!
+//! Operations are only counted when the `stats` feature is enabled.
+//! Otherwise, the counters always read zero, and counting is free.
+
+/// The number of each kind of DOM operation made since the counters were last
+/// [`take`]n.
+#[derive(Copy, Clone, PartialEq, Eq, Hash, Default, Debug)]
+pub struct Stats {
+    /// Elements, text nodes and markers created.
+    pub created_nodes: u32,
+    /// Nodes removed from their parent. Their descendants, which are removed
+    /// along with them, aren't counted.
+    pub removed_nodes: u32,
+    /// Attributes, classes and style properties set or removed.
+    pub attribute_writes: u32,
+    /// Changes to the data of existing text nodes.
+    pub text_writes: u32,
+}
+
+/// Returns the counters, without resetting them.
+pub fn get() -> Stats {
+    imp::get()
+}
+
+/// Returns the counters, and resets them to zero.
+pub fn take() -> Stats {
+    imp::take()
+}
+
+pub(crate) fn created() {
+    imp::record(|stats| stats.created_nodes += 1)
+}
+
+pub(crate) fn removed() {
+    imp::record(|stats| stats.removed_nodes += 1)
+}
+
+pub(crate) fn attribute_written() {
+    imp::record(|stats| stats.attribute_writes += 1)
+}
+
+pub(crate) fn text_written() {
+    imp::record(|stats| stats.text_writes += 1)
+}
+
+#[cfg(feature = "stats")]
+mod imp {
+    use std::cell::Cell;
+
+    use super::Stats;
+
+    thread_local! {
+        static STATS: Cell<Stats> = Cell::default();
+    }
+
+    pub fn get() -> Stats {
+        STATS.get()
+    }
+
+    pub fn take() -> Stats {
+        STATS.take()
+    }
+
+    pub fn record(f: impl FnOnce(&mut Stats)) {
+        let mut stats = STATS.get();
+        f(&mut stats);
+        STATS.set(stats);
+    }
+}
+
+#[cfg(not(feature = "stats"))]
+mod imp {
+    use super::Stats;
+
+    pub fn get() -> Stats {
+        Stats::default()
+    }
+
+    pub fn take() -> Stats {
+        Stats::default()
+    }
+
+    pub fn record(_: impl FnOnce(&mut Stats)) {}
+}
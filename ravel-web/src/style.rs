@@ -0,0 +1,577 @@
+//! Typed inline style properties.
+//!
+//! Unlike the [`Style`](crate::attr::Style) attribute, which is rewritten as a
+//! whole whenever any part of it changes, each property here is set
+//! individually through the element's
+//! [`CSSStyleDeclaration`](https://developer.mozilla.org/en-US/docs/Web/API/CSSStyleDeclaration),
+//! and only when its own value changes. Properties are combined with tuples,
+//! and an [`Option`] value removes the property while it is [`None`]:
+//!
+//! ```ignore
+//! el::div((
+//!     style::display(Display::Flex),
+//!     style::width(px(100)),
+//!     style::opacity(model.faded.then_some(0.5)),
+//! ))
+//! ```
+//!
+//! For apps which support right-to-left languages, the logical properties
+//! (like [`margin_inline_start`] rather than `margin-left`) apply to the side
+//! matching the [`direction`](crate::direction) of the text.
+//!
+//! Writing the `style` attribute clears these properties, so they shouldn't be
+//! combined with a [`Style`](crate::attr::Style) attribute on the same
+//! element. Properties are only set on HTML elements.
+
+use std::{convert::Infallible, fmt::Arguments};
+
+use ravel::{Builder, MemSize, State};
+use web_sys::wasm_bindgen::{JsCast as _, UnwrapThrowExt as _};
+
+use crate::{
+    ssr::{Ssr, SsrCx},
+    stats,
+    text::update_string,
+    BuildCx, RebuildCx, Web,
+};
+
+pub use crate::units::*;
+
+/// Trait for values which can be assigned to a style property.
+pub trait StyleValue: 'static + PartialEq {
+    /// Calls `f` with the value as CSS text, or [`None`] to remove the
+    /// property.
+    fn with_css<F, R>(&self, f: F) -> R
+    where
+        F: FnOnce(Option<&str>) -> R;
+}
+
+/// Marks the values accepted by properties of the given kind.
+///
+/// The kind of a keyword property (like [`display`]) is its keyword type (like
+/// [`Display`]), and other properties use one of the uninhabited kinds
+/// [`LengthKind`], [`ColorKind`], [`DurationKind`], [`NumberKind`] or
+/// [`IntegerKind`].
+pub trait ValueFor<Kind>: StyleValue {}
+
+impl<V: StyleValue> StyleValue for Option<V> {
+    fn with_css<F, R>(&self, f: F) -> R
+    where
+        F: FnOnce(Option<&str>) -> R,
+    {
+        match self {
+            Some(v) => v.with_css(f),
+            None => f(None),
+        }
+    }
+}
+
+impl<Kind, V: ValueFor<Kind>> ValueFor<Kind> for Option<V> {}
+
+impl StyleValue for &'static str {
+    fn with_css<F, R>(&self, f: F) -> R
+    where
+        F: FnOnce(Option<&str>) -> R,
+    {
+        f(Some(self))
+    }
+}
+
+impl StyleValue for String {
+    fn with_css<F, R>(&self, f: F) -> R
+    where
+        F: FnOnce(Option<&str>) -> R,
+    {
+        f(Some(self))
+    }
+}
+
+macro_rules! make_style_value_to_string {
+    ($t:ty, $kind:ty) => {
+        impl StyleValue for $t {
+            fn with_css<F, R>(&self, f: F) -> R
+            where
+                F: FnOnce(Option<&str>) -> R,
+            {
+                f(Some(&self.to_string()))
+            }
+        }
+
+        impl ValueFor<$kind> for $t {}
+    };
+}
+
+/// The kind of properties which take a length, like [`width`].
+pub enum LengthKind {}
+
+/// The kind of properties which take a color, like [`color`].
+///
+/// Besides [`Color`], colors can be given as CSS text, for example
+/// `"currentcolor"` or `"var(--accent)"`.
+pub enum ColorKind {}
+
+/// The kind of properties which take a duration, like [`transition_duration`].
+pub enum DurationKind {}
+
+/// The kind of properties which take a number, like [`opacity`].
+pub enum NumberKind {}
+
+/// The kind of properties which take an integer, like [`z_index`].
+pub enum IntegerKind {}
+
+impl ValueFor<ColorKind> for &'static str {}
+impl ValueFor<ColorKind> for String {}
+
+make_style_value_to_string!(f64, NumberKind);
+make_style_value_to_string!(i32, IntegerKind);
+
+make_style_value_to_string!(Px, LengthKind);
+make_style_value_to_string!(Em, LengthKind);
+make_style_value_to_string!(Rem, LengthKind);
+make_style_value_to_string!(Percent, LengthKind);
+make_style_value_to_string!(Color, ColorKind);
+make_style_value_to_string!(Duration, DurationKind);
+
+impl<T: 'static> StyleValue for Var<T> {
+    fn with_css<F, R>(&self, f: F) -> R
+    where
+        F: FnOnce(Option<&str>) -> R,
+    {
+        f(Some(&self.to_string()))
+    }
+}
+
+impl<Kind, T: ValueFor<Kind>> ValueFor<Kind> for Var<T> {}
+
+macro_rules! keyword {
+    (
+        $(#[$attr:meta])*
+        $name:ident { $($(#[$var_attr:meta])* $var:ident => $css:literal,)* }
+    ) => {
+        $(#[$attr])*
+        #[derive(Copy, Clone, PartialEq, Eq, Hash, Debug)]
+        pub enum $name {
+            $($(#[$var_attr])* $var,)*
+        }
+
+        impl StyleValue for $name {
+            fn with_css<F, R>(&self, f: F) -> R
+            where
+                F: FnOnce(Option<&str>) -> R,
+            {
+                f(Some(match self {
+                    $($name::$var => $css,)*
+                }))
+            }
+        }
+
+        impl ValueFor<$name> for $name {}
+
+        impl MemSize for $name {
+            fn heap_size(&self) -> usize {
+                0
+            }
+        }
+    };
+}
+
+/// The `auto` keyword, which is accepted by most length properties.
+#[derive(Copy, Clone, PartialEq, Eq, Hash, Debug)]
+pub struct Auto;
+
+impl StyleValue for Auto {
+    fn with_css<F, R>(&self, f: F) -> R
+    where
+        F: FnOnce(Option<&str>) -> R,
+    {
+        f(Some("auto"))
+    }
+}
+
+impl MemSize for Auto {
+    fn heap_size(&self) -> usize {
+        0
+    }
+}
+
+impl ValueFor<LengthKind> for Auto {}
+
+keyword!(
+    /// Values of the [`display`] property.
+    Display {
+        None => "none",
+        Block => "block",
+        Inline => "inline",
+        InlineBlock => "inline-block",
+        Flex => "flex",
+        InlineFlex => "inline-flex",
+        Grid => "grid",
+        InlineGrid => "inline-grid",
+        Contents => "contents",
+    }
+);
+
+keyword!(
+    /// Values of the [`position`] property.
+    Position {
+        Static => "static",
+        Relative => "relative",
+        Absolute => "absolute",
+        Fixed => "fixed",
+        Sticky => "sticky",
+    }
+);
+
+keyword!(
+    /// Values of the [`overflow`] property.
+    Overflow {
+        Visible => "visible",
+        Hidden => "hidden",
+        Clip => "clip",
+        Scroll => "scroll",
+        Auto => "auto",
+    }
+);
+
+keyword!(
+    /// Values of the [`visibility`] property.
+    Visibility {
+        Visible => "visible",
+        Hidden => "hidden",
+        Collapse => "collapse",
+    }
+);
+
+keyword!(
+    /// Values of the [`flex_direction`] property.
+    FlexDirection {
+        Row => "row",
+        RowReverse => "row-reverse",
+        Column => "column",
+        ColumnReverse => "column-reverse",
+    }
+);
+
+keyword!(
+    /// Values of the [`align_items`] property.
+    AlignItems {
+        Normal => "normal",
+        Stretch => "stretch",
+        Center => "center",
+        Start => "start",
+        End => "end",
+        Baseline => "baseline",
+    }
+);
+
+keyword!(
+    /// Values of the [`justify_content`] property.
+    JustifyContent {
+        Normal => "normal",
+        Center => "center",
+        Start => "start",
+        End => "end",
+        SpaceBetween => "space-between",
+        SpaceAround => "space-around",
+        SpaceEvenly => "space-evenly",
+    }
+);
+
+keyword!(
+    /// Values of the [`text_align`] property.
+    ///
+    /// [`TextAlign::Start`] and [`TextAlign::End`] follow the text
+    /// [`direction`](crate::direction), unlike [`TextAlign::Left`] and
+    /// [`TextAlign::Right`].
+    TextAlign {
+        Start => "start",
+        End => "end",
+        Left => "left",
+        Right => "right",
+        Center => "center",
+        Justify => "justify",
+    }
+);
+
+/// A style property, created with [`property`] or one of the typed property
+/// functions.
+#[derive(Copy, Clone, Debug)]
+pub struct Property<V> {
+    name: &'static str,
+    value: V,
+}
+
+/// Sets an arbitrary style property on the parent element, including custom
+/// properties like `--accent`.
+pub fn property<V: StyleValue>(name: &'static str, value: V) -> Property<V> {
+    Property { name, value }
+}
+
+macro_rules! properties {
+    ($($kind:ty { $($name:literal => $f:ident,)* })*) => {
+        $($(
+            #[doc = concat!("Sets the `", $name, "` property.")]
+            pub fn $f<V: ValueFor<$kind>>(value: V) -> Property<V> {
+                property($name, value)
+            }
+        )*)*
+    };
+}
+
+properties! {
+    Display { "display" => display, }
+    Position { "position" => position, }
+    Overflow {
+        "overflow" => overflow,
+        "overflow-x" => overflow_x,
+        "overflow-y" => overflow_y,
+    }
+    Visibility { "visibility" => visibility, }
+    FlexDirection { "flex-direction" => flex_direction, }
+    AlignItems { "align-items" => align_items, }
+    JustifyContent { "justify-content" => justify_content, }
+    TextAlign { "text-align" => text_align, }
+    LengthKind {
+        "width" => width,
+        "height" => height,
+        "min-width" => min_width,
+        "min-height" => min_height,
+        "max-width" => max_width,
+        "max-height" => max_height,
+        "top" => top,
+        "right" => right,
+        "bottom" => bottom,
+        "left" => left,
+        "margin" => margin,
+        "padding" => padding,
+        "inset-inline-start" => inset_inline_start,
+        "inset-inline-end" => inset_inline_end,
+        "inset-block-start" => inset_block_start,
+        "inset-block-end" => inset_block_end,
+        "margin-inline" => margin_inline,
+        "margin-inline-start" => margin_inline_start,
+        "margin-inline-end" => margin_inline_end,
+        "margin-block" => margin_block,
+        "margin-block-start" => margin_block_start,
+        "margin-block-end" => margin_block_end,
+        "padding-inline" => padding_inline,
+        "padding-inline-start" => padding_inline_start,
+        "padding-inline-end" => padding_inline_end,
+        "padding-block" => padding_block,
+        "padding-block-start" => padding_block_start,
+        "padding-block-end" => padding_block_end,
+        "gap" => gap,
+        "font-size" => font_size,
+    }
+    ColorKind {
+        "color" => color,
+        "background-color" => background_color,
+        "border-color" => border_color,
+    }
+    DurationKind {
+        "transition-duration" => transition_duration,
+        "transition-delay" => transition_delay,
+        "animation-duration" => animation_duration,
+        "animation-delay" => animation_delay,
+    }
+    NumberKind {
+        "opacity" => opacity,
+        "flex-grow" => flex_grow,
+        "flex-shrink" => flex_shrink,
+    }
+    IntegerKind { "z-index" => z_index, }
+}
+
+fn declaration(
+    parent: &web_sys::Element,
+) -> Option<web_sys::CssStyleDeclaration> {
+    Some(parent.dyn_ref::<web_sys::HtmlElement>()?.style())
+}
+
+fn write(parent: &web_sys::Element, name: &str, value: &impl StyleValue) {
+    let Some(style) = declaration(parent) else {
+        return;
+    };
+
+    stats::attribute_written();
+    value.with_css(|value| match value {
+        Some(value) => style.set_property(name, value).unwrap_throw(),
+        None => {
+            style.remove_property(name).unwrap_throw();
+        }
+    })
+}
+
+impl<V: StyleValue> Builder<Web> for Property<V> {
+    type State = PropertyState<V>;
+
+    fn build(self, cx: BuildCx) -> Self::State {
+        write(cx.position.parent, self.name, &self.value);
+
+        PropertyState { value: self.value }
+    }
+
+    fn rebuild(self, cx: RebuildCx, state: &mut Self::State) {
+        if self.value != state.value {
+            write(cx.parent, self.name, &self.value);
+            state.value = self.value;
+        }
+    }
+}
+
+impl<V: StyleValue> Builder<Ssr> for Property<V> {
+    type State = ();
+
+    fn build(self, cx: SsrCx) -> Self::State {
+        self.value.with_css(|value| {
+            if let Some(value) = value {
+                cx.style(self.name, value)
+            }
+        })
+    }
+
+    fn rebuild(self, cx: Infallible, _: &mut Self::State) {
+        match cx {}
+    }
+}
+
+/// The state of a [`Property`].
+pub struct PropertyState<V> {
+    value: V,
+}
+
+impl<V: 'static, Output> State<Output> for PropertyState<V> {
+    fn run(&mut self, _: &mut Output) {}
+}
+
+impl<V: MemSize> MemSize for PropertyState<V> {
+    fn heap_size(&self) -> usize {
+        self.value.heap_size()
+    }
+}
+
+/// Style declarations formatted at runtime, created with
+/// [`style!`](crate::style!).
+#[derive(Copy, Clone, Debug)]
+pub struct Declarations<'a> {
+    args: Arguments<'a>,
+}
+
+/// Sets the style declarations formatted by `args`, like
+/// `"width: 10px; color: red"`, on the parent element.
+///
+/// Usually, [`style!`](crate::style!) is more convenient.
+pub fn declarations(args: Arguments) -> Declarations {
+    Declarations { args }
+}
+
+/// Splits a declaration block into property names and values.
+fn parse(text: &str) -> impl Iterator<Item = (&str, &str)> {
+    text.split(';').filter_map(|declaration| {
+        let (name, value) = declaration.split_once(':')?;
+        Some((name.trim(), value.trim()))
+    })
+}
+
+impl Builder<Web> for Declarations<'_> {
+    type State = DeclarationsState;
+
+    fn build(self, cx: BuildCx) -> Self::State {
+        let mut state = DeclarationsState {
+            text: String::new(),
+            properties: Vec::new(),
+        };
+        update_string(&mut state.text, self.args);
+        state.apply(cx.position.parent);
+
+        state
+    }
+
+    fn rebuild(self, cx: RebuildCx, state: &mut Self::State) {
+        if update_string(&mut state.text, self.args) {
+            state.apply(cx.parent)
+        }
+    }
+}
+
+impl Builder<Ssr> for Declarations<'_> {
+    type State = ();
+
+    fn build(self, cx: SsrCx) -> Self::State {
+        for (name, value) in parse(&self.args.to_string()) {
+            cx.style(name, value)
+        }
+    }
+
+    fn rebuild(self, cx: Infallible, _: &mut Self::State) {
+        match cx {}
+    }
+}
+
+/// The state of a [`Declarations`].
+pub struct DeclarationsState {
+    text: String,
+    /// The properties set from `text`, the last time it was applied.
+    properties: Vec<(String, String)>,
+}
+
+impl DeclarationsState {
+    /// Sets each property of `text` whose value changed since the last time,
+    /// and removes those which are gone.
+    fn apply(&mut self, parent: &web_sys::Element) {
+        let Some(style) = declaration(parent) else {
+            return;
+        };
+
+        let properties: Vec<_> = parse(&self.text)
+            .map(|(name, value)| (name.to_string(), value.to_string()))
+            .collect();
+
+        for (name, _) in &self.properties {
+            if !properties.iter().any(|(n, _)| n == name) {
+                stats::attribute_written();
+                style.remove_property(name).unwrap_throw();
+            }
+        }
+        for property @ (name, value) in &properties {
+            if !self.properties.contains(property) {
+                stats::attribute_written();
+                style.set_property(name, value).unwrap_throw();
+            }
+        }
+
+        self.properties = properties;
+    }
+}
+
+impl<Output> State<Output> for DeclarationsState {
+    fn run(&mut self, _: &mut Output) {}
+}
+
+impl MemSize for DeclarationsState {
+    fn heap_size(&self) -> usize {
+        self.text.heap_size() + self.properties.heap_size()
+    }
+}
+
+/// Sets style declarations on the parent element, with runtime values
+/// interpolated like [`format!`]:
+///
+/// ```ignore
+/// el::div((
+///     style!("width: {width}px; color: {}", model.color),
+///     text("Hello"),
+/// ))
+/// ```
+///
+/// On rebuild, the text is formatted in place over the previous text, so the
+/// constant parts are only compared, and the element is only touched when a
+/// value changed. Then, only the properties whose values changed are set, with
+/// the same per-property API as the rest of the [`style`](mod@crate::style)
+/// module. Values must not contain `;`.
+#[macro_export]
+macro_rules! style {
+    ($($args:tt)*) => {
+        $crate::style::declarations(::std::format_args!($($args)*))
+    };
+}
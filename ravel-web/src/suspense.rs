@@ -0,0 +1,169 @@
+use std::{cell::RefCell, convert::Infallible, future::Future, rc::Rc};
+
+use ravel::{MemSize, State};
+
+use crate::{
+    dom::{clear, marker, Position},
+    ssr::{Ssr, SsrCx},
+    BuildCx, Builder, RebuildCx, View, ViewMarker, Web,
+};
+
+/// A [`Builder`] created from [`suspense`].
+pub struct Suspense<MakeFuture, Placeholder, Render> {
+    make_future: MakeFuture,
+    placeholder: Placeholder,
+    render: Render,
+}
+
+/// Shows `placeholder` until a future resolves, then the view returned by
+/// `render` for its value.
+///
+/// `make_future` is only called when the component is initially built, and the
+/// future is spawned as a [`wasm_bindgen_futures`] task, which wakes the event
+/// loop when it completes. It isn't cancelled if the component is removed
+/// first, but its value is then discarded.
+pub fn suspense<MakeFuture, Fut, Placeholder, Render, V>(
+    make_future: MakeFuture,
+    placeholder: Placeholder,
+    render: Render,
+) -> Suspense<MakeFuture, Placeholder, Render>
+where
+    MakeFuture: FnOnce() -> Fut,
+    Fut: 'static + Future,
+    Placeholder: View,
+    Render: FnOnce(&Fut::Output) -> V,
+    V: View,
+{
+    Suspense {
+        make_future,
+        placeholder,
+        render,
+    }
+}
+
+enum Inner<P, V> {
+    Pending(P),
+    Ready(V),
+}
+
+impl<P: MemSize, V: MemSize> MemSize for Inner<P, V> {
+    fn heap_size(&self) -> usize {
+        match self {
+            Inner::Pending(state) => state.heap_size(),
+            Inner::Ready(state) => state.heap_size(),
+        }
+    }
+}
+
+impl<MakeFuture, Fut, Placeholder, Render, V> Builder<Web>
+    for Suspense<MakeFuture, Placeholder, Render>
+where
+    MakeFuture: FnOnce() -> Fut,
+    Fut: 'static + Future,
+    Placeholder: View,
+    Render: FnOnce(&Fut::Output) -> V,
+    V: View,
+{
+    type State = SuspenseState<Fut::Output, Placeholder::State, V::State>;
+
+    fn build(self, cx: BuildCx) -> Self::State {
+        let value = Rc::new(RefCell::new(None));
+
+        let future = (self.make_future)();
+        let weak = Rc::downgrade(&value);
+        let waker = cx.position.waker.clone();
+        wasm_bindgen_futures::spawn_local(async move {
+            let result = future.await;
+            if let Some(value) = weak.upgrade() {
+                *value.borrow_mut() = Some(result);
+                waker.wake();
+            }
+        });
+
+        let start = marker("{");
+        let end = marker("}");
+
+        cx.position.insert(&start);
+        let inner = Inner::Pending(self.placeholder.build(cx));
+        cx.position.insert(&end);
+
+        SuspenseState {
+            value,
+            inner,
+            start,
+            end,
+        }
+    }
+
+    fn rebuild(self, cx: RebuildCx, state: &mut Self::State) {
+        let value = state.value.borrow();
+
+        match (&*value, &mut state.inner) {
+            (None, Inner::Pending(placeholder)) => {
+                self.placeholder.rebuild(cx, placeholder)
+            }
+            (Some(value), Inner::Pending(_)) => {
+                clear(cx.parent, &state.start, &state.end);
+                state.inner =
+                    Inner::Ready((self.render)(value).build(BuildCx {
+                        position: Position {
+                            parent: cx.parent,
+                            insert_before: &state.end,
+                            waker: cx.waker,
+                        },
+                    }));
+            }
+            (Some(value), Inner::Ready(view)) => {
+                (self.render)(value).rebuild(cx, view)
+            }
+            (None, Inner::Ready(_)) => unreachable!(),
+        }
+    }
+}
+
+impl<MakeFuture, Placeholder: Builder<Ssr>, Render> Builder<Ssr>
+    for Suspense<MakeFuture, Placeholder, Render>
+{
+    type State = Placeholder::State;
+
+    fn build(self, cx: SsrCx) -> Self::State {
+        cx.comment("{");
+        let state = self.placeholder.build(cx);
+        cx.comment("}");
+
+        state
+    }
+
+    fn rebuild(self, cx: Infallible, _: &mut Self::State) {
+        match cx {}
+    }
+}
+
+/// The state of a [`Suspense`].
+pub struct SuspenseState<T, P, V> {
+    value: Rc<RefCell<Option<T>>>,
+    inner: Inner<P, V>,
+    start: web_sys::CharacterData,
+    end: web_sys::CharacterData,
+}
+
+impl<T: 'static, P, V, Output> State<Output> for SuspenseState<T, P, V>
+where
+    P: State<Output>,
+    V: State<Output>,
+{
+    fn run(&mut self, output: &mut Output) {
+        match &mut self.inner {
+            Inner::Pending(state) => state.run(output),
+            Inner::Ready(state) => state.run(output),
+        }
+    }
+}
+
+impl<T, P: MemSize, V: MemSize> MemSize for SuspenseState<T, P, V> {
+    fn heap_size(&self) -> usize {
+        self.inner.heap_size()
+    }
+}
+
+impl<T, P, V> ViewMarker for SuspenseState<T, P, V> {}
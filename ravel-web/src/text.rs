@@ -2,13 +2,27 @@
 
 use std::{
     borrow::Cow,
+    convert::Infallible,
     fmt::{Arguments, Write},
 };
 
-use ravel::{Builder, State};
+use ravel::{Builder, MemSize, State};
 use web_sys::wasm_bindgen::UnwrapThrowExt;
 
-use crate::{BuildCx, RebuildCx, ViewMarker, Web};
+use crate::{
+    ssr::{Ssr, SsrCx},
+    stats, BuildCx, RebuildCx, ViewMarker, Web,
+};
+
+fn new_node(data: &str) -> web_sys::Text {
+    stats::created();
+    web_sys::Text::new_with_data(data).unwrap_throw()
+}
+
+fn set_data(node: &web_sys::Text, data: &str) {
+    stats::text_written();
+    node.set_data(data)
+}
 
 /// A text node.
 pub struct Text<Value: ToString + AsRef<str>> {
@@ -19,8 +33,7 @@ impl<Value: ToString + AsRef<str>> Builder<Web> for Text<Value> {
     type State = TextState<String>;
 
     fn build(self, cx: BuildCx) -> Self::State {
-        let node =
-            web_sys::Text::new_with_data(self.value.as_ref()).unwrap_throw();
+        let node = new_node(self.value.as_ref());
 
         cx.position.insert(&node);
 
@@ -32,12 +45,24 @@ impl<Value: ToString + AsRef<str>> Builder<Web> for Text<Value> {
 
     fn rebuild(self, _: RebuildCx, state: &mut Self::State) {
         if state.value != self.value.as_ref() {
-            state.node.set_data(self.value.as_ref());
+            set_data(&state.node, self.value.as_ref());
             state.value = self.value.to_string();
         }
     }
 }
 
+impl<Value: ToString + AsRef<str>> Builder<Ssr> for Text<Value> {
+    type State = ();
+
+    fn build(self, cx: SsrCx) -> Self::State {
+        cx.text(self.value.as_ref())
+    }
+
+    fn rebuild(self, cx: Infallible, _: &mut Self::State) {
+        match cx {}
+    }
+}
+
 /// The state of a [`Text`].
 pub struct TextState<Value> {
     node: web_sys::Text,
@@ -48,6 +73,12 @@ impl<Output, Value: 'static> State<Output> for TextState<Value> {
     fn run(&mut self, _: &mut Output) {}
 }
 
+impl<Value: MemSize> MemSize for TextState<Value> {
+    fn heap_size(&self) -> usize {
+        self.value.heap_size()
+    }
+}
+
 impl<Value> ViewMarker for TextState<Value> {}
 
 /// A text node.
@@ -59,7 +90,7 @@ impl Builder<Web> for &'static str {
     type State = TextState<Self>;
 
     fn build(self, cx: BuildCx) -> Self::State {
-        let node = web_sys::Text::new_with_data(self).unwrap_throw();
+        let node = new_node(self);
 
         cx.position.insert(&node);
 
@@ -68,12 +99,24 @@ impl Builder<Web> for &'static str {
 
     fn rebuild(self, _: RebuildCx, state: &mut Self::State) {
         if !std::ptr::eq(self, state.value) {
-            state.node.set_data(self);
+            set_data(&state.node, self);
             state.value = self;
         }
     }
 }
 
+impl Builder<Ssr> for &'static str {
+    type State = ();
+
+    fn build(self, cx: SsrCx) -> Self::State {
+        cx.text(self)
+    }
+
+    fn rebuild(self, cx: Infallible, _: &mut Self::State) {
+        match cx {}
+    }
+}
+
 macro_rules! make_builder_web_to_string {
     ($t:ty) => {
         impl Builder<Web> for $t {
@@ -82,7 +125,7 @@ macro_rules! make_builder_web_to_string {
             fn build(self, cx: BuildCx) -> Self::State {
                 let data = self.to_string();
 
-                let node = web_sys::Text::new_with_data(&data).unwrap_throw();
+                let node = new_node(&data);
                 cx.position.insert(&node);
 
                 TextState { node, value: self }
@@ -93,10 +136,22 @@ macro_rules! make_builder_web_to_string {
                     return;
                 }
 
-                state.node.set_data(&self.to_string());
+                set_data(&state.node, &self.to_string());
                 state.value = self.clone();
             }
         }
+
+        impl Builder<Ssr> for $t {
+            type State = ();
+
+            fn build(self, cx: SsrCx) -> Self::State {
+                cx.text(&self.to_string())
+            }
+
+            fn rebuild(self, cx: Infallible, _: &mut Self::State) {
+                match cx {}
+            }
+        }
     };
 }
 
@@ -127,7 +182,7 @@ impl<T: 'static + ToString + PartialEq + Clone> Builder<Web> for Display<T> {
     fn build(self, cx: BuildCx<'_>) -> Self::State {
         let data = self.value.to_string();
 
-        let node = web_sys::Text::new_with_data(&data).unwrap_throw();
+        let node = new_node(&data);
         cx.position.insert(&node);
 
         DisplayState {
@@ -141,11 +196,23 @@ impl<T: 'static + ToString + PartialEq + Clone> Builder<Web> for Display<T> {
             return;
         }
 
-        state.node.set_data(&self.value.to_string());
+        set_data(&state.node, &self.value.to_string());
         state.value = self.value.clone();
     }
 }
 
+impl<T: ToString + PartialEq + Clone> Builder<Ssr> for Display<T> {
+    type State = ();
+
+    fn build(self, cx: SsrCx) -> Self::State {
+        cx.text(&self.value.to_string())
+    }
+
+    fn rebuild(self, cx: Infallible, _: &mut Self::State) {
+        match cx {}
+    }
+}
+
 /// Displays a borrowed value, updating when not equal to the previous value.
 pub struct DisplayRef<'a, T: ToString + PartialEq + Clone> {
     value: &'a T,
@@ -159,7 +226,7 @@ impl<'a, T: 'static + ToString + PartialEq + Clone> Builder<Web>
     fn build(self, cx: BuildCx<'_>) -> Self::State {
         let data = self.value.to_string();
 
-        let node = web_sys::Text::new_with_data(&data).unwrap_throw();
+        let node = new_node(&data);
         cx.position.insert(&node);
 
         DisplayState {
@@ -173,11 +240,23 @@ impl<'a, T: 'static + ToString + PartialEq + Clone> Builder<Web>
             return;
         }
 
-        state.node.set_data(&self.value.to_string());
+        set_data(&state.node, &self.value.to_string());
         state.value = self.value.clone();
     }
 }
 
+impl<T: ToString + PartialEq + Clone> Builder<Ssr> for DisplayRef<'_, T> {
+    type State = ();
+
+    fn build(self, cx: SsrCx) -> Self::State {
+        cx.text(&self.value.to_string())
+    }
+
+    fn rebuild(self, cx: Infallible, _: &mut Self::State) {
+        match cx {}
+    }
+}
+
 /// The state for a [`Display`].
 pub struct DisplayState<T: ToString + PartialEq> {
     node: web_sys::Text,
@@ -197,6 +276,12 @@ pub fn display<T: ToString + PartialEq + Clone>(value: T) -> Display<T> {
     Display { value }
 }
 
+impl<T: ToString + PartialEq + MemSize> MemSize for DisplayState<T> {
+    fn heap_size(&self) -> usize {
+        self.value.heap_size()
+    }
+}
+
 /// Displays a borrowed value, updating when not equal to the previous value.
 pub fn display_ref<T: ToString + PartialEq + Clone>(
     value: &T,
@@ -204,6 +289,74 @@ pub fn display_ref<T: ToString + PartialEq + Clone>(
     DisplayRef { value }
 }
 
+/// Text computed from a key, which is only recomputed when the key changes.
+pub struct CachedText<K, F> {
+    key: K,
+    text: F,
+}
+
+/// Displays the text returned by `text`, which is only called when the
+/// component is built, and when `key` isn't equal to the previous key.
+///
+/// This is for text which is expensive to format (for example, dates or
+/// currencies), even when it rarely changes:
+///
+/// ```ignore
+/// cached_text(model.total, || format_currency(model.total, &model.locale))
+/// ```
+///
+/// The key must capture everything the text depends on.
+pub fn cached_text<K, F, T>(key: K, text: F) -> CachedText<K, F>
+where
+    K: PartialEq,
+    F: FnOnce() -> T,
+    T: AsRef<str>,
+{
+    CachedText { key, text }
+}
+
+impl<K, F, T> Builder<Web> for CachedText<K, F>
+where
+    K: 'static + PartialEq,
+    F: FnOnce() -> T,
+    T: AsRef<str>,
+{
+    type State = TextState<K>;
+
+    fn build(self, cx: BuildCx) -> Self::State {
+        let node = new_node((self.text)().as_ref());
+        cx.position.insert(&node);
+
+        TextState {
+            node,
+            value: self.key,
+        }
+    }
+
+    fn rebuild(self, _: RebuildCx, state: &mut Self::State) {
+        if self.key != state.value {
+            set_data(&state.node, (self.text)().as_ref());
+            state.value = self.key;
+        }
+    }
+}
+
+impl<K, F, T> Builder<Ssr> for CachedText<K, F>
+where
+    F: FnOnce() -> T,
+    T: AsRef<str>,
+{
+    type State = ();
+
+    fn build(self, cx: SsrCx) -> Self::State {
+        cx.text((self.text)().as_ref())
+    }
+
+    fn rebuild(self, cx: Infallible, _: &mut Self::State) {
+        match cx {}
+    }
+}
+
 impl<'a> Builder<Web> for Arguments<'a> {
     type State = TextState<Cow<'static, str>>;
 
@@ -213,7 +366,7 @@ impl<'a> Builder<Web> for Arguments<'a> {
             None => Cow::Owned(self.to_string()),
         };
 
-        let node = web_sys::Text::new_with_data(&value).unwrap_throw();
+        let node = new_node(&value);
 
         cx.position.insert(&node);
 
@@ -227,27 +380,19 @@ impl<'a> Builder<Web> for Arguments<'a> {
                     Cow::Borrowed(old) => std::ptr::eq(new, *old),
                     Cow::Owned(old) => new == old,
                 } {
-                    state.node.set_data(new);
+                    set_data(&state.node, new);
                     state.value = Cow::Borrowed(new);
                 }
             }
             None => match &mut state.value {
                 Cow::Borrowed(_) => {
                     let new = self.to_string();
-                    state.node.set_data(&new);
+                    set_data(&state.node, &new);
                     state.value = Cow::Owned(new);
                 }
                 Cow::Owned(value) => {
-                    let mut w = UpdateString {
-                        value,
-                        index: 0,
-                        changed: false,
-                    };
-
-                    std::fmt::write(&mut w, self).unwrap_throw();
-
-                    if w.changed {
-                        state.node.set_data(value);
+                    if update_string(value, self) {
+                        set_data(&state.node, value);
                     }
                 }
             },
@@ -255,6 +400,40 @@ impl<'a> Builder<Web> for Arguments<'a> {
     }
 }
 
+impl Builder<Ssr> for Arguments<'_> {
+    type State = ();
+
+    fn build(self, cx: SsrCx) -> Self::State {
+        match self.as_str() {
+            Some(s) => cx.text(s),
+            None => cx.text(&self.to_string()),
+        }
+    }
+
+    fn rebuild(self, cx: Infallible, _: &mut Self::State) {
+        match cx {}
+    }
+}
+
+/// Formats `args` into `value` in place, only rewriting from the first
+/// difference onwards. Returns whether `value` changed.
+pub(crate) fn update_string(value: &mut String, args: Arguments) -> bool {
+    let mut w = UpdateString {
+        value,
+        index: 0,
+        changed: false,
+    };
+
+    std::fmt::write(&mut w, args).unwrap_throw();
+
+    if w.index < w.value.len() {
+        w.value.truncate(w.index);
+        w.changed = true;
+    }
+
+    w.changed
+}
+
 struct UpdateString<'a> {
     value: &'a mut String,
     index: usize,
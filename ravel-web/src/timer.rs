@@ -0,0 +1,169 @@
+//! Timers which update the model periodically or after a delay.
+//!
+//! Timers are scheduled with the [`Clock`](crate::env::Clock) provided when
+//! they are built, so they can be driven by a
+//! [`ManualClock`](crate::env::ManualClock) in tests.
+
+use std::{cell::Cell, convert::Infallible, rc::Rc, sync::Arc, time::Duration};
+
+use atomic_waker::AtomicWaker;
+use ravel::{Builder, MemSize, State};
+
+use crate::{
+    env::{self, Scheduled},
+    ssr::{Ssr, SsrCx},
+    BuildCx, RebuildCx, ViewMarker, Web,
+};
+
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+enum Mode {
+    Interval,
+    Timeout,
+}
+
+/// A timer, created with [`interval`] or [`timeout`].
+pub struct Timer<Action> {
+    mode: Mode,
+    duration: Duration,
+    action: Action,
+}
+
+/// Calls `action` every `period`, starting one `period` after the component is
+/// built, until it is removed.
+///
+/// If several ticks elapse before the next frame, `action` is called once for
+/// each of them. Changing `period` restarts the timer.
+pub fn interval<Action, Output>(
+    period: Duration,
+    action: Action,
+) -> Timer<Action>
+where
+    Action: 'static + FnMut(&mut Output),
+    Output: 'static,
+{
+    Timer {
+        mode: Mode::Interval,
+        duration: period,
+        action,
+    }
+}
+
+/// Calls `action` once, `delay` after the component is built, unless it has
+/// been removed by then.
+///
+/// Changing `delay` before the timer fires restarts it.
+pub fn timeout<Action, Output>(delay: Duration, action: Action) -> Timer<Action>
+where
+    Action: 'static + FnMut(&mut Output),
+    Output: 'static,
+{
+    Timer {
+        mode: Mode::Timeout,
+        duration: delay,
+        action,
+    }
+}
+
+enum Handle {
+    Interval { _handle: Scheduled },
+    Timeout { _handle: Scheduled },
+    Fired,
+}
+
+fn start(
+    mode: Mode,
+    duration: Duration,
+    ticks: &Rc<Cell<u32>>,
+    waker: &Arc<AtomicWaker>,
+) -> Handle {
+    let millis = u32::try_from(duration.as_millis()).unwrap_or(u32::MAX);
+    let tick = {
+        let ticks = ticks.clone();
+        let waker = waker.clone();
+        move || {
+            ticks.set(ticks.get() + 1);
+            waker.wake();
+        }
+    };
+
+    match mode {
+        Mode::Interval => Handle::Interval {
+            _handle: env::set_interval(millis, tick),
+        },
+        Mode::Timeout => Handle::Timeout {
+            _handle: env::set_timeout(millis, tick),
+        },
+    }
+}
+
+impl<Action: 'static> Builder<Web> for Timer<Action> {
+    type State = TimerState<Action>;
+
+    fn build(self, cx: BuildCx) -> Self::State {
+        let ticks = Rc::new(Cell::new(0));
+
+        TimerState {
+            handle: start(self.mode, self.duration, &ticks, cx.position.waker),
+            ticks,
+            waker: cx.position.waker.clone(),
+            duration: self.duration,
+            action: self.action,
+        }
+    }
+
+    fn rebuild(self, _: RebuildCx, state: &mut Self::State) {
+        state.action = self.action;
+
+        if state.duration != self.duration
+            && !matches!(state.handle, Handle::Fired)
+        {
+            state.duration = self.duration;
+            state.handle =
+                start(self.mode, self.duration, &state.ticks, &state.waker);
+        }
+    }
+}
+
+impl<Action> Builder<Ssr> for Timer<Action> {
+    type State = ();
+
+    fn build(self, _: SsrCx) -> Self::State {}
+
+    fn rebuild(self, cx: Infallible, _: &mut Self::State) {
+        match cx {}
+    }
+}
+
+/// The state of a [`Timer`].
+pub struct TimerState<Action> {
+    handle: Handle,
+    ticks: Rc<Cell<u32>>,
+    waker: Arc<AtomicWaker>,
+    duration: Duration,
+    action: Action,
+}
+
+impl<Action, Output> State<Output> for TimerState<Action>
+where
+    Action: 'static + FnMut(&mut Output),
+    Output: 'static,
+{
+    fn run(&mut self, output: &mut Output) {
+        let ticks = self.ticks.replace(0);
+        if ticks > 0 && matches!(self.handle, Handle::Timeout { .. }) {
+            self.handle = Handle::Fired;
+        }
+
+        for _ in 0..ticks {
+            (self.action)(output);
+        }
+    }
+}
+
+impl<Action> MemSize for TimerState<Action> {
+    fn heap_size(&self) -> usize {
+        0
+    }
+}
+
+impl<Action> ViewMarker for TimerState<Action> {}
@@ -0,0 +1,445 @@
+//! Animating components as they are inserted and removed.
+//!
+//! A [`transition`] wraps an optional view like an [`Option`], but toggles CSS
+//! classes on its elements so that stylesheet transitions can run when they
+//! appear and disappear. For a [`Config`] named `fade`:
+//!
+//! ```css
+//! .fade-enter, .fade-exit { opacity: 0; }
+//! .panel { transition: opacity 200ms; }
+//! ```
+//!
+//! * `fade-enter` is added to the root elements of newly built content, and
+//!   removed once every DOM change of the frame has been made, so that they
+//!   transition from the styles it applies to their normal ones.
+//! * `fade-exit` is added to the root elements of removed content, which is
+//!   kept in the document until one of them fires `transitionend`, or the
+//!   configured duration has elapsed.
+//!
+//! The entrances of list entries can be spread out in time with a [`Stagger`].
+
+use std::{
+    cell::{Cell, RefCell},
+    collections::HashMap,
+    convert::Infallible,
+    rc::Rc,
+    sync::Arc,
+    time::Duration,
+};
+
+use atomic_waker::AtomicWaker;
+use gloo_events::EventListener;
+use ravel::{MemSize, State};
+use web_sys::wasm_bindgen::{JsCast as _, UnwrapThrowExt as _};
+
+use crate::{
+    dom::{marker, remove, Position},
+    env::{self, Clock, Scheduled},
+    ssr::{Ssr, SsrCx},
+    BuildCx, Builder, RebuildCx, View, ViewMarker, Web,
+};
+
+/// The elements of content which entered in the current pass.
+struct Entering {
+    elements: Vec<web_sys::Element>,
+    /// The enter class, to remove once the pass has finished.
+    class: String,
+    config: Config,
+    /// The clock provided where the content was built, to delay staggered
+    /// transitions with.
+    clock: Rc<dyn Clock>,
+}
+
+thread_local! {
+    static ENTERING: RefCell<Vec<Entering>> = const { RefCell::new(Vec::new()) };
+}
+
+/// Starts the enter transitions of elements built in the current pass.
+pub(crate) fn flush() {
+    let entering = ENTERING.take();
+    let Some(first) = entering.iter().flat_map(|e| &e.elements).next() else {
+        return;
+    };
+
+    // Force a style recalculation, so that the enter classes take effect
+    // before they are removed.
+    first.get_bounding_client_rect();
+
+    // For each staggered config, the number of its transitions entering in
+    // this pass, and how many of them have been started.
+    let mut staggered: HashMap<&'static str, (usize, usize)> = HashMap::new();
+    for entering in &entering {
+        if entering.config.stagger.is_some() {
+            staggered.entry(entering.config.name).or_default().0 += 1;
+        }
+    }
+
+    for Entering {
+        elements,
+        class,
+        config,
+        clock,
+    } in entering
+    {
+        let delay = match config.stagger {
+            Some(stagger) => {
+                let (count, started) =
+                    staggered.get_mut(config.name).unwrap_throw();
+                *started += 1;
+                stagger.delay(*started - 1, *count)
+            }
+            None => Duration::ZERO,
+        };
+
+        let start = move || {
+            for element in &elements {
+                let _ = element.class_list().remove_1(&class);
+            }
+        };
+        if delay.is_zero() {
+            start()
+        } else {
+            let millis = delay.as_millis().try_into().unwrap_or(u32::MAX);
+            clock.set_timeout(millis, Box::new(start)).forget();
+        }
+    }
+}
+
+/// How the enter transitions of several pieces of content entering at once,
+/// such as the new entries of a [`keyed`](fn@crate::collections::keyed)
+/// collection, are spread out in time. See [`Config::stagger`].
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+pub enum Stagger {
+    /// Each transition starts this long after the previous one, so the
+    /// total time grows with the number of entering items.
+    Each(Duration),
+    /// The transitions start evenly spread over this long, however many items
+    /// are entering: the first starts immediately, and the last after the
+    /// whole duration.
+    Over(Duration),
+}
+
+impl Stagger {
+    /// The delay of the `index`th of `count` entering transitions.
+    fn delay(self, index: usize, count: usize) -> Duration {
+        match self {
+            Stagger::Each(step) => {
+                step.saturating_mul(index.try_into().unwrap_or(u32::MAX))
+            }
+            Stagger::Over(_) if count <= 1 => Duration::ZERO,
+            Stagger::Over(total) => {
+                total.mul_f64(index as f64 / (count - 1) as f64)
+            }
+        }
+    }
+}
+
+/// The classes and timing of a [`transition`].
+#[derive(Copy, Clone, Debug)]
+pub struct Config {
+    name: &'static str,
+    duration: Duration,
+    appear: bool,
+    stagger: Option<Stagger>,
+}
+
+impl Config {
+    /// A transition using the classes `{name}-enter` and `{name}-exit`, where
+    /// removed content is kept for at most `duration`.
+    pub const fn new(name: &'static str, duration: Duration) -> Self {
+        Self {
+            name,
+            duration,
+            appear: false,
+            stagger: None,
+        }
+    }
+
+    /// Also runs the enter transition for content present when the
+    /// [`transition`] is initially built.
+    pub const fn appear(self) -> Self {
+        Self {
+            appear: true,
+            ..self
+        }
+    }
+
+    /// Staggers the enter transitions of content with this config which
+    /// enters in the same pass, in the order it was built.
+    ///
+    /// This is mostly useful for the entries of a collection, each wrapped in
+    /// a transition with [`Config::appear`], so that new entries (and, on the
+    /// first build, the whole list) cascade in:
+    ///
+    /// ```ignore
+    /// const ITEM: Config = Config::new("item", Duration::from_millis(200))
+    ///     .appear()
+    ///     .stagger(Stagger::Each(Duration::from_millis(40)));
+    ///
+    /// keyed(&items, |item| item.id, |cx, item| {
+    ///     cx.build(transition(ITEM, Some(row(item))))
+    /// })
+    /// ```
+    ///
+    /// Until its transition starts, content keeps its enter class. Exit
+    /// transitions aren't staggered.
+    pub const fn stagger(self, stagger: Stagger) -> Self {
+        Self {
+            stagger: Some(stagger),
+            ..self
+        }
+    }
+
+    fn class(&self, suffix: &str) -> String {
+        format!("{}-{suffix}", self.name)
+    }
+}
+
+/// A [`Builder`] created from [`transition`].
+pub struct Transition<V> {
+    config: Config,
+    view: Option<V>,
+}
+
+/// Shows `view` if it is [`Some`], running the enter and exit transitions of
+/// `config` as it is inserted and removed.
+///
+/// Only the root elements of the view have their classes changed: text nodes
+/// and nested elements are left alone. Content which is removed stops being
+/// rebuilt immediately, so its event handlers no longer fire, but its nodes
+/// stay in place until the exit transition ends. If the view reappears in the
+/// meantime, it is built afresh after them.
+pub fn transition<V: View>(config: Config, view: Option<V>) -> Transition<V> {
+    Transition { config, view }
+}
+
+impl<V: View> Builder<Web> for Transition<V> {
+    type State = TransitionState<V::State>;
+
+    fn build(self, cx: BuildCx) -> Self::State {
+        let start = marker("(");
+        let content = marker("");
+        let end = marker(")");
+
+        cx.position.insert(&start);
+        cx.position.insert(&content);
+        cx.position.insert(&end);
+
+        let mut state = TransitionState {
+            state: None,
+            exits: Vec::new(),
+            start,
+            content,
+            end,
+        };
+        if let Some(view) = self.view {
+            state.enter(
+                cx.position.parent,
+                cx.position.waker,
+                &self.config,
+                view,
+                self.config.appear,
+            );
+        }
+
+        state
+    }
+
+    fn rebuild(self, cx: RebuildCx, state: &mut Self::State) {
+        state.exits.retain(|exit| !exit.done.get());
+
+        match (self.view, &mut state.state) {
+            (None, None) => {}
+            (None, Some(_)) => state.exit(&self.config),
+            (Some(view), None) => {
+                state.enter(cx.parent, cx.waker, &self.config, view, true)
+            }
+            (Some(view), Some(inner)) => view.rebuild(cx, inner),
+        }
+    }
+}
+
+impl<V: Builder<Ssr>> Builder<Ssr> for Transition<V> {
+    type State = Option<V::State>;
+
+    fn build(self, cx: SsrCx) -> Self::State {
+        cx.comment("(");
+        cx.comment("");
+        let state = self.view.map(|b| b.build(cx));
+        cx.comment(")");
+
+        state
+    }
+
+    fn rebuild(self, cx: Infallible, _: &mut Self::State) {
+        match cx {}
+    }
+}
+
+/// Removed content whose exit transition is running.
+struct Exit {
+    done: Rc<Cell<bool>>,
+    _listeners: Vec<EventListener>,
+    _timeout: Scheduled,
+}
+
+/// The state of a [`Transition`].
+pub struct TransitionState<S> {
+    state: Option<S>,
+    exits: Vec<Exit>,
+    start: web_sys::CharacterData,
+    /// The marker between exiting content and the current content.
+    content: web_sys::CharacterData,
+    end: web_sys::CharacterData,
+}
+
+impl<S> TransitionState<S> {
+    /// The marker before the transition's nodes, including exiting ones.
+    pub fn start(&self) -> &web_sys::Node {
+        &self.start
+    }
+
+    /// The marker after the transition's nodes.
+    pub fn end(&self) -> &web_sys::Node {
+        &self.end
+    }
+
+    /// The nodes of the current content.
+    fn nodes(&self) -> Vec<web_sys::Node> {
+        let end: &web_sys::Node = &self.end;
+        let mut nodes = Vec::new();
+        let mut next = self.content.next_sibling();
+        while let Some(node) = next {
+            if &node == end {
+                break;
+            }
+            next = node.next_sibling();
+            nodes.push(node);
+        }
+        nodes
+    }
+
+    /// The root elements of the current content.
+    fn elements(&self) -> Vec<web_sys::Element> {
+        self.nodes()
+            .into_iter()
+            .filter_map(|node| node.dyn_into().ok())
+            .collect()
+    }
+
+    fn enter<V: Builder<Web, State = S>>(
+        &mut self,
+        parent: &web_sys::Element,
+        waker: &Arc<AtomicWaker>,
+        config: &Config,
+        view: V,
+        animate: bool,
+    ) {
+        self.state = Some(view.build(BuildCx {
+            position: Position {
+                parent,
+                insert_before: &self.end,
+                waker,
+            },
+        }));
+
+        if !animate {
+            return;
+        }
+
+        let class = config.class("enter");
+        let elements = self.elements();
+        for element in &elements {
+            let _ = element.class_list().add_1(&class);
+        }
+        ENTERING.with_borrow_mut(|entering| {
+            entering.push(Entering {
+                elements,
+                class,
+                config: *config,
+                clock: env::clock(),
+            })
+        });
+    }
+
+    fn exit(&mut self, config: &Config) {
+        self.state = None;
+
+        let nodes = self.nodes();
+        let elements: Vec<web_sys::Element> = nodes
+            .iter()
+            .filter_map(|node| node.dyn_ref().cloned())
+            .collect();
+
+        // Later content goes after the exiting nodes.
+        if let Some(parent) = self.end.parent_node() {
+            parent
+                .insert_before(&self.content, Some(&self.end))
+                .unwrap_throw();
+        }
+
+        let done = Rc::new(Cell::new(false));
+        let nodes: Rc<[web_sys::Node]> = nodes.into();
+        let finish = {
+            let done = done.clone();
+            move || {
+                if done.replace(true) {
+                    return;
+                }
+                for node in nodes.iter() {
+                    if let Some(parent) = node.parent_node() {
+                        remove(&parent, node);
+                    }
+                }
+            }
+        };
+
+        let class = config.class("exit");
+        let listeners = elements
+            .iter()
+            .map(|element| {
+                let _ = element.class_list().add_1(&class);
+
+                let finish = finish.clone();
+                let target = element.clone();
+                EventListener::new(element, "transitionend", move |e| {
+                    // Ignore transitions of descendants, which bubble.
+                    if e.target().as_ref() == Some(&target) {
+                        finish()
+                    }
+                })
+            })
+            .collect();
+
+        let millis = config.duration.as_millis().try_into().unwrap_or(u32::MAX);
+        self.exits.push(Exit {
+            done,
+            _listeners: listeners,
+            _timeout: env::set_timeout(millis, finish),
+        });
+    }
+}
+
+impl<S, Output> State<Output> for TransitionState<S>
+where
+    S: State<Output>,
+{
+    fn run(&mut self, output: &mut Output) {
+        let Some(state) = &mut self.state else { return };
+        state.run(output)
+    }
+}
+
+impl<S: MemSize> MemSize for TransitionState<S> {
+    fn heap_size(&self) -> usize {
+        self.state.heap_size()
+            + self.exits.capacity() * size_of::<Exit>()
+            + self
+                .exits
+                .iter()
+                .map(|e| e._listeners.capacity() * size_of::<EventListener>())
+                .sum::<usize>()
+    }
+}
+
+impl<S> ViewMarker for TransitionState<S> {}
@@ -0,0 +1,209 @@
+//! Typed CSS values, shared by [`style`](mod@crate::style) properties and
+//! attributes.
+//!
+//! Each type formats with its unit, so units can't be mixed up or left out,
+//! and compares numerically, which makes diffing cheap.
+
+use std::{fmt, marker::PhantomData};
+
+use ravel::MemSize;
+
+macro_rules! unit {
+    ($(#[$attr:meta])* $name:ident, $f:ident, $unit:literal) => {
+        $(#[$attr])*
+        #[derive(Copy, Clone, PartialEq, PartialOrd, Debug, Default)]
+        pub struct $name(pub f64);
+
+        $(#[$attr])*
+        pub fn $f(value: impl Into<f64>) -> $name {
+            $name(value.into())
+        }
+
+        impl fmt::Display for $name {
+            fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+                write!(f, "{}{}", self.0, $unit)
+            }
+        }
+
+        impl MemSize for $name {
+            fn heap_size(&self) -> usize {
+                0
+            }
+        }
+    };
+}
+
+unit!(
+    /// A length in CSS pixels.
+    Px, px, "px"
+);
+unit!(
+    /// A length relative to the element's font size.
+    Em, em, "em"
+);
+unit!(
+    /// A length relative to the root element's font size.
+    Rem, rem, "rem"
+);
+unit!(
+    /// A length relative to the corresponding length of the containing block.
+    Percent, percent, "%"
+);
+
+/// A duration, for transitions and animations.
+#[derive(Copy, Clone, PartialEq, PartialOrd, Debug, Default)]
+pub struct Duration {
+    millis: f64,
+}
+
+impl MemSize for Duration {
+    fn heap_size(&self) -> usize {
+        0
+    }
+}
+
+impl Duration {
+    /// The duration in milliseconds.
+    pub fn as_millis(self) -> f64 {
+        self.millis
+    }
+}
+
+/// A duration in milliseconds.
+pub fn ms(value: impl Into<f64>) -> Duration {
+    Duration {
+        millis: value.into(),
+    }
+}
+
+/// A duration in seconds.
+pub fn secs(value: impl Into<f64>) -> Duration {
+    ms(value.into() * 1000.)
+}
+
+impl From<std::time::Duration> for Duration {
+    fn from(duration: std::time::Duration) -> Self {
+        ms(duration.as_secs_f64() * 1000.)
+    }
+}
+
+impl fmt::Display for Duration {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}ms", self.millis)
+    }
+}
+
+/// An sRGB color, with an alpha channel.
+#[derive(Copy, Clone, PartialEq, Debug)]
+pub struct Color {
+    /// The red channel.
+    pub r: u8,
+    /// The green channel.
+    pub g: u8,
+    /// The blue channel.
+    pub b: u8,
+    /// The opacity, from 0 (transparent) to 1 (opaque).
+    pub alpha: f32,
+}
+
+impl MemSize for Color {
+    fn heap_size(&self) -> usize {
+        0
+    }
+}
+
+/// An opaque color.
+pub fn rgb(r: u8, g: u8, b: u8) -> Color {
+    rgba(r, g, b, 1.)
+}
+
+/// A color with the given opacity, from 0 (transparent) to 1 (opaque).
+pub fn rgba(r: u8, g: u8, b: u8, alpha: f32) -> Color {
+    Color { r, g, b, alpha }
+}
+
+/// An opaque color from its hexadecimal notation, for example
+/// `hex(0xff8800)`.
+pub fn hex(rgb: u32) -> Color {
+    let [_, r, g, b] = rgb.to_be_bytes();
+    self::rgb(r, g, b)
+}
+
+impl Color {
+    /// The same color with a different opacity.
+    pub fn with_alpha(self, alpha: f32) -> Self {
+        Self { alpha, ..self }
+    }
+}
+
+/// Opaque colors are formatted like `#ff8800`, which is also the format
+/// expected by `<input type="color">`. Others are formatted like
+/// `rgb(255 136 0 / 0.5)`.
+impl fmt::Display for Color {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let Self { r, g, b, alpha } = *self;
+
+        if alpha >= 1. {
+            write!(f, "#{r:02x}{g:02x}{b:02x}")
+        } else {
+            write!(f, "rgb({r} {g} {b} / {alpha})")
+        }
+    }
+}
+
+/// A reference to a CSS custom property holding a value of type `T`, usually
+/// declared with [`theme!`](crate::theme!).
+///
+/// This formats as `var(--name)`, and can be used wherever a `T` is expected.
+pub struct Var<T> {
+    name: &'static str,
+    phantom: PhantomData<fn() -> T>,
+}
+
+impl<T> MemSize for Var<T> {
+    fn heap_size(&self) -> usize {
+        0
+    }
+}
+
+impl<T> Var<T> {
+    /// A reference to the custom property `name`, which includes the leading
+    /// `--`.
+    pub const fn new(name: &'static str) -> Self {
+        Self {
+            name,
+            phantom: PhantomData,
+        }
+    }
+
+    /// The name of the custom property, including the leading `--`.
+    pub fn name(&self) -> &'static str {
+        self.name
+    }
+}
+
+impl<T> Clone for Var<T> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<T> Copy for Var<T> {}
+
+impl<T> PartialEq for Var<T> {
+    fn eq(&self, other: &Self) -> bool {
+        self.name == other.name
+    }
+}
+
+impl<T> fmt::Debug for Var<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("Var").field(&self.name).finish()
+    }
+}
+
+impl<T> fmt::Display for Var<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "var({})", self.name)
+    }
+}
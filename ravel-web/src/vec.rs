@@ -0,0 +1,125 @@
+use std::convert::Infallible;
+
+use ravel::{MemSize, State};
+
+use crate::{
+    dom::{clear, marker, remove, Position},
+    ssr::{Ssr, SsrCx},
+    BuildCx, Builder, RebuildCx, View, ViewMarker, Web,
+};
+
+/// A [`Vec`] of views is built in order, like an array, but its length may
+/// change between frames. Views are matched by index, so items added or
+/// removed anywhere but the end cause the following items to be rebuilt with
+/// each other's data. For lists of data which change in other ways, see
+/// [`crate::collections`].
+impl<V: View> Builder<Web> for Vec<V> {
+    type State = VecState<V::State>;
+
+    fn build(self, cx: BuildCx) -> Self::State {
+        let entries = self
+            .into_iter()
+            .map(|v| {
+                let header = marker("|");
+                cx.position.insert(&header);
+
+                Entry {
+                    header,
+                    state: v.build(cx),
+                }
+            })
+            .collect();
+
+        let footer = marker("|");
+        cx.position.insert(&footer);
+
+        VecState { entries, footer }
+    }
+
+    fn rebuild(self, cx: RebuildCx, state: &mut Self::State) {
+        let len = self.len();
+
+        if let Some(first) = state.entries.get(len) {
+            clear(cx.parent, &first.header, &state.footer);
+            remove(cx.parent, &first.header);
+            state.entries.truncate(len);
+        }
+
+        let mut views = self.into_iter();
+        for (v, entry) in views.by_ref().zip(state.entries.iter_mut()) {
+            v.rebuild(cx, &mut entry.state)
+        }
+
+        state.entries.extend(views.map(|v| {
+            let position = Position {
+                parent: cx.parent,
+                insert_before: &state.footer,
+                waker: cx.waker,
+            };
+
+            let header = marker("|");
+            position.insert(&header);
+
+            Entry {
+                header,
+                state: v.build(BuildCx { position }),
+            }
+        }));
+    }
+}
+
+impl<B: Builder<Ssr>> Builder<Ssr> for Vec<B> {
+    type State = Vec<B::State>;
+
+    fn build(self, cx: SsrCx) -> Self::State {
+        let states = self
+            .into_iter()
+            .map(|b| {
+                cx.comment("|");
+                b.build(cx)
+            })
+            .collect();
+        cx.comment("|");
+
+        states
+    }
+
+    fn rebuild(self, cx: Infallible, _: &mut Self::State) {
+        match cx {}
+    }
+}
+
+struct Entry<S> {
+    header: web_sys::CharacterData,
+    state: S,
+}
+
+/// The state of a [`Vec`] of views.
+pub struct VecState<S> {
+    entries: Vec<Entry<S>>,
+    footer: web_sys::CharacterData,
+}
+
+impl<S, Output> State<Output> for VecState<S>
+where
+    S: State<Output>,
+{
+    fn run(&mut self, output: &mut Output) {
+        for entry in &mut self.entries {
+            entry.state.run(output)
+        }
+    }
+}
+
+impl<S: MemSize> MemSize for VecState<S> {
+    fn heap_size(&self) -> usize {
+        self.entries.capacity() * size_of::<Entry<S>>()
+            + self
+                .entries
+                .iter()
+                .map(|e| e.state.heap_size())
+                .sum::<usize>()
+    }
+}
+
+impl<S> ViewMarker for VecState<S> {}
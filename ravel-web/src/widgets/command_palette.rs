@@ -0,0 +1,215 @@
+//! A keyboard driven command palette with fuzzy search.
+
+use std::rc::Rc;
+
+use ravel::with_local;
+use web_sys::wasm_bindgen::JsCast as _;
+
+use crate::{
+    attr::{Class, Hidden, Placeholder, Role, Type},
+    collections::iter,
+    el,
+    event::{on, on_window, target_value, Active, Click, InputEvent, KeyDown},
+    form::{controlled_value, input_state},
+    text::text,
+    View,
+};
+
+/// The maximum number of matching commands shown at once.
+pub const MAX_RESULTS: usize = 50;
+
+/// A command which can be run from a [`command_palette`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Command<Id> {
+    /// Identifies the command when it is selected.
+    pub id: Id,
+    /// The text shown for, and searched for, the command.
+    pub label: String,
+}
+
+/// Scores how well `query` fuzzy matches `label`, or returns [`None`] if the
+/// characters of `query` do not all appear in order in `label`.
+///
+/// Matching is case insensitive. Higher scores are better: consecutive
+/// characters and characters at the start of a word score higher.
+pub fn fuzzy_score(query: &str, label: &str) -> Option<u32> {
+    let mut query = query.chars().flat_map(char::to_lowercase).peekable();
+    let mut score = 0;
+    let mut previous: Option<char> = None;
+    let mut consecutive = false;
+
+    for c in label.chars() {
+        let Some(&q) = query.peek() else { break };
+
+        if c.to_lowercase().next() == Some(q) {
+            query.next();
+
+            score += 1;
+            if consecutive {
+                score += 4;
+            }
+            if previous.is_none_or(|p| !p.is_alphanumeric()) {
+                score += 8;
+            }
+
+            consecutive = true;
+        } else {
+            consecutive = false;
+        }
+
+        previous = Some(c);
+    }
+
+    query.peek().is_none().then_some(score)
+}
+
+#[derive(Default)]
+struct Palette {
+    open: bool,
+    query: String,
+    cursor: usize,
+}
+
+impl Palette {
+    fn close(&mut self) {
+        *self = Self::default();
+    }
+
+    fn key<Id, Output>(
+        &mut self,
+        e: &web_sys::KeyboardEvent,
+        ids: &[Id],
+        output: &mut Output,
+        on_select: impl Fn(&mut Output, &Id),
+    ) -> bool {
+        if e.key() == "k" && (e.ctrl_key() || e.meta_key()) {
+            let open = !self.open;
+            self.close();
+            self.open = open;
+            return true;
+        }
+
+        if !self.open {
+            return false;
+        }
+
+        let cursor = self.cursor.min(ids.len().saturating_sub(1));
+
+        match e.key().as_str() {
+            "Escape" => self.close(),
+            "ArrowDown" => {
+                self.cursor = (cursor + 1).min(ids.len().saturating_sub(1))
+            }
+            "ArrowUp" => self.cursor = cursor.saturating_sub(1),
+            "Enter" => {
+                let Some(id) = ids.get(cursor) else {
+                    return false;
+                };
+
+                self.close();
+                on_select(output, id);
+            }
+            _ => return false,
+        }
+
+        true
+    }
+}
+
+/// A command palette, which searches `commands` and calls `on_select` with the
+/// [`Command::id`] of the chosen one.
+///
+/// * Ctrl-K (or cmd-K) anywhere on the page opens and closes the palette.
+/// * Typing filters the commands with [`fuzzy_score`], best matches first.
+/// * The arrow keys move the cursor, `Enter` runs the command under the
+///   cursor, and `Escape` closes the palette. Clicking a command also runs it.
+///
+/// The open state and query are local to the component, so the model must
+/// implement [`Default`] (see [`ravel::with_local`]). At most [`MAX_RESULTS`]
+/// matches are rendered. The item under the cursor has the `cursor` class.
+pub fn command_palette<'a, Id, OnSelect, Output>(
+    commands: &'a [Command<Id>],
+    on_select: OnSelect,
+) -> View!(Output, 'a)
+where
+    Id: 'static + Clone,
+    OnSelect: 'static + Clone + Fn(&mut Output, &Id),
+    Output: 'static + Default,
+{
+    with_local(Palette::default, move |cx, palette: &Palette| {
+        let mut matches: Vec<_> = commands
+            .iter()
+            .filter_map(|c| Some((fuzzy_score(&palette.query, &c.label)?, c)))
+            .collect();
+        matches.sort_by(|(a, _), (b, _)| b.cmp(a));
+        matches.truncate(MAX_RESULTS);
+
+        let ids: Rc<[Id]> = matches.iter().map(|(_, c)| c.id.clone()).collect();
+        let cursor = palette.cursor.min(ids.len().saturating_sub(1));
+        let open = palette.open;
+
+        cx.build(el::div((
+            Class("command-palette"),
+            Role("dialog"),
+            Hidden(!open),
+            on_window(Active(KeyDown), {
+                let ids = ids.clone();
+                let on_select = on_select.clone();
+                move |(output, palette): &mut (Output, Palette),
+                      e: web_sys::KeyboardEvent| {
+                    if palette.key(&e, &ids, output, &on_select) {
+                        e.prevent_default();
+                    }
+                }
+            }),
+            el::input((
+                Type("text"),
+                Placeholder("Search commands"),
+                controlled_value(palette.query.as_str()),
+                on(InputEvent, |(_, palette): &mut (Output, Palette), e| {
+                    if let Some(query) = target_value(&e) {
+                        palette.query = query;
+                        palette.cursor = 0;
+                    }
+                }),
+                input_state(move |cx, input| {
+                    if open {
+                        focus(input.element());
+                    }
+                    cx.build(())
+                }),
+            )),
+            el::ul((
+                Role("listbox"),
+                iter(matches, move |cx, i, (_, command)| {
+                    let ids = ids.clone();
+                    let on_select = on_select.clone();
+                    let select =
+                        move |(output, palette): &mut (Output, Palette),
+                              _: web_sys::MouseEvent| {
+                            palette.close();
+                            on_select(output, &ids[i]);
+                        };
+
+                    cx.build(el::li((
+                        Role("option"),
+                        Class((i == cursor).then_some("cursor")),
+                        on(Click, select),
+                        text(command.label.as_str()),
+                    )))
+                }),
+            )),
+        )))
+    })
+}
+
+/// Keeps focus in the search field while the palette is open.
+fn focus(element: &web_sys::Element) {
+    let active = gloo_utils::document().active_element();
+
+    if active.as_ref() != Some(element) {
+        if let Some(element) = element.dyn_ref::<web_sys::HtmlElement>() {
+            let _ = element.focus();
+        }
+    }
+}
@@ -0,0 +1,11 @@
+//! Reusable interactive widgets built from the lower level components.
+
+pub mod command_palette;
+pub mod select_list;
+pub mod split;
+pub mod table;
+
+pub use command_palette::command_palette;
+pub use select_list::select_list;
+pub use split::split;
+pub use table::table;
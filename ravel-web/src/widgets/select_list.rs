@@ -0,0 +1,228 @@
+//! A list supporting multiple selection with the mouse and keyboard.
+
+use std::{collections::BTreeSet, rc::Rc};
+
+use ravel::{with, State, Token};
+
+use crate::{
+    attr::{AriaMultiselectable, AriaSelected, Class, Role, Tabindex},
+    collections::iter,
+    el,
+    event::{on, Active, Click, KeyDown},
+    Cx, View, Web,
+};
+
+/// The selection state of a [`select_list`], stored in the model.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Selection<K> {
+    selected: BTreeSet<K>,
+    anchor: Option<K>,
+    cursor: Option<K>,
+}
+
+impl<K> Default for Selection<K> {
+    fn default() -> Self {
+        Self {
+            selected: BTreeSet::new(),
+            anchor: None,
+            cursor: None,
+        }
+    }
+}
+
+impl<K: Ord + Clone> Selection<K> {
+    /// An empty selection.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// The set of selected keys.
+    pub fn selected(&self) -> &BTreeSet<K> {
+        &self.selected
+    }
+
+    /// Whether a key is selected.
+    pub fn is_selected(&self, key: &K) -> bool {
+        self.selected.contains(key)
+    }
+
+    /// The key with keyboard focus, if any.
+    pub fn cursor(&self) -> Option<&K> {
+        self.cursor.as_ref()
+    }
+
+    /// Deselects everything.
+    pub fn clear(&mut self) {
+        self.selected.clear();
+        self.anchor = None;
+    }
+
+    /// Selects only the given key.
+    pub fn select_only(&mut self, key: K) {
+        self.selected.clear();
+        self.selected.insert(key.clone());
+        self.anchor = Some(key.clone());
+        self.cursor = Some(key);
+    }
+
+    /// Toggles whether the given key is selected, making it the new anchor for
+    /// range selection.
+    pub fn toggle(&mut self, key: K) {
+        if !self.selected.remove(&key) {
+            self.selected.insert(key.clone());
+        }
+        self.anchor = Some(key.clone());
+        self.cursor = Some(key);
+    }
+
+    /// Selects the keys between the anchor and `order[index]` (inclusive),
+    /// according to the display order of the list. If `extend` is `false`, the
+    /// rest of the selection is cleared first.
+    ///
+    /// Does nothing if `index` is out of bounds of `order`.
+    pub fn select_range(&mut self, order: &[K], index: usize, extend: bool) {
+        if index >= order.len() {
+            return;
+        }
+
+        let anchor = self
+            .anchor
+            .as_ref()
+            .and_then(|a| order.iter().position(|k| k == a))
+            .unwrap_or(index);
+
+        if !extend {
+            self.selected.clear();
+        }
+
+        let (lo, hi) = (anchor.min(index), anchor.max(index));
+        self.selected.extend(order[lo..=hi].iter().cloned());
+
+        if self.anchor.is_none() {
+            self.anchor = Some(order[anchor].clone());
+        }
+        self.cursor = Some(order[index].clone());
+    }
+
+    fn click(&mut self, order: &[K], index: usize, e: &web_sys::MouseEvent) {
+        let modifier = e.ctrl_key() || e.meta_key();
+
+        if e.shift_key() {
+            self.select_range(order, index, modifier);
+        } else if modifier {
+            self.toggle(order[index].clone());
+        } else {
+            self.select_only(order[index].clone());
+        }
+    }
+
+    fn key(&mut self, order: &[K], e: &web_sys::KeyboardEvent) -> bool {
+        if order.is_empty() {
+            return false;
+        }
+
+        let modifier = e.ctrl_key() || e.meta_key();
+        let last = order.len() - 1;
+        let cursor = self
+            .cursor
+            .as_ref()
+            .and_then(|c| order.iter().position(|k| k == c));
+
+        let target = match e.key().as_str() {
+            "ArrowDown" => cursor.map_or(0, |i| (i + 1).min(last)),
+            "ArrowUp" => cursor.map_or(0, |i| i.saturating_sub(1)),
+            "Home" => 0,
+            "End" => last,
+            " " => {
+                let index = cursor.unwrap_or(0);
+                self.toggle(order[index].clone());
+                return true;
+            }
+            "a" if modifier => {
+                self.selected.extend(order.iter().cloned());
+                return true;
+            }
+            _ => return false,
+        };
+
+        if e.shift_key() {
+            self.select_range(order, target, modifier);
+        } else if modifier {
+            self.cursor = Some(order[target].clone());
+        } else {
+            self.select_only(order[target].clone());
+        }
+
+        true
+    }
+}
+
+/// A list of `items` supporting multiple selection, with the selection stored
+/// in the model.
+///
+/// * Click selects a single item, ctrl-click (or cmd-click) toggles an item,
+///   and shift-click selects a range.
+/// * When focused, the arrow keys, `Home` and `End` move the selection (or
+///   just the cursor, with ctrl), shift extends it as a range, space toggles
+///   the item under the cursor, and ctrl-A selects everything.
+///
+/// `key` identifies each item, `lens` locates the [`Selection`] in the model,
+/// and `render_item` renders the content of an item given whether it is
+/// selected. Selected items have the `selected` class, and the item under the
+/// keyboard cursor has the `cursor` class.
+pub fn select_list<'a, T, K, Key, Lens, RenderItem, S, Output>(
+    items: &'a [T],
+    selection: &'a Selection<K>,
+    key: Key,
+    lens: Lens,
+    render_item: RenderItem,
+) -> View!(Output, 'a)
+where
+    K: 'static + Ord + Clone,
+    Key: 'a + Fn(&T) -> K,
+    Lens: 'static + Clone + Fn(&mut Output) -> &mut Selection<K>,
+    RenderItem: 'a + Fn(Cx<S, Web>, &T, bool) -> Token<S>,
+    S: 'static + State<Output>,
+    Output: 'static,
+{
+    let order: Rc<[K]> = items.iter().map(&key).collect();
+
+    el::ul((
+        Role("listbox"),
+        Tabindex("0"),
+        AriaMultiselectable(true),
+        on(Active(KeyDown), {
+            let order = order.clone();
+            let lens = lens.clone();
+            move |output: &mut Output, e: web_sys::KeyboardEvent| {
+                if lens(output).key(&order, &e) {
+                    e.prevent_default();
+                }
+            }
+        }),
+        iter(items, move |cx, i, item| {
+            let k = key(item);
+            let selected = selection.is_selected(&k);
+            let cursor = selection.cursor() == Some(&k);
+
+            let order = order.clone();
+            let lens = lens.clone();
+
+            cx.build(el::li((
+                Role("option"),
+                AriaSelected(selected),
+                Class((
+                    selected.then_some("selected"),
+                    cursor.then_some("cursor"),
+                )),
+                on(
+                    Click,
+                    move |output: &mut Output, e: web_sys::MouseEvent| {
+                        lens(output).click(&order, i, &e);
+                    },
+                ),
+                with(|cx| render_item(cx, item, selected)),
+            )))
+        }),
+    ))
+}
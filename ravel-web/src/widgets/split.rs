@@ -0,0 +1,202 @@
+//! Two panes separated by a draggable divider.
+
+use ravel::{adapt_ref, with_local, Builder, State};
+use web_sys::wasm_bindgen::JsCast as _;
+
+use crate::{
+    attr::{
+        AriaOrientation, AriaValuenow, Class, CloneString, Role, Style,
+        Tabindex,
+    },
+    el,
+    event::{
+        on, Active, KeepLatest, KeyDown, PointerDown, PointerMove, PointerUp,
+    },
+    View, Web,
+};
+
+/// The amount the divider of a [`split`] moves for each arrow key press, as a
+/// fraction of the whole.
+pub const KEYBOARD_STEP: f64 = 0.05;
+
+/// How the panes of a [`split`] are arranged.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum Direction {
+    /// Side by side, with a vertical divider.
+    Horizontal,
+    /// One above the other, with a horizontal divider.
+    Vertical,
+}
+
+impl Direction {
+    fn class(self) -> &'static str {
+        match self {
+            Direction::Horizontal => "split-horizontal",
+            Direction::Vertical => "split-vertical",
+        }
+    }
+}
+
+#[derive(Default)]
+struct Split {
+    ratio: f64,
+    dragging: bool,
+}
+
+impl Split {
+    fn set_ratio(&mut self, ratio: f64) {
+        self.ratio = ratio.clamp(0., 1.);
+    }
+
+    fn key(&mut self, e: &web_sys::KeyboardEvent) -> bool {
+        match e.key().as_str() {
+            "ArrowLeft" | "ArrowUp" => {
+                self.set_ratio(self.ratio - KEYBOARD_STEP)
+            }
+            "ArrowRight" | "ArrowDown" => {
+                self.set_ratio(self.ratio + KEYBOARD_STEP)
+            }
+            "Home" => self.set_ratio(0.),
+            "End" => self.set_ratio(1.),
+            _ => return false,
+        }
+
+        true
+    }
+
+    fn start_drag(&mut self, e: &web_sys::PointerEvent) {
+        let Some(separator) = separator(e) else {
+            return;
+        };
+
+        self.dragging = true;
+        let _ = separator.set_pointer_capture(e.pointer_id());
+    }
+
+    fn drag_to(&mut self, direction: Direction, e: &web_sys::PointerEvent) {
+        if !self.dragging {
+            return;
+        }
+
+        let Some(container) = separator(e).and_then(|s| s.parent_element())
+        else {
+            return;
+        };
+
+        let rect = container.get_bounding_client_rect();
+        let (position, start, size) = match direction {
+            Direction::Horizontal => {
+                (f64::from(e.client_x()), rect.left(), rect.width())
+            }
+            Direction::Vertical => {
+                (f64::from(e.client_y()), rect.top(), rect.height())
+            }
+        };
+
+        if size > 0. {
+            self.set_ratio((position - start) / size);
+        }
+    }
+}
+
+fn separator(e: &web_sys::PointerEvent) -> Option<web_sys::Element> {
+    e.target()
+        .and_then(|t| t.dyn_into::<web_sys::Element>().ok())
+        .and_then(|t| t.closest(".split-separator").ok().flatten())
+}
+
+/// Two panes, `a` and `b`, separated by a divider which can be dragged with the
+/// pointer to resize them.
+///
+/// `initial_ratio` is the fraction of the space initially given to `a`. The
+/// divider is focusable, and can also be moved with the arrow keys (by
+/// [`KEYBOARD_STEP`]), `Home` and `End`.
+///
+/// The ratio is local to the component, so the model must implement
+/// [`Default`] (see [`ravel::with_local`]). The container has the `split` class
+/// and either `split-horizontal` or `split-vertical`, the panes have the
+/// `split-pane` class, and the divider has the `split-separator` class, as well
+/// as `dragging` while it is being dragged. Only the layout of the panes is
+/// styled inline, so the divider should be given a size with CSS.
+pub fn split<A, B, Output>(
+    direction: Direction,
+    initial_ratio: f64,
+    a: A,
+    b: B,
+) -> View!(Output)
+where
+    A: Builder<Web>,
+    A::State: 'static + State<Output>,
+    B: Builder<Web>,
+    B::State: 'static + State<Output>,
+    Output: 'static + Default,
+{
+    let init = move || {
+        let mut split = Split {
+            ratio: 0.,
+            dragging: false,
+        };
+        split.set_ratio(initial_ratio);
+        split
+    };
+
+    with_local(init, move |cx, split: &Split| {
+        let (flex_direction, orientation) = match direction {
+            Direction::Horizontal => ("row", "vertical"),
+            Direction::Vertical => ("column", "horizontal"),
+        };
+        let pane = |ratio: f64| {
+            Style(CloneString(format!("flex: {ratio} 1 0; overflow: auto")))
+        };
+
+        cx.build(el::div((
+            Class(("split", direction.class())),
+            Style(CloneString(format!(
+                "display: flex; flex-direction: {flex_direction}"
+            ))),
+            el::div((
+                Class("split-pane"),
+                pane(split.ratio),
+                adapt_ref(a, |(output, _): &mut (Output, Split)| output),
+            )),
+            el::div((
+                Class((
+                    "split-separator",
+                    split.dragging.then_some("dragging"),
+                )),
+                Role("separator"),
+                Tabindex("0"),
+                AriaOrientation(orientation),
+                AriaValuenow((split.ratio * 100.).round() as u32),
+                (
+                    on(
+                        Active(KeyDown),
+                        |(_, split): &mut (Output, Split), e| {
+                            if split.key(&e) {
+                                e.prevent_default();
+                            }
+                        },
+                    ),
+                    on(PointerDown, |(_, split): &mut (Output, Split), e| {
+                        split.start_drag(&e)
+                    }),
+                    on(
+                        PointerMove,
+                        move |(_, split): &mut (Output, Split), e| {
+                            split.drag_to(direction, &e)
+                        },
+                    )
+                    .policy(KeepLatest),
+                    on(PointerUp, |(_, split): &mut (Output, Split), _| {
+                        split.dragging = false
+                    }),
+                ),
+            )),
+            el::div((
+                Class("split-pane"),
+                pane(1. - split.ratio),
+                adapt_ref(b, |(output, _): &mut (Output, Split)| output),
+            )),
+        )))
+    })
+}
@@ -0,0 +1,273 @@
+//! A data table with resizable and reorderable columns.
+
+use std::hash::Hash;
+
+use ravel::{with, State, Token};
+use web_sys::wasm_bindgen::JsCast as _;
+
+use crate::{
+    attr::{Class, CloneString, Style},
+    collections::keyed,
+    el,
+    event::{on, KeepLatest, PointerDown, PointerMove, PointerUp},
+    Cx, View, Web,
+};
+
+/// The minimum width of a column, in pixels.
+pub const MIN_COLUMN_WIDTH: f64 = 30.0;
+
+/// A column of a [`table`].
+#[derive(Clone, Debug, PartialEq)]
+pub struct Column<K> {
+    /// Identifies the column.
+    pub key: K,
+    /// The width of the column, in pixels.
+    pub width: f64,
+}
+
+/// The column layout of a [`table`], stored in the model.
+///
+/// This includes the order and width of each column, as well as any resize or
+/// reorder interaction in progress.
+#[derive(Clone, Debug, PartialEq)]
+pub struct TableLayout<K> {
+    columns: Vec<Column<K>>,
+    drag: Option<Drag>,
+}
+
+#[derive(Copy, Clone, Debug, PartialEq)]
+enum Drag {
+    Resize {
+        index: usize,
+        start_x: i32,
+        start_width: f64,
+    },
+    Move {
+        index: usize,
+        start_x: i32,
+        offset: i32,
+    },
+}
+
+impl<K> TableLayout<K> {
+    /// A layout with the given columns, in order.
+    pub fn new(columns: impl IntoIterator<Item = Column<K>>) -> Self {
+        Self {
+            columns: columns.into_iter().collect(),
+            drag: None,
+        }
+    }
+
+    /// The columns, in display order.
+    pub fn columns(&self) -> &[Column<K>] {
+        &self.columns
+    }
+
+    /// Sets the width of a column, in pixels.
+    pub fn set_width(&mut self, index: usize, width: f64) {
+        self.columns[index].width = width.max(MIN_COLUMN_WIDTH);
+    }
+
+    /// Moves a column to a new position.
+    pub fn move_column(&mut self, from: usize, to: usize) {
+        let column = self.columns.remove(from);
+        self.columns.insert(to, column);
+    }
+
+    fn offset(&self, index: usize) -> i32 {
+        match self.drag {
+            Some(Drag::Move {
+                index: i, offset, ..
+            }) if i == index => offset,
+            _ => 0,
+        }
+    }
+
+    fn start_resize(&mut self, index: usize, e: &web_sys::PointerEvent) {
+        self.drag = Some(Drag::Resize {
+            index,
+            start_x: e.client_x(),
+            start_width: self.columns[index].width,
+        });
+        capture(e, ".resize-handle");
+    }
+
+    fn start_move(&mut self, index: usize, e: &web_sys::PointerEvent) {
+        self.drag = Some(Drag::Move {
+            index,
+            start_x: e.client_x(),
+            offset: 0,
+        });
+        capture(e, "th");
+    }
+
+    fn drag_to(&mut self, e: &web_sys::PointerEvent) {
+        match &mut self.drag {
+            None => {}
+            Some(Drag::Resize {
+                index,
+                start_x,
+                start_width,
+            }) => {
+                let (index, width) =
+                    (*index, *start_width + f64::from(e.client_x() - *start_x));
+                self.set_width(index, width);
+            }
+            Some(Drag::Move {
+                start_x, offset, ..
+            }) => *offset = e.client_x() - *start_x,
+        }
+    }
+
+    fn drop(&mut self) {
+        let Some(Drag::Move { index, offset, .. }) = self.drag.take() else {
+            return;
+        };
+
+        let mut offset = f64::from(offset);
+        let mut to = index;
+
+        while to + 1 < self.columns.len()
+            && offset > self.columns[to + 1].width / 2.
+        {
+            offset -= self.columns[to + 1].width;
+            to += 1;
+        }
+        while to > 0 && -offset > self.columns[to - 1].width / 2. {
+            offset += self.columns[to - 1].width;
+            to -= 1;
+        }
+
+        self.move_column(index, to);
+    }
+}
+
+/// Captures the pointer on the closest ancestor of the event target matching
+/// `selector`, so that the interaction continues outside of it.
+fn capture(e: &web_sys::PointerEvent, selector: &str) {
+    let Some(target) = e
+        .target()
+        .and_then(|t| t.dyn_into::<web_sys::Element>().ok())
+        .and_then(|t| t.closest(selector).ok().flatten())
+    else {
+        return;
+    };
+
+    let _ = target.set_pointer_capture(e.pointer_id());
+}
+
+fn is_resize_handle(e: &web_sys::PointerEvent) -> bool {
+    e.target()
+        .and_then(|t| t.dyn_into::<web_sys::Element>().ok())
+        .is_some_and(|t| t.class_list().contains("resize-handle"))
+}
+
+/// A table of `rows` with a [`TableLayout`] of columns stored in the model.
+///
+/// Columns can be resized by dragging the `resize-handle` element at the edge
+/// of each header, and reordered by dragging the header itself. The header
+/// being dragged has the `dragging` class.
+///
+/// `lens` locates the [`TableLayout`] in the model. `row_key` identifies each
+/// row, `render_header` renders the content of a column header, and
+/// `render_cell` renders the content of the cell for a column and row. Both
+/// rows and columns are diffed by key.
+pub fn table<
+    'a,
+    K,
+    Row,
+    RowKey,
+    RK,
+    Lens,
+    RenderHeader,
+    SH,
+    RenderCell,
+    SC,
+    Output,
+>(
+    layout: &'a TableLayout<K>,
+    rows: &'a [Row],
+    lens: Lens,
+    row_key: RowKey,
+    render_header: RenderHeader,
+    render_cell: RenderCell,
+) -> View!(Output, 'a)
+where
+    K: 'static + Clone + Hash + Eq,
+    RowKey: 'a + Fn(&Row) -> RK,
+    RK: 'static + Hash + Eq,
+    Lens: 'static + Clone + Fn(&mut Output) -> &mut TableLayout<K>,
+    RenderHeader: 'a + Fn(Cx<SH, Web>, &K) -> Token<SH>,
+    SH: 'static + State<Output>,
+    RenderCell: 'a + Fn(Cx<SC, Web>, &K, &Row) -> Token<SC>,
+    SC: 'static + State<Output>,
+    Output: 'static,
+{
+    let dragging = layout.drag.map(|drag| match drag {
+        Drag::Resize { index, .. } | Drag::Move { index, .. } => index,
+    });
+
+    el::table((
+        Class("data-table"),
+        el::thead(el::tr(keyed(
+            layout.columns.iter().enumerate(),
+            |(_, column)| column.key.clone(),
+            move |cx, (index, column)| {
+                let pointer_down = lens.clone();
+                let pointer_move = lens.clone();
+                let pointer_up = lens.clone();
+                let resize = lens.clone();
+
+                cx.build(el::th((
+                    Style(CloneString(format!(
+                        "width: {w}px; min-width: {w}px; transform: \
+                         translateX({}px)",
+                        layout.offset(index),
+                        w = column.width,
+                    ))),
+                    Class((dragging == Some(index)).then_some("dragging")),
+                    on(PointerDown, move |output: &mut Output, e| {
+                        if !is_resize_handle(&e) {
+                            pointer_down(output).start_move(index, &e);
+                        }
+                    }),
+                    on(PointerMove, move |output: &mut Output, e| {
+                        pointer_move(output).drag_to(&e);
+                    })
+                    .policy(KeepLatest),
+                    on(PointerUp, move |output: &mut Output, _| {
+                        pointer_up(output).drop()
+                    }),
+                    with(|cx| render_header(cx, &column.key)),
+                    el::span((
+                        Class("resize-handle"),
+                        on(PointerDown, move |output: &mut Output, e| {
+                            resize(output).start_resize(index, &e);
+                        }),
+                    )),
+                )))
+            },
+        ))),
+        el::tbody(keyed(
+            rows,
+            move |row: &&Row| row_key(row),
+            move |cx, row| {
+                let render_cell = &render_cell;
+
+                cx.build(el::tr(keyed(
+                    layout.columns.iter(),
+                    |column| column.key.clone(),
+                    move |cx, column| {
+                        cx.build(el::td((
+                            Style(CloneString(format!(
+                                "width: {w}px; min-width: {w}px",
+                                w = column.width,
+                            ))),
+                            with(|cx| render_cell(cx, &column.key, row)),
+                        )))
+                    },
+                )))
+            },
+        )),
+    ))
+}
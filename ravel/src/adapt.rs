@@ -1,6 +1,6 @@
 use std::marker::PhantomData;
 
-use crate::{Builder, CxRep, State};
+use crate::{Builder, CxRep, MemSize, State};
 
 /// A [`Builder`] created from [`adapt`].
 pub struct Adapt<B, F, S, Output> {
@@ -71,6 +71,12 @@ where
     }
 }
 
+impl<S: MemSize, F> MemSize for AdaptState<S, F> {
+    fn heap_size(&self) -> usize {
+        self.inner.heap_size()
+    }
+}
+
 /// Adapts a [`Builder`] so that its [`State`] is compatible with a different
 /// `Output` type.
 ///
@@ -0,0 +1,103 @@
+use std::{
+    any::{Any, TypeId},
+    cell::RefCell,
+    rc::Rc,
+};
+
+use crate::{Builder, Cx, CxRep, MemSize, State};
+
+thread_local! {
+    static STACK: RefCell<Vec<(TypeId, Rc<dyn Any>)>> =
+        const { RefCell::new(Vec::new()) };
+}
+
+fn scope<T: 'static, R>(value: &Rc<T>, f: impl FnOnce() -> R) -> R {
+    struct Pop;
+
+    impl Drop for Pop {
+        fn drop(&mut self) {
+            STACK.with_borrow_mut(|stack| stack.pop());
+        }
+    }
+
+    STACK.with_borrow_mut(|stack| {
+        stack.push((TypeId::of::<T>(), value.clone()))
+    });
+    let _pop = Pop;
+    f()
+}
+
+/// A [`Builder`] created from [`provide_context`].
+pub struct ProvideContext<T, B> {
+    value: T,
+    body: B,
+}
+
+impl<R: CxRep, T: 'static, B: Builder<R>> Builder<R> for ProvideContext<T, B> {
+    type State = ContextState<T, B::State>;
+
+    fn build(self, cx: R::BuildCx<'_>) -> Self::State {
+        let value = Rc::new(self.value);
+        let body = self.body;
+        let inner = scope(&value, || body.build(cx));
+
+        ContextState { value, inner }
+    }
+
+    fn rebuild(self, cx: R::RebuildCx<'_>, state: &mut Self::State) {
+        state.value = Rc::new(self.value);
+        let body = self.body;
+        scope(&state.value, || body.rebuild(cx, &mut state.inner))
+    }
+}
+
+/// The state of a [`ProvideContext`].
+pub struct ContextState<T, S> {
+    value: Rc<T>,
+    inner: S,
+}
+
+impl<T: 'static, S, Output> State<Output> for ContextState<T, S>
+where
+    S: State<Output>,
+{
+    fn run(&mut self, output: &mut Output) {
+        self.inner.run(output)
+    }
+}
+
+impl<T: MemSize, S: MemSize> MemSize for ContextState<T, S> {
+    /// Counts the provided value, which is shared with descendants while they
+    /// are built, but owned by the provider.
+    fn heap_size(&self) -> usize {
+        T::mem_size(&self.value) + self.inner.heap_size()
+    }
+}
+
+/// Makes `value` available to every component in `body` with
+/// [`use_context`], while they are built or rebuilt.
+///
+/// This saves passing shared data, such as a theme, through every component
+/// function in between. The value is replaced on every rebuild, and an inner
+/// `provide_context` with the same type shadows an outer one.
+pub fn provide_context<T: 'static, B>(
+    value: T,
+    body: B,
+) -> ProvideContext<T, B> {
+    ProvideContext { value, body }
+}
+
+/// Looks up the value of type `T` from the innermost enclosing
+/// [`provide_context`], if there is one.
+///
+/// This takes a [`Cx`] to make sure it is only called while building or
+/// rebuilding, for example inside a [`with`](crate::with) callback.
+pub fn use_context<T: 'static, S, R: CxRep>(_: &Cx<S, R>) -> Option<Rc<T>> {
+    STACK.with_borrow(|stack| {
+        let (_, value) = stack
+            .iter()
+            .rev()
+            .find(|(id, _)| *id == TypeId::of::<T>())?;
+        value.clone().downcast().ok()
+    })
+}
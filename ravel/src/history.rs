@@ -0,0 +1,169 @@
+use std::{
+    collections::VecDeque,
+    ops::{Deref, DerefMut},
+};
+
+use crate::{adapt, Adapt, MemSize, State, Thunk, ThunkResult};
+
+/// A model with undo and redo.
+///
+/// This wraps the model passed to the event loop, and dereferences to it, so
+/// event handlers update it as usual. Each time it is [committed](Self::commit)
+/// with changes, a snapshot of the previous version is kept, which
+/// [`undo`](Self::undo) restores. Components wrapped in [`record_history`]
+/// commit after their handlers have run, so that each frame's changes are
+/// undone together.
+///
+/// Snapshots are clones of the whole model, so large models should share
+/// their unchanged parts, for example with [`Rc`](std::rc::Rc).
+#[derive(Clone, Debug)]
+pub struct History<Model> {
+    present: Model,
+    /// The present as of the last commit.
+    saved: Model,
+    past: VecDeque<Model>,
+    future: Vec<Model>,
+    limit: usize,
+}
+
+impl<Model: Clone + PartialEq> History<Model> {
+    /// A history starting at `model`, with nothing to undo.
+    pub fn new(model: Model) -> Self {
+        Self {
+            saved: model.clone(),
+            present: model,
+            past: VecDeque::new(),
+            future: Vec::new(),
+            limit: usize::MAX,
+        }
+    }
+
+    /// Keeps at most `limit` versions to undo, forgetting the oldest ones.
+    pub fn with_limit(mut self, limit: usize) -> Self {
+        self.limit = limit;
+        self.trim();
+        self
+    }
+
+    /// Records the changes to the model since the last commit, if any, as a
+    /// step which can be undone. This discards the versions which could be
+    /// redone.
+    pub fn commit(&mut self) {
+        if self.present == self.saved {
+            return;
+        }
+
+        let previous = std::mem::replace(&mut self.saved, self.present.clone());
+        self.past.push_back(previous);
+        self.future.clear();
+        self.trim();
+    }
+
+    /// Restores the version before the last step, after committing any
+    /// changes. Returns whether there was a step to undo.
+    pub fn undo(&mut self) -> bool {
+        self.commit();
+        let Some(previous) = self.past.pop_back() else {
+            return false;
+        };
+
+        self.present = previous.clone();
+        self.future
+            .push(std::mem::replace(&mut self.saved, previous));
+        true
+    }
+
+    /// Restores the version undone by the last [`undo`](Self::undo), after
+    /// committing any changes. Returns whether there was a step to redo.
+    ///
+    /// Changing the model after undoing discards the versions which could be
+    /// redone.
+    pub fn redo(&mut self) -> bool {
+        self.commit();
+        let Some(next) = self.future.pop() else {
+            return false;
+        };
+
+        self.present = next.clone();
+        self.past
+            .push_back(std::mem::replace(&mut self.saved, next));
+        true
+    }
+
+    /// Whether [`undo`](Self::undo) would restore a previous version.
+    pub fn can_undo(&self) -> bool {
+        !self.past.is_empty() || self.present != self.saved
+    }
+
+    /// Whether [`redo`](Self::redo) would restore a version.
+    pub fn can_redo(&self) -> bool {
+        !self.future.is_empty() && self.present == self.saved
+    }
+
+    /// Forgets every version other than the present one.
+    pub fn clear(&mut self) {
+        self.commit();
+        self.past.clear();
+        self.future.clear();
+    }
+
+    /// The present version of the model, without its history.
+    pub fn into_inner(self) -> Model {
+        self.present
+    }
+
+    fn trim(&mut self) {
+        while self.past.len() > self.limit {
+            self.past.pop_front();
+        }
+    }
+}
+
+impl<Model> Deref for History<Model> {
+    type Target = Model;
+
+    fn deref(&self) -> &Model {
+        &self.present
+    }
+}
+
+impl<Model> DerefMut for History<Model> {
+    fn deref_mut(&mut self) -> &mut Model {
+        &mut self.present
+    }
+}
+
+impl<Model: MemSize> MemSize for History<Model> {
+    fn heap_size(&self) -> usize {
+        self.present.heap_size()
+            + self.saved.heap_size()
+            + self.past.heap_size()
+            + self.future.heap_size()
+    }
+}
+
+/// Adapts a component whose events update a [`History`], so that it is
+/// [committed](History::commit) each time the component is run.
+///
+/// Wrapping the root of the application in this makes each frame in which the
+/// model changed a single step of undo, including every event handled in it.
+/// Handlers can call [`History::undo`] and [`History::redo`] directly.
+#[allow(clippy::type_complexity)]
+pub fn record_history<B, S, Model>(
+    builder: B,
+) -> Adapt<
+    B,
+    impl 'static + FnMut(Thunk<S>, &mut History<Model>) -> ThunkResult<S>,
+    S,
+    History<Model>,
+>
+where
+    S: State<History<Model>>,
+    Model: 'static + Clone + PartialEq,
+{
+    adapt(builder, |thunk, history: &mut History<Model>| {
+        let result = thunk.run(history);
+        history.commit();
+        result
+    })
+}
@@ -0,0 +1,90 @@
+use crate::{adapt_ref, Adapt, State, Thunk, ThunkResult};
+
+/// A part of a model of type `M`, which can be borrowed both immutably (to
+/// build a view of it) and mutably (to handle its events).
+///
+/// Lenses are usually created with [`lens!`](crate::lens!).
+pub trait Lens<M>: 'static {
+    /// The type of the part.
+    type Target;
+
+    /// Borrows the part of `model`.
+    fn get<'m>(&self, model: &'m M) -> &'m Self::Target;
+
+    /// Mutably borrows the part of `model`.
+    fn get_mut<'m>(&self, model: &'m mut M) -> &'m mut Self::Target;
+}
+
+/// A [`Lens`] onto a field of a model, created with [`lens!`](crate::lens!).
+pub struct FieldLens<M, T> {
+    get: fn(&M) -> &T,
+    get_mut: fn(&mut M) -> &mut T,
+}
+
+impl<M, T> FieldLens<M, T> {
+    /// A lens which borrows with `get` and `get_mut`, which should borrow the
+    /// same part.
+    pub const fn new(get: fn(&M) -> &T, get_mut: fn(&mut M) -> &mut T) -> Self {
+        Self { get, get_mut }
+    }
+}
+
+impl<M, T> Clone for FieldLens<M, T> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<M, T> Copy for FieldLens<M, T> {}
+
+impl<M: 'static, T: 'static> Lens<M> for FieldLens<M, T> {
+    type Target = T;
+
+    fn get<'m>(&self, model: &'m M) -> &'m T {
+        (self.get)(model)
+    }
+
+    fn get_mut<'m>(&self, model: &'m mut M) -> &'m mut T {
+        (self.get_mut)(model)
+    }
+}
+
+/// Creates a [`FieldLens`] onto a (possibly nested) field of a model type,
+/// for example `lens!(Model, settings.theme)`.
+#[macro_export]
+macro_rules! lens {
+    ($model:ty, $($field:tt).+) => {
+        $crate::FieldLens::new(
+            |model: &$model| &model.$($field).+,
+            |model: &mut $model| &mut model.$($field).+,
+        )
+    };
+}
+
+/// Builds a component against a part of the model, selected by `lens`.
+///
+/// `view` is called with the part of `model`, and the component it returns
+/// handles its events with the same part of the `Output` model. This lets
+/// components be written against their own small model type, and composed
+/// into a larger one:
+///
+/// ```ignore
+/// struct Model { counter: u32, ... }
+///
+/// // A component whose events update a `u32`.
+/// fn counter(count: &u32) -> impl Builder<R, State = impl State<u32>> { ... }
+///
+/// lens(model, lens!(Model, counter), counter)
+/// ```
+pub fn lens<M, L, F, B, S>(
+    model: &M,
+    lens: L,
+    view: F,
+) -> Adapt<B, impl 'static + FnMut(Thunk<S>, &mut M) -> ThunkResult<S>, S, M>
+where
+    L: Lens<M>,
+    F: FnOnce(&L::Target) -> B,
+    S: State<L::Target>,
+{
+    adapt_ref(view(lens.get(model)), move |model| lens.get_mut(model))
+}
@@ -3,17 +3,55 @@
 //!
 //! This crate contains shared infrastructure which is platform agnostic. To
 //! build an application, you'll need a backend crate such as `ravel-web`.
+//!
+//! # Backend protocol
+//!
+//! A backend is a [`CxRep`], whose contexts carry whatever a component needs to
+//! create and update its platform objects (for example, a parent DOM element
+//! and insertion point). Components for the backend implement [`Builder`] for
+//! it, and their states implement [`State`].
+//!
+//! This is the surface third party backends depend on, and it only changes in
+//! a new major version:
+//!
+//! * [`CxRep`], [`Builder`] and [`State`].
+//! * [`Builder`] implementations for tuples and arrays, which build, rebuild
+//!   and run their elements in order.
+//! * The backend agnostic combinators: [`with`] (with [`Cx`] and [`Token`]),
+//!   [`with_local`], [`with_local_mut`], [`with_local_keyed`],
+//!   [`with_stored`], [`adapt`], [`adapt_ref`], [`record_history`] (with
+//!   [`History`]), [`lens`](fn@lens) (with [`Lens`]), [`memo`], [`tracked`],
+//!   [`provide_context`] (with [`use_context`]), and [`mvu::dispatch`] and
+//!   [`mvu::map`] (with [`mvu::Messages`]).
+//!
+//! The `ravel-compliance` crate contains a test suite which backends can run
+//! to check that these behave as expected with their contexts.
 use std::{marker::PhantomData, mem::MaybeUninit};
 
 use paste::paste;
 
 mod adapt;
 mod any;
+mod context;
+mod history;
+mod lens;
 mod local;
+mod mem;
+mod memo;
+pub mod mvu;
+mod revision;
+mod stored;
 
 pub use adapt::*;
 pub use any::*;
+pub use context::*;
+pub use history::*;
+pub use lens::*;
 pub use local::*;
+pub use mem::*;
+pub use memo::*;
+pub use revision::*;
+pub use stored::*;
 
 /// A dummy type which typically represents a "backend".
 pub trait CxRep {
@@ -61,6 +99,83 @@ tuple_builder!(a, b, c, d, e);
 tuple_builder!(a, b, c, d, e, f);
 tuple_builder!(a, b, c, d, e, f, g);
 tuple_builder!(a, b, c, d, e, f, g, h);
+tuple_builder!(a, b, c, d, e, f, g, h, i);
+tuple_builder!(a, b, c, d, e, f, g, h, i, j);
+tuple_builder!(a, b, c, d, e, f, g, h, i, j, k);
+tuple_builder!(a, b, c, d, e, f, g, h, i, j, k, l);
+tuple_builder!(a, b, c, d, e, f, g, h, i, j, k, l, m);
+tuple_builder!(a, b, c, d, e, f, g, h, i, j, k, l, m, n);
+tuple_builder!(a, b, c, d, e, f, g, h, i, j, k, l, m, n, o);
+tuple_builder!(a, b, c, d, e, f, g, h, i, j, k, l, m, n, o, p);
+
+/// Arrays build, rebuild and run their elements in order, like tuples.
+impl<R: CxRep, B: Builder<R>, const N: usize> Builder<R> for [B; N] {
+    type State = [B::State; N];
+
+    fn build(self, cx: R::BuildCx<'_>) -> Self::State {
+        self.map(|b| b.build(cx))
+    }
+
+    fn rebuild(self, cx: R::RebuildCx<'_>, state: &mut Self::State) {
+        for (b, state) in self.into_iter().zip(state) {
+            b.rebuild(cx, state)
+        }
+    }
+}
+
+/// Combines any number of [`Builder`]s into one, in order.
+///
+/// Tuples are only [`Builder`]s up to a fixed arity. This macro groups its
+/// arguments into nested tuples of at most that size, so that arbitrarily many
+/// siblings can be built without nesting them by hand. The nesting is a tree
+/// of logarithmic depth, which keeps the resulting state types manageable.
+///
+/// ```ignore
+/// el::ul(seq![
+///     el::li("one"),
+///     el::li("two"),
+///     // ...
+///     el::li("twenty"),
+/// ])
+/// ```
+#[macro_export]
+macro_rules! seq {
+    // A full group at the start of a new one can be taken in one step, which
+    // keeps the recursion depth proportional to the number of groups.
+    (@group [$($groups:tt)*] []
+        $a:tt $b:tt $c:tt $d:tt $e:tt $f:tt $g:tt $h:tt
+        $i:tt $j:tt $k:tt $l:tt $m:tt $n:tt $o:tt $p:tt $($rest:tt)*
+    ) => {
+        $crate::seq!(
+            @group [
+                $($groups)*
+                ($a, $b, $c, $d, $e, $f, $g, $h, $i, $j, $k, $l, $m, $n, $o, $p,)
+            ] []
+            $($rest)*
+        )
+    };
+    (@group [$($groups:tt)*] [$($current:tt)*] $next:tt $($rest:tt)*) => {
+        $crate::seq!(@group [$($groups)*] [$($current)* $next] $($rest)*)
+    };
+    (@group [$($groups:tt)*] []) => {
+        $crate::seq!(@done $($groups)*)
+    };
+    (@group [$($groups:tt)*] [$($current:tt)+]) => {
+        $crate::seq!(@done $($groups)* ($($current,)+))
+    };
+    (@done) => {
+        ()
+    };
+    (@done $group:tt) => {
+        $group
+    };
+    (@done $($groups:tt)+) => {
+        $crate::seq!(@group [] [] $($groups)+)
+    };
+    ($($builder:expr),* $(,)?) => {
+        $crate::seq!(@group [] [] $($builder)*)
+    };
+}
 
 /// Trait for the state of a [`Builder`].
 pub trait State<Output>: AsAny {
@@ -95,6 +210,22 @@ tuple_state!(a, b, c, d, e);
 tuple_state!(a, b, c, d, e, f);
 tuple_state!(a, b, c, d, e, f, g);
 tuple_state!(a, b, c, d, e, f, g, h);
+tuple_state!(a, b, c, d, e, f, g, h, i);
+tuple_state!(a, b, c, d, e, f, g, h, i, j);
+tuple_state!(a, b, c, d, e, f, g, h, i, j, k);
+tuple_state!(a, b, c, d, e, f, g, h, i, j, k, l);
+tuple_state!(a, b, c, d, e, f, g, h, i, j, k, l, m);
+tuple_state!(a, b, c, d, e, f, g, h, i, j, k, l, m, n);
+tuple_state!(a, b, c, d, e, f, g, h, i, j, k, l, m, n, o);
+tuple_state!(a, b, c, d, e, f, g, h, i, j, k, l, m, n, o, p);
+
+impl<S: State<O>, O, const N: usize> State<O> for [S; N] {
+    fn run(&mut self, output: &mut O) {
+        for state in self {
+            state.run(output)
+        }
+    }
+}
 
 /// Context provided by [`with`].
 pub struct Cx<'cx, 'state, State, R: CxRep> {
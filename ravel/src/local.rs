@@ -1,6 +1,6 @@
 use std::marker::PhantomData;
 
-use crate::{with, Builder, Cx, CxRep, State, Token};
+use crate::{with, Builder, Cx, CxRep, MemSize, State, Token};
 
 /// A [`Builder`] created from [`with_local`].
 pub struct WithLocal<Init, F, S> {
@@ -46,6 +46,12 @@ where
     }
 }
 
+impl<T: MemSize, S: MemSize> MemSize for WithLocalState<T, S> {
+    fn heap_size(&self) -> usize {
+        self.value.heap_size() + self.inner.heap_size()
+    }
+}
+
 /// Creates a [`Builder`] which has access to a local state value.
 ///
 /// The `init` callback determines the initial value of the local state, and will
@@ -66,3 +72,133 @@ where
         phantom: PhantomData,
     }
 }
+
+/// A [`Builder`] created from [`with_local_mut`].
+pub struct WithLocalMut<Init, F, S> {
+    init: Init,
+    f: F,
+    phantom: PhantomData<S>,
+}
+
+impl<R: CxRep, T, Init, F, S> Builder<R> for WithLocalMut<Init, F, S>
+where
+    Init: FnOnce() -> T,
+    F: FnOnce(Cx<S, R>, &mut T) -> Token<S>,
+{
+    type State = WithLocalState<T, S>;
+
+    fn build(self, cx: R::BuildCx<'_>) -> Self::State {
+        let mut value = (self.init)();
+        let inner = with(|cx| (self.f)(cx, &mut value)).build(cx);
+        WithLocalState { value, inner }
+    }
+
+    fn rebuild(self, cx: R::RebuildCx<'_>, state: &mut Self::State) {
+        with(|cx| (self.f)(cx, &mut state.value)).rebuild(cx, &mut state.inner)
+    }
+}
+
+/// Like [`with_local`], but `f` can modify the local state while building.
+///
+/// This is meant for values derived while building, such as caches, which are
+/// kept for the next rebuild. Modifying the value doesn't schedule a rebuild
+/// by itself: changes which should be shown immediately belong in the model,
+/// or in an event handler's update of the local state.
+pub fn with_local_mut<T, Init, F, S, R: CxRep>(
+    init: Init,
+    f: F,
+) -> WithLocalMut<Init, F, S>
+where
+    Init: FnOnce() -> T,
+    F: FnOnce(Cx<S, R>, &mut T) -> Token<S>,
+{
+    WithLocalMut {
+        init,
+        f,
+        phantom: PhantomData,
+    }
+}
+
+/// A [`Builder`] created from [`with_local_keyed`].
+pub struct WithLocalKeyed<K, Init, F, S> {
+    key: K,
+    init: Init,
+    f: F,
+    phantom: PhantomData<S>,
+}
+
+impl<R: CxRep, K, T, Init, F, S> Builder<R> for WithLocalKeyed<K, Init, F, S>
+where
+    K: PartialEq,
+    Init: FnOnce() -> T,
+    F: FnOnce(Cx<S, R>, &T) -> Token<S>,
+{
+    type State = WithLocalKeyedState<K, T, S>;
+
+    fn build(self, cx: R::BuildCx<'_>) -> Self::State {
+        let value = (self.init)();
+        let inner = with(|cx| (self.f)(cx, &value)).build(cx);
+        WithLocalKeyedState {
+            key: self.key,
+            local: WithLocalState { value, inner },
+        }
+    }
+
+    fn rebuild(self, cx: R::RebuildCx<'_>, state: &mut Self::State) {
+        if self.key != state.key {
+            state.key = self.key;
+            state.local.value = (self.init)();
+        }
+
+        let local = &mut state.local;
+        with(|cx| (self.f)(cx, &local.value)).rebuild(cx, &mut local.inner)
+    }
+}
+
+/// The state of a [`WithLocalKeyed`].
+pub struct WithLocalKeyedState<K, T, S> {
+    key: K,
+    local: WithLocalState<T, S>,
+}
+
+impl<Output, K: 'static, T: 'static, S> State<Output>
+    for WithLocalKeyedState<K, T, S>
+where
+    WithLocalState<T, S>: State<Output>,
+{
+    fn run(&mut self, output: &mut Output) {
+        self.local.run(output)
+    }
+}
+
+impl<K: MemSize, T: MemSize, S: MemSize> MemSize
+    for WithLocalKeyedState<K, T, S>
+{
+    fn heap_size(&self) -> usize {
+        self.key.heap_size() + self.local.heap_size()
+    }
+}
+
+/// Like [`with_local`], but the local state is reinitialized with `init`
+/// whenever `key` changes, for example when the component switches to showing
+/// a different item.
+///
+/// Only the local state is reinitialized: the components built by `f` are
+/// rebuilt as usual, so any state of their own is kept.
+pub fn with_local_keyed<K, T, Init, F, S, R: CxRep>(
+    key: K,
+    init: Init,
+    f: F,
+) -> WithLocalKeyed<K, Init, F, S>
+where
+    K: PartialEq,
+    Init: FnOnce() -> T,
+    F: FnOnce(Cx<S, R>, &T) -> Token<S>,
+{
+    WithLocalKeyed {
+        key,
+        init,
+        f,
+        phantom: PhantomData,
+    }
+}
@@ -0,0 +1,219 @@
+use std::{
+    borrow::Cow,
+    cell::{Cell, RefCell},
+    collections::{BTreeMap, BTreeSet, HashMap, HashSet, VecDeque},
+    marker::PhantomData,
+    mem::{size_of, size_of_val},
+    rc::Rc,
+    sync::Arc,
+};
+
+/// An estimate of the memory used by a value, such as a component's state.
+///
+/// Implementations count the heap memory which a value owns, so that large
+/// retained states (for example, of long lists) can be found and tuned.
+/// They are estimates: allocator overhead is ignored, hash tables and trees
+/// are assumed to use one slot per entry beyond their length, and memory
+/// shared through [`Rc`] or [`Arc`] isn't counted, since it isn't owned by any
+/// one value. Closures are assumed to own no heap memory, since what they
+/// capture can't be inspected.
+pub trait MemSize {
+    /// The number of bytes owned by this value on the heap, not counting its
+    /// own size.
+    fn heap_size(&self) -> usize;
+
+    /// The number of bytes used by this value, including its own size.
+    fn mem_size(&self) -> usize {
+        size_of_val(self) + self.heap_size()
+    }
+}
+
+macro_rules! no_heap {
+    ($($t:ty),* $(,)?) => {
+        $(impl MemSize for $t {
+            fn heap_size(&self) -> usize {
+                0
+            }
+        })*
+    };
+}
+
+no_heap!(
+    (),
+    bool,
+    char,
+    u8,
+    u16,
+    u32,
+    u64,
+    u128,
+    usize,
+    i8,
+    i16,
+    i32,
+    i64,
+    i128,
+    isize,
+    f32,
+    f64,
+    str,
+);
+
+impl<T: ?Sized> MemSize for &T {
+    fn heap_size(&self) -> usize {
+        0
+    }
+}
+
+impl<T: ?Sized> MemSize for PhantomData<T> {
+    fn heap_size(&self) -> usize {
+        0
+    }
+}
+
+impl<T: ?Sized> MemSize for Rc<T> {
+    fn heap_size(&self) -> usize {
+        0
+    }
+}
+
+impl<T: ?Sized> MemSize for Arc<T> {
+    fn heap_size(&self) -> usize {
+        0
+    }
+}
+
+impl MemSize for String {
+    fn heap_size(&self) -> usize {
+        self.capacity()
+    }
+}
+
+impl<T: ?Sized + MemSize> MemSize for Box<T> {
+    fn heap_size(&self) -> usize {
+        (**self).mem_size()
+    }
+}
+
+impl<T: ?Sized + ToOwned> MemSize for Cow<'_, T>
+where
+    T::Owned: MemSize,
+{
+    fn heap_size(&self) -> usize {
+        match self {
+            Cow::Borrowed(_) => 0,
+            Cow::Owned(owned) => owned.heap_size(),
+        }
+    }
+}
+
+impl<T: MemSize> MemSize for Option<T> {
+    fn heap_size(&self) -> usize {
+        self.as_ref().map_or(0, T::heap_size)
+    }
+}
+
+impl<T: MemSize, E: MemSize> MemSize for Result<T, E> {
+    fn heap_size(&self) -> usize {
+        match self {
+            Ok(value) => value.heap_size(),
+            Err(error) => error.heap_size(),
+        }
+    }
+}
+
+impl<T: Copy + MemSize> MemSize for Cell<T> {
+    fn heap_size(&self) -> usize {
+        self.get().heap_size()
+    }
+}
+
+impl<T: MemSize> MemSize for RefCell<T> {
+    /// Zero if the value is mutably borrowed.
+    fn heap_size(&self) -> usize {
+        self.try_borrow().map_or(0, |value| value.heap_size())
+    }
+}
+
+impl<T: MemSize, const N: usize> MemSize for [T; N] {
+    fn heap_size(&self) -> usize {
+        self.iter().map(T::heap_size).sum()
+    }
+}
+
+impl<T: MemSize> MemSize for [T] {
+    fn heap_size(&self) -> usize {
+        self.iter().map(T::heap_size).sum()
+    }
+}
+
+impl<T: MemSize> MemSize for Vec<T> {
+    fn heap_size(&self) -> usize {
+        self.capacity() * size_of::<T>() + self.as_slice().heap_size()
+    }
+}
+
+impl<T: MemSize> MemSize for VecDeque<T> {
+    fn heap_size(&self) -> usize {
+        self.capacity() * size_of::<T>()
+            + self.iter().map(T::heap_size).sum::<usize>()
+    }
+}
+
+impl<K: MemSize, V: MemSize, H> MemSize for HashMap<K, V, H> {
+    fn heap_size(&self) -> usize {
+        self.capacity() * size_of::<(K, V)>()
+            + self
+                .iter()
+                .map(|(k, v)| k.heap_size() + v.heap_size())
+                .sum::<usize>()
+    }
+}
+
+impl<T: MemSize, H> MemSize for HashSet<T, H> {
+    fn heap_size(&self) -> usize {
+        self.capacity() * size_of::<T>()
+            + self.iter().map(T::heap_size).sum::<usize>()
+    }
+}
+
+impl<K: MemSize, V: MemSize> MemSize for BTreeMap<K, V> {
+    fn heap_size(&self) -> usize {
+        self.iter().map(|(k, v)| k.mem_size() + v.mem_size()).sum()
+    }
+}
+
+impl<T: MemSize> MemSize for BTreeSet<T> {
+    fn heap_size(&self) -> usize {
+        self.iter().map(T::mem_size).sum()
+    }
+}
+
+macro_rules! tuple_mem_size {
+    ($($a:ident),*) => {
+        #[allow(non_camel_case_types)]
+        impl<$($a: MemSize,)*> MemSize for ($($a,)*) {
+            fn heap_size(&self) -> usize {
+                let ($($a,)*) = self;
+                0 $(+ $a.heap_size())*
+            }
+        }
+    };
+}
+
+tuple_mem_size!(a);
+tuple_mem_size!(a, b);
+tuple_mem_size!(a, b, c);
+tuple_mem_size!(a, b, c, d);
+tuple_mem_size!(a, b, c, d, e);
+tuple_mem_size!(a, b, c, d, e, f);
+tuple_mem_size!(a, b, c, d, e, f, g);
+tuple_mem_size!(a, b, c, d, e, f, g, h);
+tuple_mem_size!(a, b, c, d, e, f, g, h, i);
+tuple_mem_size!(a, b, c, d, e, f, g, h, i, j);
+tuple_mem_size!(a, b, c, d, e, f, g, h, i, j, k);
+tuple_mem_size!(a, b, c, d, e, f, g, h, i, j, k, l);
+tuple_mem_size!(a, b, c, d, e, f, g, h, i, j, k, l, m);
+tuple_mem_size!(a, b, c, d, e, f, g, h, i, j, k, l, m, n);
+tuple_mem_size!(a, b, c, d, e, f, g, h, i, j, k, l, m, n, o);
+tuple_mem_size!(a, b, c, d, e, f, g, h, i, j, k, l, m, n, o, p);
@@ -0,0 +1,74 @@
+use std::marker::PhantomData;
+
+use crate::{with, Builder, Cx, CxRep, MemSize, State, Token};
+
+/// A [`Builder`] created from [`memo`].
+pub struct Memo<T, F, S> {
+    value: T,
+    f: F,
+    phantom: PhantomData<S>,
+}
+
+impl<R: CxRep, T: PartialEq, F, S> Builder<R> for Memo<T, F, S>
+where
+    F: FnOnce(Cx<S, R>, &T) -> Token<S>,
+{
+    type State = MemoState<T, S>;
+
+    fn build(self, cx: R::BuildCx<'_>) -> Self::State {
+        let inner = with(|cx| (self.f)(cx, &self.value)).build(cx);
+        MemoState {
+            value: self.value,
+            inner,
+        }
+    }
+
+    fn rebuild(self, cx: R::RebuildCx<'_>, state: &mut Self::State) {
+        if self.value == state.value {
+            return;
+        }
+
+        with(|cx| (self.f)(cx, &self.value)).rebuild(cx, &mut state.inner);
+        state.value = self.value;
+    }
+}
+
+/// The state of a [`Memo`].
+pub struct MemoState<T, S> {
+    value: T,
+    inner: S,
+}
+
+impl<T: 'static, S, Output> State<Output> for MemoState<T, S>
+where
+    S: State<Output>,
+{
+    fn run(&mut self, output: &mut Output) {
+        self.inner.run(output)
+    }
+}
+
+impl<T: MemSize, S: MemSize> MemSize for MemoState<T, S> {
+    fn heap_size(&self) -> usize {
+        self.value.heap_size() + self.inner.heap_size()
+    }
+}
+
+/// Creates a [`Builder`] which is only rebuilt when `value` changes.
+///
+/// On rebuild, if `value` is equal to the previous value, `f` is not called and
+/// the entire subtree is left as is. This means `f` should only depend on
+/// `value`: anything else it captures, including event handlers, keeps its
+/// value from the last time it was called.
+///
+/// Like [`with`], `f` must call [`Cx::build`] to return a [`Token`].
+pub fn memo<T: PartialEq, F, S, R: CxRep>(value: T, f: F) -> Memo<T, F, S>
+where
+    F: FnOnce(Cx<S, R>, &T) -> Token<S>,
+{
+    Memo {
+        value,
+        f,
+        phantom: PhantomData,
+    }
+}
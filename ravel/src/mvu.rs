@@ -0,0 +1,153 @@
+//! Model-view-update: components which send messages, rather than mutating
+//! the model directly.
+//!
+//! In this style, event handlers only describe what happened, by sending a
+//! message to [`Messages`]. A single `update` function then applies each
+//! message to the model, so all of the application's logic is in one place,
+//! and can be tested without building any components:
+//!
+//! ```ignore
+//! enum Msg {
+//!     Increment,
+//!     Reset,
+//! }
+//!
+//! fn update(model: &mut u32, msg: Msg) {
+//!     match msg {
+//!         Msg::Increment => *model += 1,
+//!         Msg::Reset => *model = 0,
+//!     }
+//! }
+//!
+//! fn view(model: &u32) -> View!(Messages<Msg>) {
+//!     el::button((
+//!         display(*model),
+//!         on_(Click, |messages: &mut Messages<Msg>| {
+//!             messages.send(Msg::Increment)
+//!         }),
+//!     ))
+//! }
+//!
+//! // At the root of the application:
+//! cx.build(mvu::dispatch(view(model), update))
+//! ```
+//!
+//! Components written against their own message type can be embedded in a
+//! larger one with [`map`].
+
+use crate::{adapt, Adapt, MemSize, State, Thunk, ThunkResult};
+
+/// The messages sent by event handlers during one run of a component.
+///
+/// This is the `Output` type of components in the model-view-update style.
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct Messages<Msg> {
+    queue: Vec<Msg>,
+}
+
+impl<Msg> Messages<Msg> {
+    /// An empty queue of messages.
+    pub fn new() -> Self {
+        Self { queue: Vec::new() }
+    }
+
+    /// Sends `msg`, to be applied after the messages sent before it.
+    pub fn send(&mut self, msg: Msg) {
+        self.queue.push(msg)
+    }
+
+    /// Whether no messages have been sent.
+    pub fn is_empty(&self) -> bool {
+        self.queue.is_empty()
+    }
+
+    /// Applies each message to `model` with `update`, in the order they were
+    /// sent, leaving the queue empty.
+    pub fn apply<Model>(
+        &mut self,
+        model: &mut Model,
+        mut update: impl FnMut(&mut Model, Msg),
+    ) {
+        for msg in self.queue.drain(..) {
+            update(model, msg)
+        }
+    }
+}
+
+impl<Msg> Default for Messages<Msg> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<Msg> IntoIterator for Messages<Msg> {
+    type Item = Msg;
+    type IntoIter = std::vec::IntoIter<Msg>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.queue.into_iter()
+    }
+}
+
+impl<Msg: MemSize> MemSize for Messages<Msg> {
+    fn heap_size(&self) -> usize {
+        self.queue.heap_size()
+    }
+}
+
+/// Adapts a component which sends messages of type `Msg` into one which
+/// handles its events by applying them to a `Model` with `update`.
+///
+/// This is usually used once, at the root of the application, so that its
+/// output is the model passed to the event loop.
+pub fn dispatch<B, U, S, Model, Msg>(
+    builder: B,
+    mut update: U,
+) -> Adapt<
+    B,
+    impl 'static + FnMut(Thunk<S>, &mut Model) -> ThunkResult<S>,
+    S,
+    Model,
+>
+where
+    U: 'static + FnMut(&mut Model, Msg),
+    S: State<Messages<Msg>>,
+    Msg: 'static,
+{
+    adapt(builder, move |thunk, model| {
+        let mut messages = Messages::new();
+        let result = thunk.run(&mut messages);
+        messages.apply(model, &mut update);
+        result
+    })
+}
+
+/// Adapts a component which sends messages of type `Msg` into one which sends
+/// messages of type `Parent`, converted with `f`.
+///
+/// This embeds a component with its own message type in a larger one, for
+/// example by wrapping them in a variant of the parent's message type.
+#[allow(clippy::type_complexity)]
+pub fn map<B, F, S, Msg, Parent>(
+    builder: B,
+    mut f: F,
+) -> Adapt<
+    B,
+    impl 'static + FnMut(Thunk<S>, &mut Messages<Parent>) -> ThunkResult<S>,
+    S,
+    Messages<Parent>,
+>
+where
+    F: 'static + FnMut(Msg) -> Parent,
+    S: State<Messages<Msg>>,
+    Msg: 'static,
+{
+    adapt(builder, move |thunk, parent: &mut Messages<Parent>| {
+        let mut messages = Messages::new();
+        let result = thunk.run(&mut messages);
+        for msg in messages {
+            parent.send(f(msg))
+        }
+        result
+    })
+}
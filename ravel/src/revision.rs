@@ -0,0 +1,105 @@
+use std::{cell::Cell, marker::PhantomData, rc::Rc};
+
+use crate::{with, Builder, Cx, CxRep, MemSize, State, Token};
+
+/// A version counter for part of the model, used with [`tracked`] to skip
+/// rebuilding the views which depend on it while it is unchanged.
+///
+/// Call [`Revision::bump`] whenever the data it covers changes. Clones share
+/// the same counter, so it can be bumped from anywhere with access to a clone,
+/// such as an event handler or a background task, without any need to compare
+/// the data itself.
+#[derive(Clone, Default, Debug)]
+pub struct Revision(Rc<Cell<u64>>);
+
+impl Revision {
+    /// Creates a new counter.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Marks the data covered by this revision as changed.
+    pub fn bump(&self) {
+        self.0.set(self.0.get().wrapping_add(1))
+    }
+
+    /// The current value of the counter.
+    pub fn get(&self) -> u64 {
+        self.0.get()
+    }
+}
+
+/// A [`Builder`] created from [`tracked`].
+pub struct Tracked<F, S> {
+    revision: u64,
+    f: F,
+    phantom: PhantomData<S>,
+}
+
+impl<R: CxRep, F, S> Builder<R> for Tracked<F, S>
+where
+    F: FnOnce(Cx<S, R>) -> Token<S>,
+{
+    type State = TrackedState<S>;
+
+    fn build(self, cx: R::BuildCx<'_>) -> Self::State {
+        TrackedState {
+            revision: self.revision,
+            inner: with(self.f).build(cx),
+        }
+    }
+
+    fn rebuild(self, cx: R::RebuildCx<'_>, state: &mut Self::State) {
+        if self.revision == state.revision {
+            return;
+        }
+
+        with(self.f).rebuild(cx, &mut state.inner);
+        state.revision = self.revision;
+    }
+}
+
+/// The state of a [`Tracked`].
+pub struct TrackedState<S> {
+    revision: u64,
+    inner: S,
+}
+
+impl<S, Output> State<Output> for TrackedState<S>
+where
+    S: State<Output>,
+{
+    fn run(&mut self, output: &mut Output) {
+        self.inner.run(output)
+    }
+}
+
+impl<S: MemSize> MemSize for TrackedState<S> {
+    fn heap_size(&self) -> usize {
+        self.inner.heap_size()
+    }
+}
+
+/// Creates a [`Builder`] which is only rebuilt when `revision` has been
+/// [bumped](Revision::bump) since it was last built.
+///
+/// This is like [`memo`](crate::memo), but only a counter is stored and
+/// compared, so it is cheap even when the data it covers is large. Since every
+/// wake rebuilds from the root, wrapping the larger parts of an application in
+/// `tracked` makes the cost of a frame proportional to what changed, rather
+/// than to the size of the whole view.
+///
+/// As with `memo`, `f` is not called while the revision is unchanged, so
+/// anything it captures, including event handlers, keeps its value from the
+/// last time it was called. Like [`with`], `f` must call [`Cx::build`] to
+/// return a [`Token`].
+pub fn tracked<F, S, R: CxRep>(revision: &Revision, f: F) -> Tracked<F, S>
+where
+    F: FnOnce(Cx<S, R>) -> Token<S>,
+{
+    Tracked {
+        revision: revision.get(),
+        f,
+        phantom: PhantomData,
+    }
+}
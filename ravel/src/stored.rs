@@ -0,0 +1,75 @@
+use std::marker::PhantomData;
+
+use crate::{with, Builder, Cx, CxRep, MemSize, State, Token};
+
+/// A [`Builder`] created from [`with_stored`].
+pub struct WithStored<Init, F, T, S> {
+    init: Init,
+    input: T,
+    phantom: PhantomData<(F, S)>,
+}
+
+impl<R: CxRep, Init, F, T, S> Builder<R> for WithStored<Init, F, T, S>
+where
+    Init: FnOnce() -> F,
+    F: FnMut(Cx<S, R>, T) -> Token<S>,
+{
+    type State = WithStoredState<F, S>;
+
+    fn build(self, cx: R::BuildCx<'_>) -> Self::State {
+        let mut f = (self.init)();
+        let inner = with(|cx| f(cx, self.input)).build(cx);
+        WithStoredState { f, inner }
+    }
+
+    fn rebuild(self, cx: R::RebuildCx<'_>, state: &mut Self::State) {
+        let f = &mut state.f;
+        with(|cx| f(cx, self.input)).rebuild(cx, &mut state.inner)
+    }
+}
+
+/// The state of a [`WithStored`].
+pub struct WithStoredState<F, S> {
+    f: F,
+    inner: S,
+}
+
+impl<F: 'static, S, Output> State<Output> for WithStoredState<F, S>
+where
+    S: State<Output>,
+{
+    fn run(&mut self, output: &mut Output) {
+        self.inner.run(output)
+    }
+}
+
+impl<F, S: MemSize> MemSize for WithStoredState<F, S> {
+    fn heap_size(&self) -> usize {
+        self.inner.heap_size()
+    }
+}
+
+/// Like [`with`], but the callback is created once and stored in the state,
+/// rather than recreated on every rebuild.
+///
+/// `init` is only run when the component is initially built. The resulting
+/// `f` is called with `input` on every build and rebuild, so it should take
+/// anything which changes between frames from `input`. This avoids cloning
+/// captured data (for example, an [`Rc`](std::rc::Rc) shared with event
+/// handlers) every frame, and lets `f` keep its own state across frames.
+///
+/// Like [`with`], `f` must call [`Cx::build`] to return a [`Token`].
+pub fn with_stored<Init, F, T, S, R: CxRep>(
+    init: Init,
+    input: T,
+) -> WithStored<Init, F, T, S>
+where
+    Init: FnOnce() -> F,
+    F: FnMut(Cx<S, R>, T) -> Token<S>,
+{
+    WithStored {
+        init,
+        input,
+        phantom: PhantomData,
+    }
+}